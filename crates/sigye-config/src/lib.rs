@@ -1,11 +1,25 @@
 //! Configuration management for the sigye clock application.
 
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use directories::ProjectDirs;
-use serde::{Deserialize, Serialize};
-use sigye_core::{AnimationSpeed, AnimationStyle, BackgroundStyle, ColorTheme, TimeFormat};
+use serde::{Deserialize, Deserializer, Serialize};
+use sigye_core::{
+    AnimationSpeed, AnimationStyle, BackgroundStyle, BlinkMode, ClockAlign, ColorDepth, ColorTheme,
+    DisplayMode, GradientAngle, ProgressScope, ReactiveGranularity, RefreshMode, ScrollDirection,
+    TerminalBackground, TextDirection, TimeFormat, TimePrecision, Warnings, write_atomic,
+};
+
+/// Widest `letter_spacing` allowed, in columns.
+const MAX_LETTER_SPACING: usize = 16;
+
+/// Longest pomodoro phase allowed, in minutes (a day).
+const MAX_POMODORO_MINUTES: u32 = 24 * 60;
+
+/// Longest matrix rain trail allowed, in rows.
+const MAX_MATRIX_TRAIL: usize = 100;
 
 /// Application configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,10 +32,103 @@ pub struct Config {
     #[serde(default)]
     pub color_theme: ColorTheme,
 
+    /// Themes to switch to at times of day, as `["HH:MM", theme]` pairs (empty disables).
+    #[serde(default)]
+    pub auto_theme_schedule: Vec<(String, ColorTheme)>,
+
+    /// Themes for calendar days, as `[rule, theme]` pairs where the rule is weekdays
+    /// (`"Sat,Sun"`) or a date range (`"12-01..12-31"`). The first match wins (empty disables).
+    #[serde(default)]
+    pub date_themes: Vec<(String, ColorTheme)>,
+
+    /// Time (`"HH:MM"`) night dimming starts; dimming is off unless both ends are set.
+    #[serde(default)]
+    pub dim_start: Option<String>,
+
+    /// Time (`"HH:MM"`) night dimming ends, which may be past midnight.
+    #[serde(default)]
+    pub dim_end: Option<String>,
+
+    /// Brightness during night dimming (0.0 - 1.0).
+    #[serde(default = "default_dim_level")]
+    pub dim_level: f32,
+
+    /// Direction of gradient themes in degrees (0, 45 or 90).
+    #[serde(default)]
+    pub gradient_angle: GradientAngle,
+
+    /// Draw the clock inside a bordered box instead of edge to edge.
+    #[serde(default)]
+    pub show_border: bool,
+
+    /// Title shown centered on the border.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// Label shown centered above the clock, e.g. to tell instances apart.
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// Whether the time is drawn as digits or an analog face.
+    #[serde(default)]
+    pub display_mode: DisplayMode,
+
+    /// Reading direction of the big font (`Auto` follows the font's print direction).
+    #[serde(default)]
+    pub text_direction: TextDirection,
+
+    /// Mirror the big font horizontally, like a reflection.
+    #[serde(default)]
+    pub mirror: bool,
+
+    /// Start time (`"YYYY-MM-DDTHH:MM:SS"`, local) the `Since` display mode counts up from.
+    #[serde(default)]
+    pub since: Option<String>,
+
+    /// Blank columns between adjacent glyphs of the big font.
+    #[serde(default)]
+    pub letter_spacing: usize,
+
+    /// Whether the clock is centered as a whole or on its first separator.
+    #[serde(default)]
+    pub align: ClockAlign,
+
+    /// Show the seconds in a smaller font below the hours and minutes, for narrow terminals.
+    #[serde(default)]
+    pub compact_layout: bool,
+
+    /// Drop font rows that are blank in every clock digit, for tighter centering.
+    #[serde(default)]
+    pub trim_font_rows: bool,
+
     /// Time format (12h or 24h).
     #[serde(default)]
     pub time_format: TimeFormat,
 
+    /// strftime format for the big clock (e.g. `"%H:%M"`), replacing `time_format`.
+    #[serde(default)]
+    pub time_format_str: Option<String>,
+
+    /// Sub-second digits shown after the seconds.
+    #[serde(default)]
+    pub precision: TimePrecision,
+
+    /// Character placed between hours, minutes and seconds.
+    #[serde(default = "default_separator")]
+    pub separator: char,
+
+    /// Whether 12-hour format shows the AM/PM suffix.
+    #[serde(default = "default_true")]
+    pub show_ampm: bool,
+
+    /// Whether 12-hour format zero-pads hours (`03:04:05`) for a stable width.
+    #[serde(default)]
+    pub pad_hours: bool,
+
+    /// Whether a leading zero on the hour renders as blank (` 9:04:05`).
+    #[serde(default)]
+    pub blank_leading_zero: bool,
+
     /// Animation style.
     #[serde(default)]
     pub animation_style: AnimationStyle,
@@ -30,67 +137,617 @@ pub struct Config {
     #[serde(default)]
     pub animation_speed: AnimationSpeed,
 
-    /// Whether colon blinks.
+    /// Whether to show ISO week and day of year under the date.
+    #[serde(default)]
+    pub show_extra_info: bool,
+
+    /// Whether to show a progress bar beneath the clock.
+    #[serde(default)]
+    pub show_progress: bool,
+
+    /// Time span the progress bar tracks.
+    #[serde(default)]
+    pub progress_scope: ProgressScope,
+
+    /// Whether to show the key help line at the bottom of the screen.
+    #[serde(default = "default_true")]
+    pub show_footer: bool,
+
+    /// Whether changes made with single keys (`t`, `c`, `a`, ...) are saved right away.
+    #[serde(default = "default_true")]
+    pub persist_quick_changes: bool,
+
+    /// Which parts of the time blink (also reads the old `colon_blink` boolean).
+    #[serde(
+        default,
+        alias = "colon_blink",
+        deserialize_with = "deserialize_blink_mode"
+    )]
+    pub blink_mode: BlinkMode,
+
+    /// Length of one colon blink cycle in milliseconds.
+    #[serde(default = "default_colon_blink_period_ms")]
+    pub colon_blink_period_ms: u64,
+
+    /// Fraction of each blink cycle the colon is shown (0.0 - 1.0).
+    #[serde(default = "default_colon_on_fraction")]
+    pub colon_on_fraction: f32,
+
+    /// Fade the blinking colon in and out smoothly instead of switching it on and off.
+    #[serde(default)]
+    pub colon_fade: bool,
+
+    /// Speed of the shifting animation's hue rotation (defaults to `animation_speed`).
+    #[serde(default)]
+    pub shift_speed: Option<AnimationSpeed>,
+
+    /// Whether hue-rotating animations apply to dynamic (rainbow/gradient) themes.
+    #[serde(default = "default_true")]
+    pub animate_dynamic_themes: bool,
+
+    /// Alarm times as `"HH:MM"`, optionally followed by weekdays (`"07:30 Mon-Fri"`).
+    #[serde(default)]
+    pub alarms: Vec<String>,
+
+    /// Whether a firing alarm rings the terminal bell.
+    #[serde(default)]
+    pub bell: bool,
+
+    /// Length of a pomodoro work phase in minutes.
+    #[serde(default = "default_pomodoro_work_minutes")]
+    pub pomodoro_work_minutes: u32,
+
+    /// Length of a pomodoro break phase in minutes.
+    #[serde(default = "default_pomodoro_break_minutes")]
+    pub pomodoro_break_minutes: u32,
+
+    /// Whether pomodoro phases switch the color theme (red for work, green for break).
+    #[serde(default)]
+    pub pomodoro_auto_theme: bool,
+
+    /// Strength of the reactive flash when the hour changes (0.0 - 1.0).
+    #[serde(default = "default_flash_hour")]
+    pub flash_hour: f32,
+
+    /// Strength of the reactive flash when the minute changes (0.0 - 1.0).
+    #[serde(default = "default_flash_minute")]
+    pub flash_minute: f32,
+
+    /// Strength of the reactive flash when the second changes (0.0 - 1.0).
+    #[serde(default = "default_flash_second")]
+    pub flash_second: f32,
+
+    /// Whether a handled key press also triggers the reactive flash.
     #[serde(default)]
-    pub colon_blink: bool,
+    pub flash_on_keypress: bool,
+
+    /// Color the reactive flash shifts the clock toward as RGB (brightens it when unset).
+    #[serde(default)]
+    pub flash_color: Option<[u8; 3]>,
+
+    /// Smallest time change that triggers the reactive flash.
+    #[serde(default)]
+    pub reactive_granularity: ReactiveGranularity,
+
+    /// Lowest brightness of the pulsing animation (0.0 - 1.0).
+    #[serde(default = "default_pulse_min_brightness")]
+    pub pulse_min_brightness: f32,
 
     /// Background animation style.
     #[serde(default)]
     pub background_style: BackgroundStyle,
+
+    /// Whether the clock glyphs cast a drop shadow down and to the right.
+    #[serde(default)]
+    pub glyph_shadow: bool,
+
+    /// Whether clock glyphs are lightened or darkened where the background behind
+    /// them would make them hard to read.
+    #[serde(default)]
+    pub contrast_boost: bool,
+
+    /// Whether the background leaves a clear margin behind the clock.
+    #[serde(default)]
+    pub safe_zone: bool,
+
+    /// Whether the second, minute and hour flashes also lighten the background.
+    #[serde(default)]
+    pub frame_flash: bool,
+
+    /// Target frame rate for redraws and animations.
+    #[serde(default = "default_fps")]
+    pub fps: u8,
+
+    /// Length of the crossfade between themes and fonts in milliseconds (0 switches instantly).
+    #[serde(default = "default_transition_ms")]
+    pub transition_ms: u64,
+
+    /// How often to redraw. `Minute` and `Manual` save power on e-ink displays and
+    /// metered connections, at the cost of seconds and animations.
+    #[serde(default)]
+    pub refresh_mode: RefreshMode,
+
+    /// Longest wait for input between frames in milliseconds (derived from `fps` when
+    /// unset). Lower values make keys feel snappier on slow links.
+    #[serde(default)]
+    pub input_poll_ms: Option<u64>,
+
+    /// Seconds without a keypress before themes, backgrounds and animations start
+    /// cycling like a screensaver (disabled when unset).
+    #[serde(default)]
+    pub idle_cycle_secs: Option<u64>,
+
+    /// Seed for background patterns (same seed gives the same look).
+    #[serde(default)]
+    pub background_seed: u64,
+
+    /// Fraction of starfield cells showing a star (0.0 - 1.0).
+    #[serde(default = "default_star_density")]
+    pub star_density: f32,
+
+    /// Glyphs the matrix rain picks from.
+    #[serde(default = "default_matrix_charset")]
+    pub matrix_charset: String,
+
+    /// Matrix rain trail color as RGB.
+    #[serde(default = "default_matrix_color")]
+    pub matrix_color: [u8; 3],
+
+    /// Shortest matrix rain trail in rows.
+    #[serde(default = "default_matrix_trail_min")]
+    pub matrix_trail_min: usize,
+
+    /// Longest matrix rain trail in rows.
+    #[serde(default = "default_matrix_trail_max")]
+    pub matrix_trail_max: usize,
+
+    /// How far the leading glyph of each trail is lightened toward white (0.0 - 1.0).
+    #[serde(default = "default_matrix_head_brightness")]
+    pub matrix_head_brightness: f32,
+
+    /// Direction the gradient background scrolls in.
+    #[serde(default)]
+    pub gradient_scroll: ScrollDirection,
+
+    /// Scroll the gradient background the opposite way.
+    #[serde(default)]
+    pub gradient_reverse: bool,
+
+    /// Brightness multiplier for background colors (0.0 hides the background).
+    #[serde(default = "default_background_brightness")]
+    pub background_brightness: f32,
+
+    /// Solid fill color behind everything as RGB (terminal default when unset).
+    #[serde(default)]
+    pub bg_color: Option<[u8; 3]>,
+
+    /// Whether to leave empty cells untouched, so a terminal background image shows
+    /// through. Overrides `bg_color` and `frame_flash`.
+    #[serde(default)]
+    pub transparent: bool,
+
+    /// Colors the terminal supports; RGB colors are mapped to the 16 ANSI colors
+    /// when it lacks truecolor.
+    #[serde(default)]
+    pub color_depth: ColorDepth,
+
+    /// Whether the terminal background is dark or light, for terminals that don't
+    /// answer the background color query.
+    #[serde(default)]
+    pub terminal_background: TerminalBackground,
+
+    /// Custom font directories, earlier ones taking precedence (also reads a single
+    /// `fonts_dir`). `~` and `$VAR` are expanded; empty uses the default fonts directory.
+    #[serde(
+        default,
+        alias = "fonts_dir",
+        deserialize_with = "deserialize_fonts_dirs"
+    )]
+    pub fonts_dirs: Vec<String>,
+
+    /// Whether custom fonts replace bundled fonts of the same name.
+    #[serde(default)]
+    pub allow_override: bool,
 }
 
 fn default_font() -> String {
     "Standard".to_string()
 }
 
+fn default_true() -> bool {
+    true
+}
+
+fn default_pomodoro_work_minutes() -> u32 {
+    25
+}
+
+fn default_pomodoro_break_minutes() -> u32 {
+    5
+}
+
+fn default_transition_ms() -> u64 {
+    300
+}
+
+fn default_colon_blink_period_ms() -> u64 {
+    1000
+}
+
+fn default_colon_on_fraction() -> f32 {
+    0.5
+}
+
+fn default_dim_level() -> f32 {
+    0.3
+}
+
+fn default_separator() -> char {
+    ':'
+}
+
+fn default_flash_hour() -> f32 {
+    1.0
+}
+
+fn default_flash_minute() -> f32 {
+    0.7
+}
+
+fn default_flash_second() -> f32 {
+    0.3
+}
+
+fn default_pulse_min_brightness() -> f32 {
+    0.3
+}
+
+fn default_fps() -> u8 {
+    20
+}
+
+fn default_star_density() -> f32 {
+    0.03
+}
+
+fn default_matrix_charset() -> String {
+    "アイウエオカキクケコサシスセソタチツテト0123456789".to_string()
+}
+
+fn default_matrix_color() -> [u8; 3] {
+    [0, 255, 0]
+}
+
+fn default_matrix_trail_min() -> usize {
+    4
+}
+
+fn default_matrix_trail_max() -> usize {
+    11
+}
+
+fn default_matrix_head_brightness() -> f32 {
+    0.78
+}
+
+fn default_background_brightness() -> f32 {
+    0.5
+}
+
+/// Accept either a `BlinkMode` or the legacy `colon_blink` boolean.
+fn deserialize_blink_mode<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BlinkMode, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BlinkModeCompat {
+        Legacy(bool),
+        Mode(BlinkMode),
+    }
+
+    Ok(match BlinkModeCompat::deserialize(deserializer)? {
+        BlinkModeCompat::Legacy(true) => BlinkMode::Colon,
+        BlinkModeCompat::Legacy(false) => BlinkMode::None,
+        BlinkModeCompat::Mode(mode) => mode,
+    })
+}
+
+/// Accept either a list of font directories or a single one.
+fn deserialize_fonts_dirs<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum FontsDirs {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    Ok(match FontsDirs::deserialize(deserializer)? {
+        FontsDirs::One(dir) => vec![dir],
+        FontsDirs::Many(dirs) => dirs,
+    })
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             font_name: default_font(),
             color_theme: ColorTheme::default(),
+            auto_theme_schedule: Vec::new(),
+            date_themes: Vec::new(),
+            dim_start: None,
+            dim_end: None,
+            dim_level: default_dim_level(),
+            gradient_angle: GradientAngle::default(),
+            show_border: false,
+            title: None,
+            label: None,
+            display_mode: DisplayMode::default(),
+            text_direction: TextDirection::default(),
+            mirror: false,
+            since: None,
+            letter_spacing: 0,
+            align: ClockAlign::default(),
+            compact_layout: false,
+            trim_font_rows: false,
             time_format: TimeFormat::default(),
+            time_format_str: None,
+            precision: TimePrecision::default(),
+            separator: default_separator(),
+            show_ampm: true,
+            pad_hours: false,
+            blank_leading_zero: false,
             animation_style: AnimationStyle::default(),
             animation_speed: AnimationSpeed::default(),
-            colon_blink: false,
+            show_extra_info: false,
+            show_progress: false,
+            progress_scope: ProgressScope::default(),
+            show_footer: true,
+            persist_quick_changes: true,
+            blink_mode: BlinkMode::default(),
+            colon_blink_period_ms: default_colon_blink_period_ms(),
+            colon_on_fraction: default_colon_on_fraction(),
+            colon_fade: false,
+            shift_speed: None,
+            animate_dynamic_themes: true,
+            alarms: Vec::new(),
+            bell: false,
+            pomodoro_work_minutes: default_pomodoro_work_minutes(),
+            pomodoro_break_minutes: default_pomodoro_break_minutes(),
+            pomodoro_auto_theme: false,
+            flash_hour: default_flash_hour(),
+            flash_minute: default_flash_minute(),
+            flash_second: default_flash_second(),
+            flash_on_keypress: false,
+            flash_color: None,
+            reactive_granularity: ReactiveGranularity::default(),
+            pulse_min_brightness: default_pulse_min_brightness(),
             background_style: BackgroundStyle::default(),
+            glyph_shadow: false,
+            contrast_boost: false,
+            safe_zone: false,
+            frame_flash: false,
+            fps: default_fps(),
+            transition_ms: default_transition_ms(),
+            refresh_mode: RefreshMode::default(),
+            input_poll_ms: None,
+            idle_cycle_secs: None,
+            background_seed: 0,
+            star_density: default_star_density(),
+            matrix_charset: default_matrix_charset(),
+            matrix_color: default_matrix_color(),
+            matrix_trail_min: default_matrix_trail_min(),
+            matrix_trail_max: default_matrix_trail_max(),
+            matrix_head_brightness: default_matrix_head_brightness(),
+            gradient_scroll: ScrollDirection::default(),
+            gradient_reverse: false,
+            background_brightness: default_background_brightness(),
+            bg_color: None,
+            transparent: false,
+            color_depth: ColorDepth::default(),
+            terminal_background: TerminalBackground::default(),
+            fonts_dirs: Vec::new(),
+            allow_override: false,
         }
     }
 }
 
 impl Config {
-    /// Load configuration from file, or return defaults if not found.
-    pub fn load() -> Self {
-        let config_path = Self::config_file_path();
-
-        if config_path.exists() {
-            match fs::read_to_string(&config_path) {
-                Ok(contents) => match toml::from_str(&contents) {
-                    Ok(config) => return config,
-                    Err(e) => {
-                        eprintln!("Warning: Failed to parse config file: {e}");
-                    }
-                },
+    /// Load configuration from file, or return defaults if not found. An unreadable
+    /// or malformed file also falls back to defaults, with a warning saying why.
+    pub fn load_result() -> (Self, Warnings) {
+        Self::load_from(&Self::config_file_path())
+    }
+
+    fn load_from(path: &Path) -> (Self, Warnings) {
+        if !path.exists() {
+            return (Self::default(), Warnings::new());
+        }
+
+        let warning = match fs::read_to_string(path) {
+            Ok(contents) => match toml::from_str(&contents) {
+                Ok(config) => return (config, Warnings::new()),
                 Err(e) => {
-                    eprintln!("Warning: Failed to read config file: {e}");
+                    // The full error draws the offending line; one line fits on screen
+                    let line = e
+                        .span()
+                        .map(|span| contents[..span.start].lines().count().max(1));
+                    match line {
+                        Some(line) => format!(
+                            "Failed to parse config file at line {line}: {}",
+                            e.message()
+                        ),
+                        None => format!("Failed to parse config file: {}", e.message()),
+                    }
                 }
-            }
-        }
+            },
+            Err(e) => format!("Failed to read config file: {e}"),
+        };
+        (Self::default(), vec![warning])
+    }
 
-        Self::default()
+    /// Clamp numeric fields to their valid ranges, returning a warning for each
+    /// value that had to be corrected or setting that can't take effect.
+    pub fn validate(&mut self) -> Warnings {
+        let mut warnings = Warnings::new();
+        clamp_field(&mut warnings, "fps", &mut self.fps, 1, 120);
+        clamp_field(&mut warnings, "dim_level", &mut self.dim_level, 0.0, 1.0);
+        clamp_field(
+            &mut warnings,
+            "letter_spacing",
+            &mut self.letter_spacing,
+            0,
+            MAX_LETTER_SPACING,
+        );
+        clamp_field(
+            &mut warnings,
+            "transition_ms",
+            &mut self.transition_ms,
+            0,
+            5_000,
+        );
+        clamp_field(
+            &mut warnings,
+            "colon_blink_period_ms",
+            &mut self.colon_blink_period_ms,
+            100,
+            60_000,
+        );
+        clamp_field(
+            &mut warnings,
+            "colon_on_fraction",
+            &mut self.colon_on_fraction,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "pomodoro_work_minutes",
+            &mut self.pomodoro_work_minutes,
+            1,
+            MAX_POMODORO_MINUTES,
+        );
+        clamp_field(
+            &mut warnings,
+            "pomodoro_break_minutes",
+            &mut self.pomodoro_break_minutes,
+            1,
+            MAX_POMODORO_MINUTES,
+        );
+        clamp_field(&mut warnings, "flash_hour", &mut self.flash_hour, 0.0, 1.0);
+        clamp_field(
+            &mut warnings,
+            "flash_minute",
+            &mut self.flash_minute,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "flash_second",
+            &mut self.flash_second,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "pulse_min_brightness",
+            &mut self.pulse_min_brightness,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "star_density",
+            &mut self.star_density,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "background_brightness",
+            &mut self.background_brightness,
+            0.0,
+            1.0,
+        );
+        clamp_field(
+            &mut warnings,
+            "matrix_trail_min",
+            &mut self.matrix_trail_min,
+            1,
+            MAX_MATRIX_TRAIL,
+        );
+        clamp_field(
+            &mut warnings,
+            "matrix_trail_max",
+            &mut self.matrix_trail_max,
+            1,
+            MAX_MATRIX_TRAIL,
+        );
+        if self.matrix_trail_min > self.matrix_trail_max {
+            warnings.push(format!(
+                "matrix_trail_min = {} is above matrix_trail_max = {}, using {} for both",
+                self.matrix_trail_min, self.matrix_trail_max, self.matrix_trail_max
+            ));
+            self.matrix_trail_min = self.matrix_trail_max;
+        }
+        clamp_field(
+            &mut warnings,
+            "matrix_head_brightness",
+            &mut self.matrix_head_brightness,
+            0.0,
+            1.0,
+        );
+        if let Some(poll_ms) = &mut self.input_poll_ms {
+            clamp_field(&mut warnings, "input_poll_ms", poll_ms, 1, 1000);
+        }
+        if !self.refresh_mode.is_real_time()
+            && (self.animation_style != AnimationStyle::None
+                || self.precision != TimePrecision::Seconds)
+        {
+            warnings.push(format!(
+                "refresh_mode = {:?} redraws too rarely for animations and seconds, so they are off",
+                self.refresh_mode
+            ));
+        }
+        warnings
     }
 
     /// Save configuration to file.
     pub fn save(&self) -> Result<(), ConfigError> {
-        let config_dir = Self::config_dir();
-        fs::create_dir_all(&config_dir).map_err(|e| ConfigError::Io(e.to_string()))?;
+        self.save_to(&Self::config_file_path())
+    }
+
+    /// Save configuration to a path, replacing it atomically so a failed write
+    /// never leaves a truncated file behind.
+    pub fn save_to(&self, path: &Path) -> Result<(), ConfigError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| ConfigError::Io(e.to_string()))?;
+        }
 
-        let config_path = Self::config_file_path();
         let contents =
             toml::to_string_pretty(self).map_err(|e| ConfigError::Serialize(e.to_string()))?;
 
-        fs::write(&config_path, contents).map_err(|e| ConfigError::Io(e.to_string()))?;
+        write_atomic(path, &contents).map_err(|e| ConfigError::Io(e.to_string()))
+    }
 
-        Ok(())
+    /// Get the time between redraws for the configured frame rate, raised when needed
+    /// to keep sub-second digits moving.
+    pub fn frame_interval(&self) -> Duration {
+        let fps = self.fps.max(self.precision.min_fps());
+        Duration::from_millis(1000 / u64::from(fps))
+    }
+
+    /// Get the input poll override, if any.
+    pub fn input_poll_interval(&self) -> Option<Duration> {
+        self.input_poll_ms.map(Duration::from_millis)
+    }
+
+    /// Get the idle time before auto-cycling starts, which is also the time between steps.
+    pub fn idle_cycle_interval(&self) -> Option<Duration> {
+        self.idle_cycle_secs
+            .map(|secs| Duration::from_secs(secs.max(1)))
     }
 
     /// Get the configuration directory path.
@@ -112,6 +769,74 @@ impl Config {
     pub fn fonts_dir() -> PathBuf {
         Self::config_dir().join("fonts")
     }
+
+    /// Get the configured custom font directories with `~` and variables expanded.
+    pub fn custom_fonts_dirs(&self) -> Vec<PathBuf> {
+        self.fonts_dirs.iter().map(|dir| expand_path(dir)).collect()
+    }
+
+    /// Get the parsed custom font cache path.
+    pub fn font_cache_path() -> PathBuf {
+        Self::config_dir().join("font-cache")
+    }
+}
+
+/// Clamp a config value into `min..=max`, noting the correction in `warnings`.
+/// Values that can't be compared (NaN) become `min`.
+fn clamp_field<T: PartialOrd + Copy + std::fmt::Display>(
+    warnings: &mut Warnings,
+    name: &str,
+    value: &mut T,
+    min: T,
+    max: T,
+) {
+    let clamped = if *value > max {
+        max
+    } else if *value >= min {
+        return;
+    } else {
+        min
+    };
+    warnings.push(format!("{name} = {value} is out of range, using {clamped}"));
+    *value = clamped;
+}
+
+/// Expand a leading `~` and `$VAR`/`${VAR}` references in a path.
+/// Unknown variables are left as written.
+pub fn expand_path(path: &str) -> PathBuf {
+    let mut expanded = String::new();
+    let mut rest = path;
+
+    if rest == "~" || rest.starts_with("~/") {
+        expanded.push_str(&dirs_fallback().to_string_lossy());
+        rest = &rest[1..];
+    }
+
+    while let Some(idx) = rest.find('$') {
+        expanded.push_str(&rest[..idx]);
+        let after = &rest[idx + 1..];
+        let braced = after.strip_prefix('{').and_then(|inner| {
+            inner
+                .find('}')
+                .map(|end| (&inner[..end], &inner[end + 1..]))
+        });
+        let (name, remainder) = braced.unwrap_or_else(|| {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], &after[end..])
+        });
+
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            // Unknown variable or lone `$`: keep the text as written
+            _ => expanded.push_str(&rest[idx..rest.len() - remainder.len()]),
+        }
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    PathBuf::from(expanded)
 }
 
 /// Fallback to get home directory if ProjectDirs fails.
@@ -138,3 +863,160 @@ impl std::fmt::Display for ConfigError {
 }
 
 impl std::error::Error for ConfigError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_colon_blink() {
+        let config: Config = toml::from_str("colon_blink = true").unwrap();
+        assert_eq!(config.blink_mode, BlinkMode::Colon);
+
+        let config: Config = toml::from_str("colon_blink = false").unwrap();
+        assert_eq!(config.blink_mode, BlinkMode::None);
+
+        let config: Config = toml::from_str("blink_mode = \"All\"").unwrap();
+        assert_eq!(config.blink_mode, BlinkMode::All);
+    }
+
+    #[test]
+    fn test_auto_theme_schedule_pairs() {
+        let config: Config = toml::from_str(
+            r#"auto_theme_schedule = [["06:00", "GradientWarm"], ["18:30", "GradientCool"]]"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.auto_theme_schedule,
+            vec![
+                ("06:00".to_string(), ColorTheme::GradientWarm),
+                ("18:30".to_string(), ColorTheme::GradientCool),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_gradient_angle_degrees() {
+        let config: Config = toml::from_str("gradient_angle = 45").unwrap();
+        assert_eq!(config.gradient_angle, GradientAngle::Diagonal);
+        assert!(
+            toml::to_string(&config)
+                .unwrap()
+                .contains("gradient_angle = 45")
+        );
+
+        assert!(toml::from_str::<Config>("gradient_angle = 30").is_err());
+    }
+
+    #[test]
+    fn test_fonts_dirs() {
+        let config: Config = toml::from_str(r#"fonts_dir = "/srv/fonts""#).unwrap();
+        assert_eq!(config.fonts_dirs, vec!["/srv/fonts".to_string()]);
+
+        let config: Config = toml::from_str(r#"fonts_dirs = ["/a", "/b"]"#).unwrap();
+        assert_eq!(config.fonts_dirs, vec!["/a".to_string(), "/b".to_string()]);
+
+        let home = dirs_fallback();
+        assert_eq!(expand_path("~/fonts"), home.join("fonts"));
+        assert_eq!(
+            expand_path("${HOME}/x/$HOME"),
+            PathBuf::from(format!("{0}/x/{0}", home.display()))
+        );
+        assert_eq!(
+            expand_path("$SIGYE_UNSET_VAR/$"),
+            PathBuf::from("$SIGYE_UNSET_VAR/$")
+        );
+    }
+
+    #[test]
+    fn test_malformed_config_warns() {
+        let dir = std::env::temp_dir().join(format!("sigye-config-load-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        let (config, warnings) = Config::load_from(&path);
+        assert_eq!(config.fps, Config::default().fps);
+        assert!(warnings.is_empty());
+
+        fs::write(&path, "fps = 30\ncolor_theme = \"Nope\"\n").unwrap();
+        let (config, warnings) = Config::load_from(&path);
+        assert_eq!(config.fps, Config::default().fps);
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("Failed to parse config file at line 2:"),
+            "{warnings:?}"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_save_leaves_no_temp_file() {
+        let dir = std::env::temp_dir().join(format!("sigye-config-save-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let file_names = || {
+            let mut names: Vec<_> = fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect();
+            names.sort();
+            names
+        };
+
+        Config::default().save_to(&path).unwrap();
+        assert_eq!(file_names(), ["config.toml"]);
+        fs::remove_file(&path).unwrap();
+
+        // A non-empty directory in the way of the rename makes the write fail
+        fs::create_dir(&path).unwrap();
+        fs::write(path.join("keep"), "").unwrap();
+        assert!(matches!(
+            Config::default().save_to(&path),
+            Err(ConfigError::Io(_))
+        ));
+        assert_eq!(file_names(), ["config.toml"]);
+        assert!(path.join("keep").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_validate_clamps_out_of_range() {
+        let mut config: Config = toml::from_str(
+            "fps = 0\ndim_level = 2.5\nstar_density = -1.0\nletter_spacing = 1000\ninput_poll_ms = 0",
+        )
+        .unwrap();
+        config.pulse_min_brightness = f32::NAN;
+
+        let warnings = config.validate();
+        assert_eq!(config.fps, 1);
+        assert_eq!(config.dim_level, 1.0);
+        assert_eq!(config.star_density, 0.0);
+        assert_eq!(config.letter_spacing, MAX_LETTER_SPACING);
+        assert_eq!(config.input_poll_ms, Some(1));
+        assert_eq!(config.pulse_min_brightness, 0.0);
+        assert_eq!(warnings.len(), 6);
+        assert!(warnings[0].starts_with("fps = 0"));
+
+        // Valid values are left alone
+        assert!(Config::default().validate().is_empty());
+
+        // Crossed trail bounds meet at the maximum
+        let mut config = Config {
+            matrix_trail_min: 20,
+            matrix_trail_max: 6,
+            ..Config::default()
+        };
+        assert_eq!(config.validate().len(), 1);
+        assert_eq!((config.matrix_trail_min, config.matrix_trail_max), (6, 6));
+
+        // Animations can't run when redrawing once a minute
+        let mut config = Config {
+            refresh_mode: RefreshMode::Minute,
+            animation_style: AnimationStyle::Pulsing,
+            ..Config::default()
+        };
+        assert_eq!(config.validate().len(), 1);
+    }
+}
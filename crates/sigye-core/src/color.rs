@@ -0,0 +1,156 @@
+//! RGB/HSL conversions shared by the clock and background renderers.
+
+/// Convert a color channel computed in floating point to `u8`, clamping to `[0, 255]`.
+/// NaN maps to 0.
+pub fn to_channel(value: f32) -> u8 {
+    if value.is_nan() {
+        0
+    } else {
+        value.clamp(0.0, 255.0) as u8
+    }
+}
+
+/// Mix two RGB colors, from `from` at `t = 0.0` to `to` at `t = 1.0`.
+pub fn mix_rgb(from: (u8, u8, u8), to: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |a: u8, b: u8| to_channel(f32::from(a) + (f32::from(b) - f32::from(a)) * t);
+    (
+        channel(from.0, to.0),
+        channel(from.1, to.1),
+        channel(from.2, to.2),
+    )
+}
+
+/// Convert RGB to HSL, with hue in degrees and saturation/lightness in 0.0 - 1.0.
+pub fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
+    let r = r as f32 / 255.0;
+    let g = g as f32 / 255.0;
+    let b = b as f32 / 255.0;
+
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+
+    if max == min {
+        return (0.0, 0.0, l);
+    }
+
+    let d = max - min;
+    let s = if l > 0.5 {
+        d / (2.0 - max - min)
+    } else {
+        d / (max + min)
+    };
+
+    let h = if max == r {
+        ((g - b) / d + if g < b { 6.0 } else { 0.0 }) * 60.0
+    } else if max == g {
+        ((b - r) / d + 2.0) * 60.0
+    } else {
+        ((r - g) / d + 4.0) * 60.0
+    };
+
+    (h, s, l)
+}
+
+/// Convert HSL to RGB. Hue wraps; saturation and lightness are clamped to 0.0 - 1.0.
+pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+
+    if s == 0.0 {
+        let v = to_channel(l * 255.0);
+        return (v, v, v);
+    }
+
+    let q = if l < 0.5 {
+        l * (1.0 + s)
+    } else {
+        l + s - l * s
+    };
+    let p = 2.0 * l - q;
+
+    let h = (h / 360.0).rem_euclid(1.0);
+
+    let r = hue_to_rgb(p, q, h + 1.0 / 3.0);
+    let g = hue_to_rgb(p, q, h);
+    let b = hue_to_rgb(p, q, h - 1.0 / 3.0);
+
+    (
+        to_channel(r * 255.0),
+        to_channel(g * 255.0),
+        to_channel(b * 255.0),
+    )
+}
+
+fn hue_to_rgb(p: f32, q: f32, mut t: f32) -> f32 {
+    if t < 0.0 {
+        t += 1.0;
+    }
+    if t > 1.0 {
+        t -= 1.0;
+    }
+
+    if t < 1.0 / 6.0 {
+        p + (q - p) * 6.0 * t
+    } else if t < 1.0 / 2.0 {
+        q
+    } else if t < 2.0 / 3.0 {
+        p + (q - p) * (2.0 / 3.0 - t) * 6.0
+    } else {
+        p
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (u8, u8, u8), expected: (u8, u8, u8)) {
+        let diff = |a: u8, b: u8| (i16::from(a) - i16::from(b)).abs();
+        assert!(
+            diff(actual.0, expected.0) <= 1
+                && diff(actual.1, expected.1) <= 1
+                && diff(actual.2, expected.2) <= 1,
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for &rgb in &[
+            (255, 0, 0),
+            (0, 255, 0),
+            (0, 0, 255),
+            (255, 127, 0),
+            (12, 200, 180),
+            (90, 30, 160),
+            (255, 255, 254),
+        ] {
+            let (h, s, l) = rgb_to_hsl(rgb.0, rgb.1, rgb.2);
+            assert_close(hsl_to_rgb(h, s, l), rgb);
+        }
+    }
+
+    #[test]
+    fn test_hsl_grays() {
+        assert_eq!(rgb_to_hsl(0, 0, 0), (0.0, 0.0, 0.0));
+        assert_eq!(rgb_to_hsl(255, 255, 255), (0.0, 0.0, 1.0));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 0.0), (0, 0, 0));
+        assert_eq!(hsl_to_rgb(0.0, 0.0, 1.0), (255, 255, 255));
+
+        let (h, s, l) = rgb_to_hsl(128, 128, 128);
+        assert_eq!((h, s), (0.0, 0.0));
+        assert_close(hsl_to_rgb(h, s, l), (128, 128, 128));
+    }
+
+    #[test]
+    fn test_out_of_range_clamps() {
+        assert_eq!(to_channel(300.0), 255);
+        assert_eq!(to_channel(-5.0), 0);
+        assert_eq!(to_channel(f32::NAN), 0);
+        assert_eq!(hsl_to_rgb(0.0, 2.0, 1.5), (255, 255, 255));
+        assert_close(hsl_to_rgb(480.0, 1.0, 0.5), hsl_to_rgb(120.0, 1.0, 0.5));
+        assert_close(hsl_to_rgb(-240.0, 1.0, 0.5), hsl_to_rgb(120.0, 1.0, 0.5));
+    }
+}
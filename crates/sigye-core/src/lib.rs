@@ -1,5 +1,13 @@
 //! Core types for the sigye clock application.
 
+mod color;
+mod file;
+
+pub use color::{hsl_to_rgb, mix_rgb, rgb_to_hsl, to_channel};
+pub use file::write_atomic;
+
+use std::str::FromStr;
+
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
@@ -21,6 +29,567 @@ impl TimeFormat {
     }
 }
 
+impl FromStr for TimeFormat {
+    type Err = String;
+
+    /// Parse `12h`/`24h` or the variant name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match normalize_name(s).as_str() {
+            "12" | "12h" | "twelvehour" => Ok(TimeFormat::TwelveHour),
+            "24" | "24h" | "twentyfourhour" => Ok(TimeFormat::TwentyFourHour),
+            _ => Err(format!("unknown time format '{s}' (expected 12h or 24h)")),
+        }
+    }
+}
+
+/// Lowercase a name and drop separators so `Gradient Warm`, `gradient-warm` and
+/// `GradientWarm` compare equal.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Problems found while loading, collected to show on screen since stderr is hidden
+/// behind the alternate screen.
+pub type Warnings = Vec<String>;
+
+/// Weekday names accepted in alarms and date rules, Monday first. Their first three
+/// letters are accepted too.
+const WEEKDAY_NAMES: &[&str] = &[
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// A daily alarm time, optionally limited to some weekdays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alarm {
+    /// Hour (0-23).
+    pub hour: u32,
+    /// Minute (0-59).
+    pub minute: u32,
+    /// Bit per weekday the alarm rings on, Monday = bit 0.
+    pub weekdays: u8,
+}
+
+impl Alarm {
+    /// Parse an alarm spec like `"07:30"`, `"07:30 Mon-Fri"` or `"09:00 Sat,Sun"`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut parts = spec.split_whitespace();
+        let minute_of_day = parse_time_of_day(parts.next()?)?;
+        let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+
+        let weekdays = match parts.next() {
+            None => 0b111_1111,
+            Some(days) => parse_weekdays(days)?,
+        };
+
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            hour,
+            minute,
+            weekdays,
+        })
+    }
+
+    /// Check if the alarm rings at the given time.
+    /// `weekday` counts days from Monday (0) to Sunday (6).
+    pub fn matches(&self, hour: u32, minute: u32, weekday: u32) -> bool {
+        self.hour == hour && self.minute == minute && self.weekdays & (1 << weekday) != 0
+    }
+}
+
+/// Parse an `"HH:MM"` time into minutes since midnight.
+pub fn parse_time_of_day(time: &str) -> Option<u32> {
+    let (hour, minute) = time.split_once(':')?;
+    let hour: u32 = hour.parse().ok().filter(|h| *h < 24)?;
+    let minute: u32 = minute.parse().ok().filter(|m| *m < 60)?;
+    Some(hour * 60 + minute)
+}
+
+/// Calendar days a date theme applies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateRule {
+    /// Bit per weekday, Monday = bit 0.
+    Weekdays(u8),
+    /// Inclusive `(month, day)` range, wrapping past New Year when `start` is later than `end`.
+    Dates { start: (u32, u32), end: (u32, u32) },
+}
+
+impl DateRule {
+    /// Parse a rule like `"Sat,Sun"`, `"Mon-Fri"`, `"12-01..12-31"` or `"12-20..01-06"`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim();
+        match spec.split_once("..") {
+            Some((start, end)) => Some(Self::Dates {
+                start: parse_month_day(start)?,
+                end: parse_month_day(end)?,
+            }),
+            None => parse_weekdays(spec).map(Self::Weekdays),
+        }
+    }
+
+    /// Check if the rule covers a date.
+    /// `weekday` counts days from Monday (0) to Sunday (6).
+    pub fn matches(&self, month: u32, day: u32, weekday: u32) -> bool {
+        match *self {
+            Self::Weekdays(mask) => mask & (1 << weekday) != 0,
+            Self::Dates { start, end } => {
+                let date = (month, day);
+                if start <= end {
+                    start <= date && date <= end
+                } else {
+                    date >= start || date <= end
+                }
+            }
+        }
+    }
+}
+
+/// Parse an `"MM-DD"` date into `(month, day)`.
+fn parse_month_day(date: &str) -> Option<(u32, u32)> {
+    let (month, day) = date.trim().split_once('-')?;
+    let month: u32 = month.parse().ok().filter(|m| (1..=12).contains(m))?;
+    let day: u32 = day.parse().ok().filter(|d| (1..=31).contains(d))?;
+    Some((month, day))
+}
+
+/// Parse weekdays like `"Mon-Fri"` or `"Sat,Sun"` into a bit per day, Monday = bit 0.
+fn parse_weekdays(days: &str) -> Option<u8> {
+    let mut mask = 0u8;
+    for day in days.split(',') {
+        let (start, end) = day.split_once('-').unwrap_or((day, day));
+        let start = weekday_index(start.trim())?;
+        let end = weekday_index(end.trim())?;
+        // Ranges may wrap around the week, e.g. Sat-Mon
+        let mut idx = start;
+        loop {
+            mask |= 1 << idx;
+            if idx == end {
+                break;
+            }
+            idx = (idx + 1) % 7;
+        }
+    }
+    Some(mask)
+}
+
+/// Color themes that take over at set times of day.
+#[derive(Debug, Clone, Default)]
+pub struct ThemeSchedule {
+    /// Minutes since midnight and the theme starting then, sorted by time.
+    entries: Vec<(u32, ColorTheme)>,
+}
+
+impl ThemeSchedule {
+    /// Create a schedule from `(minute_of_day, theme)` thresholds in any order.
+    pub fn new(mut entries: Vec<(u32, ColorTheme)>) -> Self {
+        entries.sort_by_key(|(minute, _)| *minute);
+        Self { entries }
+    }
+
+    /// Check if the schedule has no thresholds.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the index and theme of the threshold active at a minute of the day.
+    /// Before the first threshold, the last one from the previous day still applies.
+    pub fn active(&self, minute_of_day: u32) -> Option<(usize, ColorTheme)> {
+        let idx = self
+            .entries
+            .iter()
+            .rposition(|(minute, _)| *minute <= minute_of_day)
+            .or_else(|| self.entries.len().checked_sub(1))?;
+        Some((idx, self.entries[idx].1))
+    }
+}
+
+/// Get the Monday-based index of a weekday name or its three-letter abbreviation.
+fn weekday_index(name: &str) -> Option<u32> {
+    let name = name.to_ascii_lowercase();
+    WEEKDAY_NAMES
+        .iter()
+        .position(|d| name == *d || name == d[..3])
+        .map(|idx| idx as u32)
+}
+
+/// Which parts of the time display blink at 1Hz.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlinkMode {
+    #[default]
+    None,
+    Colon,
+    All,
+}
+
+/// All blink modes for cycling.
+const ALL_BLINK_MODES: &[BlinkMode] = &[BlinkMode::None, BlinkMode::Colon, BlinkMode::All];
+
+impl BlinkMode {
+    /// Cycle to the next blink mode.
+    pub fn next(&self) -> Self {
+        let current_idx = ALL_BLINK_MODES.iter().position(|m| m == self).unwrap_or(0);
+        let next_idx = (current_idx + 1) % ALL_BLINK_MODES.len();
+        ALL_BLINK_MODES[next_idx]
+    }
+
+    /// Cycle to the previous blink mode.
+    pub fn prev(&self) -> Self {
+        let current_idx = ALL_BLINK_MODES.iter().position(|m| m == self).unwrap_or(0);
+        let prev_idx = if current_idx == 0 {
+            ALL_BLINK_MODES.len() - 1
+        } else {
+            current_idx - 1
+        };
+        ALL_BLINK_MODES[prev_idx]
+    }
+
+    /// Get display name for the blink mode.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            BlinkMode::None => "Off",
+            BlinkMode::Colon => "Colon",
+            BlinkMode::All => "All",
+        }
+    }
+}
+
+/// How the time is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayMode {
+    #[default]
+    Digital,
+    Analog,
+    /// Time elapsed since the configured `since` timestamp.
+    Since,
+}
+
+/// All display modes for cycling.
+const ALL_DISPLAY_MODES: &[DisplayMode] = &[
+    DisplayMode::Digital,
+    DisplayMode::Analog,
+    DisplayMode::Since,
+];
+
+impl DisplayMode {
+    /// Cycle to the next display mode.
+    pub fn next(&self) -> Self {
+        let current_idx = ALL_DISPLAY_MODES
+            .iter()
+            .position(|m| m == self)
+            .unwrap_or(0);
+        let next_idx = (current_idx + 1) % ALL_DISPLAY_MODES.len();
+        ALL_DISPLAY_MODES[next_idx]
+    }
+
+    /// Cycle to the previous display mode.
+    pub fn prev(&self) -> Self {
+        let current_idx = ALL_DISPLAY_MODES
+            .iter()
+            .position(|m| m == self)
+            .unwrap_or(0);
+        let prev_idx = if current_idx == 0 {
+            ALL_DISPLAY_MODES.len() - 1
+        } else {
+            current_idx - 1
+        };
+        ALL_DISPLAY_MODES[prev_idx]
+    }
+
+    /// Get display name for the display mode.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            DisplayMode::Digital => "Digital",
+            DisplayMode::Analog => "Analog",
+            DisplayMode::Since => "Since",
+        }
+    }
+}
+
+/// Format an elapsed time as `HH:MM:SS`, with a leading day count (`d:HH:MM:SS`) once
+/// it reaches a day. Negative times show as zero.
+pub fn format_elapsed(secs: i64, separator: char) -> String {
+    let secs = secs.max(0);
+    let (days, hours) = (secs / 86_400, secs / 3600 % 24);
+    let (minutes, seconds) = (secs / 60 % 60, secs % 60);
+    let sep = separator;
+    if days > 0 {
+        format!("{days}{sep}{hours:02}{sep}{minutes:02}{sep}{seconds:02}")
+    } else {
+        format!("{hours:02}{sep}{minutes:02}{sep}{seconds:02}")
+    }
+}
+
+/// Sub-second digits shown after the seconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimePrecision {
+    #[default]
+    Seconds,
+    Tenths,
+    Hundredths,
+}
+
+impl TimePrecision {
+    /// Format the fraction of a second as `.c`/`.cc`, or an empty string for whole seconds.
+    /// Digits are truncated so the display never runs ahead of the clock.
+    pub fn fraction_str(self, nanos: u32) -> String {
+        // Leap seconds report more than a second of nanoseconds
+        let nanos = nanos.min(999_999_999);
+        match self {
+            TimePrecision::Seconds => String::new(),
+            TimePrecision::Tenths => format!(".{}", nanos / 100_000_000),
+            TimePrecision::Hundredths => format!(".{:02}", nanos / 10_000_000),
+        }
+    }
+
+    /// Get the lowest frame rate at which the fraction visibly changes every frame.
+    pub fn min_fps(self) -> u8 {
+        match self {
+            TimePrecision::Seconds => 1,
+            TimePrecision::Tenths => 10,
+            TimePrecision::Hundredths => 30,
+        }
+    }
+}
+
+/// How often the screen is redrawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RefreshMode {
+    /// Every frame, at the configured frame rate.
+    #[default]
+    RealTime,
+    /// Once a minute, without seconds or animations.
+    Minute,
+    /// Only after a key press, without seconds or animations.
+    Manual,
+}
+
+impl RefreshMode {
+    /// Check whether seconds and animations are drawn, which needs redrawing every frame.
+    pub fn is_real_time(self) -> bool {
+        self == RefreshMode::RealTime
+    }
+}
+
+/// Reading direction of the big clock font.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextDirection {
+    /// Always left to right.
+    #[default]
+    LeftToRight,
+    /// Always right to left.
+    RightToLeft,
+    /// Follow the font's own print direction.
+    Auto,
+}
+
+impl TextDirection {
+    /// Check if text should be laid out right to left for a font with the given direction.
+    pub fn is_rtl(self, font_rtl: bool) -> bool {
+        match self {
+            TextDirection::LeftToRight => false,
+            TextDirection::RightToLeft => true,
+            TextDirection::Auto => font_rtl,
+        }
+    }
+}
+
+/// How the big clock text is placed horizontally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClockAlign {
+    /// Center the whole text.
+    #[default]
+    Center,
+    /// Center on the first separator, so it stays put as the digits change width.
+    ColonCenter,
+}
+
+/// Direction dynamic gradients run in, configured as degrees from horizontal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "u16", into = "u16")]
+pub enum GradientAngle {
+    /// Left to right (0°).
+    #[default]
+    Horizontal,
+    /// Top-left to bottom-right (45°).
+    Diagonal,
+    /// Top to bottom (90°).
+    Vertical,
+}
+
+impl GradientAngle {
+    /// Get how far along the gradient a cell is (0.0 - 1.0).
+    pub fn progress(self, x: usize, y: usize, width: usize, height: usize) -> f32 {
+        let normalize = |pos: usize, len: usize| {
+            if len > 0 {
+                (pos as f32 / len as f32).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        };
+        let x_norm = normalize(x, width);
+        let y_norm = normalize(y, height);
+        match self {
+            GradientAngle::Horizontal => x_norm,
+            GradientAngle::Diagonal => (x_norm + y_norm) / 2.0,
+            GradientAngle::Vertical => y_norm,
+        }
+    }
+}
+
+impl TryFrom<u16> for GradientAngle {
+    type Error = String;
+
+    fn try_from(degrees: u16) -> Result<Self, Self::Error> {
+        match degrees {
+            0 => Ok(GradientAngle::Horizontal),
+            45 => Ok(GradientAngle::Diagonal),
+            90 => Ok(GradientAngle::Vertical),
+            other => Err(format!("gradient angle must be 0, 45 or 90, got {other}")),
+        }
+    }
+}
+
+impl From<GradientAngle> for u16 {
+    fn from(angle: GradientAngle) -> Self {
+        match angle {
+            GradientAngle::Horizontal => 0,
+            GradientAngle::Diagonal => 45,
+            GradientAngle::Vertical => 90,
+        }
+    }
+}
+
+/// Time span the progress bar tracks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProgressScope {
+    #[default]
+    Minute,
+    Hour,
+    Day,
+}
+
+/// All progress scopes for cycling.
+const ALL_PROGRESS_SCOPES: &[ProgressScope] = &[
+    ProgressScope::Minute,
+    ProgressScope::Hour,
+    ProgressScope::Day,
+];
+
+impl ProgressScope {
+    /// Cycle to the next progress scope.
+    pub fn next(&self) -> Self {
+        let current_idx = ALL_PROGRESS_SCOPES
+            .iter()
+            .position(|s| s == self)
+            .unwrap_or(0);
+        let next_idx = (current_idx + 1) % ALL_PROGRESS_SCOPES.len();
+        ALL_PROGRESS_SCOPES[next_idx]
+    }
+
+    /// Cycle to the previous progress scope.
+    pub fn prev(&self) -> Self {
+        let current_idx = ALL_PROGRESS_SCOPES
+            .iter()
+            .position(|s| s == self)
+            .unwrap_or(0);
+        let prev_idx = if current_idx == 0 {
+            ALL_PROGRESS_SCOPES.len() - 1
+        } else {
+            current_idx - 1
+        };
+        ALL_PROGRESS_SCOPES[prev_idx]
+    }
+
+    /// Get display name for the progress scope.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ProgressScope::Minute => "Minute",
+            ProgressScope::Hour => "Hour",
+            ProgressScope::Day => "Day",
+        }
+    }
+
+    /// Get how far through the scope a time of day is (0.0 to 1.0).
+    pub fn fraction(self, seconds_of_day: f64) -> f32 {
+        let span = match self {
+            ProgressScope::Minute => 60.0,
+            ProgressScope::Hour => 3600.0,
+            ProgressScope::Day => 86400.0,
+        };
+        ((seconds_of_day % span) / span).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Smallest time change that triggers the reactive flash.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReactiveGranularity {
+    /// Flash every second, minute and hour.
+    #[default]
+    Second,
+    /// Flash on minute and hour changes only.
+    Minute,
+    /// Flash on hour changes only.
+    Hour,
+}
+
+/// All reactive granularities for cycling.
+const ALL_REACTIVE_GRANULARITIES: &[ReactiveGranularity] = &[
+    ReactiveGranularity::Second,
+    ReactiveGranularity::Minute,
+    ReactiveGranularity::Hour,
+];
+
+impl ReactiveGranularity {
+    /// Cycle to the next granularity.
+    pub fn next(&self) -> Self {
+        let current_idx = ALL_REACTIVE_GRANULARITIES
+            .iter()
+            .position(|g| g == self)
+            .unwrap_or(0);
+        let next_idx = (current_idx + 1) % ALL_REACTIVE_GRANULARITIES.len();
+        ALL_REACTIVE_GRANULARITIES[next_idx]
+    }
+
+    /// Cycle to the previous granularity.
+    pub fn prev(&self) -> Self {
+        let current_idx = ALL_REACTIVE_GRANULARITIES
+            .iter()
+            .position(|g| g == self)
+            .unwrap_or(0);
+        let prev_idx = if current_idx == 0 {
+            ALL_REACTIVE_GRANULARITIES.len() - 1
+        } else {
+            current_idx - 1
+        };
+        ALL_REACTIVE_GRANULARITIES[prev_idx]
+    }
+
+    /// Get display name for the granularity.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            ReactiveGranularity::Second => "Second",
+            ReactiveGranularity::Minute => "Minute",
+            ReactiveGranularity::Hour => "Hour",
+        }
+    }
+
+    /// Check whether a change of the given unit should flash.
+    pub fn flashes_on(self, change: ReactiveGranularity) -> bool {
+        change as u8 >= self as u8
+    }
+}
+
 /// Animation style for color themes.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AnimationStyle {
@@ -30,6 +599,8 @@ pub enum AnimationStyle {
     Pulsing,
     Wave,
     Reactive,
+    Typewriter,
+    Wobble,
 }
 
 /// All animation styles for cycling.
@@ -39,9 +610,16 @@ const ALL_ANIMATION_STYLES: &[AnimationStyle] = &[
     AnimationStyle::Pulsing,
     AnimationStyle::Wave,
     AnimationStyle::Reactive,
+    AnimationStyle::Typewriter,
+    AnimationStyle::Wobble,
 ];
 
 impl AnimationStyle {
+    /// Get every animation style in cycling order.
+    pub fn all() -> &'static [Self] {
+        ALL_ANIMATION_STYLES
+    }
+
     /// Cycle to the next animation style.
     pub fn next(&self) -> Self {
         let current_idx = ALL_ANIMATION_STYLES
@@ -74,10 +652,24 @@ impl AnimationStyle {
             AnimationStyle::Pulsing => "Pulsing",
             AnimationStyle::Wave => "Wave",
             AnimationStyle::Reactive => "Reactive",
+            AnimationStyle::Typewriter => "Typewriter",
+            AnimationStyle::Wobble => "Wobble",
         }
     }
 }
 
+/// Direction the gradient background scrolls in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScrollDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Up and to the left.
+    #[default]
+    Diagonal,
+}
+
 /// Background animation style for the terminal.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BackgroundStyle {
@@ -86,6 +678,8 @@ pub enum BackgroundStyle {
     Starfield,
     MatrixRain,
     GradientWave,
+    Snow,
+    GameOfLife,
     // Reactive backgrounds that respond to system resource usage
     SystemPulse,
     ResourceWave,
@@ -99,6 +693,8 @@ const ALL_BACKGROUND_STYLES: &[BackgroundStyle] = &[
     BackgroundStyle::Starfield,
     BackgroundStyle::MatrixRain,
     BackgroundStyle::GradientWave,
+    BackgroundStyle::Snow,
+    BackgroundStyle::GameOfLife,
     BackgroundStyle::SystemPulse,
     BackgroundStyle::ResourceWave,
     BackgroundStyle::DataFlow,
@@ -137,6 +733,8 @@ impl BackgroundStyle {
             BackgroundStyle::Starfield => "Starfield",
             BackgroundStyle::MatrixRain => "Matrix",
             BackgroundStyle::GradientWave => "Gradient",
+            BackgroundStyle::Snow => "Snow",
+            BackgroundStyle::GameOfLife => "Life",
             BackgroundStyle::SystemPulse => "Sys Pulse",
             BackgroundStyle::ResourceWave => "Resource",
             BackgroundStyle::DataFlow => "Data Flow",
@@ -233,6 +831,15 @@ impl AnimationSpeed {
         }
     }
 
+    /// Get the wobble period in milliseconds.
+    pub fn wobble_period_ms(self) -> u64 {
+        match self {
+            AnimationSpeed::Slow => 2_400,
+            AnimationSpeed::Medium => 1_200,
+            AnimationSpeed::Fast => 600,
+        }
+    }
+
     /// Get the flash decay duration in milliseconds for reactive animation.
     pub fn flash_decay_ms(self) -> u64 {
         match self {
@@ -242,6 +849,16 @@ impl AnimationSpeed {
         }
     }
 
+    /// Get the typewriter reveal duration in milliseconds.
+    /// Kept under a second so each tick finishes typing before the next.
+    pub fn typewriter_duration_ms(self) -> u64 {
+        match self {
+            AnimationSpeed::Slow => 800,
+            AnimationSpeed::Medium => 500,
+            AnimationSpeed::Fast => 250,
+        }
+    }
+
     /// Get the star twinkle period in milliseconds.
     pub fn star_twinkle_period_ms(self) -> u64 {
         match self {
@@ -260,6 +877,24 @@ impl AnimationSpeed {
         }
     }
 
+    /// Get the snowfall speed multiplier.
+    pub fn snow_fall_speed(self) -> f32 {
+        match self {
+            AnimationSpeed::Slow => 0.2,
+            AnimationSpeed::Medium => 0.4,
+            AnimationSpeed::Fast => 0.8,
+        }
+    }
+
+    /// Get the Game of Life generation step period in milliseconds.
+    pub fn life_step_period_ms(self) -> u64 {
+        match self {
+            AnimationSpeed::Slow => 800,
+            AnimationSpeed::Medium => 400,
+            AnimationSpeed::Fast => 200,
+        }
+    }
+
     /// Get the gradient scroll period in milliseconds.
     pub fn gradient_scroll_period_ms(self) -> u64 {
         match self {
@@ -277,6 +912,7 @@ pub enum ColorTheme {
     Cyan,
     Green,
     White,
+    Gray,
     Magenta,
     Yellow,
     Red,
@@ -289,6 +925,10 @@ pub enum ColorTheme {
     GradientOcean,
     GradientNeon,
     GradientFire,
+    // Color-blind-safe themes
+    GradientDeuteranopia,
+    GradientTritanopia,
+    Mono,
 }
 
 /// All color themes in order for cycling.
@@ -300,6 +940,7 @@ const ALL_THEMES: &[ColorTheme] = &[
     ColorTheme::Red,
     ColorTheme::Blue,
     ColorTheme::White,
+    ColorTheme::Gray,
     ColorTheme::Rainbow,
     ColorTheme::RainbowVertical,
     ColorTheme::GradientWarm,
@@ -307,9 +948,22 @@ const ALL_THEMES: &[ColorTheme] = &[
     ColorTheme::GradientOcean,
     ColorTheme::GradientNeon,
     ColorTheme::GradientFire,
+    ColorTheme::GradientDeuteranopia,
+    ColorTheme::GradientTritanopia,
+    ColorTheme::Mono,
 ];
 
 impl ColorTheme {
+    /// Get every color theme in cycling order.
+    pub fn all() -> &'static [Self] {
+        ALL_THEMES
+    }
+
+    /// Get the theme at a position in cycling order, wrapping past the end.
+    pub fn nth(index: usize) -> Self {
+        ALL_THEMES[index % ALL_THEMES.len()]
+    }
+
     /// Cycle to the next color theme.
     pub fn next(&self) -> Self {
         let current_idx = ALL_THEMES.iter().position(|t| t == self).unwrap_or(0);
@@ -328,12 +982,22 @@ impl ColorTheme {
         ALL_THEMES[prev_idx]
     }
 
+    /// Get the theme to draw with on a dark or light terminal. White washes out on
+    /// light terminals, so it is swapped for gray there.
+    pub fn for_terminal(self, dark_terminal: bool) -> Self {
+        match self {
+            ColorTheme::White if !dark_terminal => ColorTheme::Gray,
+            _ => self,
+        }
+    }
+
     /// Convert theme to Ratatui Color (for static themes).
     pub fn color(self) -> Color {
         match self {
             ColorTheme::Cyan => Color::Cyan,
             ColorTheme::Green => Color::Green,
             ColorTheme::White => Color::White,
+            ColorTheme::Gray => Color::DarkGray,
             ColorTheme::Magenta => Color::Magenta,
             ColorTheme::Yellow => Color::Yellow,
             ColorTheme::Red => Color::Red,
@@ -344,6 +1008,9 @@ impl ColorTheme {
             }
             ColorTheme::GradientWarm | ColorTheme::GradientFire => Color::Red,
             ColorTheme::GradientCool | ColorTheme::GradientOcean => Color::Cyan,
+            ColorTheme::GradientDeuteranopia => Color::Blue,
+            ColorTheme::GradientTritanopia => Color::Red,
+            ColorTheme::Mono => Color::White,
         }
     }
 
@@ -358,6 +1025,9 @@ impl ColorTheme {
                 | ColorTheme::GradientOcean
                 | ColorTheme::GradientNeon
                 | ColorTheme::GradientFire
+                | ColorTheme::GradientDeuteranopia
+                | ColorTheme::GradientTritanopia
+                | ColorTheme::Mono
         )
     }
 
@@ -365,6 +1035,20 @@ impl ColorTheme {
     /// `x` is the horizontal position (column), `y` is the vertical position (row).
     /// `width` and `height` are the total dimensions for normalization.
     pub fn color_at_position(self, x: usize, y: usize, width: usize, height: usize) -> Color {
+        self.color_at_angle(x, y, width, height, GradientAngle::Horizontal)
+    }
+
+    /// Get color at a specific position for dynamic themes, with gradients running at `angle`.
+    /// `RainbowVertical` always runs top to bottom.
+    pub fn color_at_angle(
+        self,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        angle: GradientAngle,
+    ) -> Color {
+        let progress = angle.progress(x, y, width, height);
         match self {
             ColorTheme::Rainbow => {
                 let colors = [
@@ -376,11 +1060,7 @@ impl ColorTheme {
                     Color::Blue,
                     Color::Magenta,
                 ];
-                let idx = if width > 0 {
-                    (x * colors.len() / width.max(1)) % colors.len()
-                } else {
-                    0
-                };
+                let idx = ((progress * colors.len() as f32) as usize).min(colors.len() - 1);
                 colors[idx]
             }
             ColorTheme::RainbowVertical => {
@@ -402,100 +1082,103 @@ impl ColorTheme {
             }
             ColorTheme::GradientWarm => {
                 // Red -> Orange -> Yellow
-                let progress = if width > 0 {
-                    (x as f32) / (width.max(1) as f32)
-                } else {
-                    0.0
-                };
                 if progress < 0.5 {
                     // Red to Orange
-                    let g = (127.0 * (progress * 2.0)) as u8;
+                    let g = to_channel(127.0 * (progress * 2.0));
                     Color::Rgb(255, g, 0)
                 } else {
                     // Orange to Yellow
-                    let g = 127 + ((128.0 * ((progress - 0.5) * 2.0)) as u8);
+                    let g = to_channel(127.0 + 128.0 * ((progress - 0.5) * 2.0));
                     Color::Rgb(255, g, 0)
                 }
             }
             ColorTheme::GradientCool => {
                 // Blue -> Cyan -> Green
-                let progress = if width > 0 {
-                    (x as f32) / (width.max(1) as f32)
-                } else {
-                    0.0
-                };
                 if progress < 0.5 {
                     // Blue to Cyan
-                    let g = (255.0 * (progress * 2.0)) as u8;
+                    let g = to_channel(255.0 * (progress * 2.0));
                     Color::Rgb(0, g, 255)
                 } else {
                     // Cyan to Green
-                    let b = 255 - ((255.0 * ((progress - 0.5) * 2.0)) as u8);
+                    let b = to_channel(255.0 - 255.0 * ((progress - 0.5) * 2.0));
                     Color::Rgb(0, 255, b)
                 }
             }
             ColorTheme::GradientOcean => {
                 // Dark blue -> Cyan -> Teal
-                let progress = if width > 0 {
-                    (x as f32) / (width.max(1) as f32)
-                } else {
-                    0.0
-                };
                 if progress < 0.5 {
                     // Dark blue to Cyan
-                    let r = (100.0 * (progress * 2.0)) as u8;
-                    let g = (150.0 + 105.0 * (progress * 2.0)) as u8;
+                    let r = to_channel(100.0 * (progress * 2.0));
+                    let g = to_channel(150.0 + 105.0 * (progress * 2.0));
                     Color::Rgb(r, g, 255)
                 } else {
                     // Cyan to Teal
-                    let b = 255 - ((127.0 * ((progress - 0.5) * 2.0)) as u8);
+                    let b = to_channel(255.0 - 127.0 * ((progress - 0.5) * 2.0));
                     Color::Rgb(100, 255, b)
                 }
             }
             ColorTheme::GradientNeon => {
                 // Magenta -> Cyan (synthwave style)
-                let progress = if width > 0 {
-                    (x as f32) / (width.max(1) as f32)
-                } else {
-                    0.0
-                };
-                let r = 255 - ((255.0 * progress) as u8);
-                let g = (255.0 * progress) as u8;
+                let r = to_channel(255.0 - 255.0 * progress);
+                let g = to_channel(255.0 * progress);
                 let b = 255;
                 Color::Rgb(r, g, b)
             }
             ColorTheme::GradientFire => {
                 // Dark red -> Red -> Orange -> Yellow (fire effect)
-                let progress = if width > 0 {
-                    (x as f32) / (width.max(1) as f32)
-                } else {
-                    0.0
-                };
                 if progress < 0.33 {
                     // Dark red to Red
-                    let r = 128 + ((127.0 * (progress * 3.0)) as u8);
+                    let r = to_channel(128.0 + 127.0 * (progress * 3.0));
                     Color::Rgb(r, 0, 0)
                 } else if progress < 0.66 {
                     // Red to Orange
-                    let g = (165.0 * ((progress - 0.33) * 3.0)) as u8;
+                    let g = to_channel(165.0 * ((progress - 0.33) * 3.0));
                     Color::Rgb(255, g, 0)
                 } else {
                     // Orange to Yellow
-                    let g = 165 + ((90.0 * ((progress - 0.66) * 3.0)) as u8);
+                    let g = to_channel(165.0 + 90.0 * ((progress - 0.66) * 3.0));
                     Color::Rgb(255, g, 0)
                 }
             }
+            // The color-blind-safe gradients diverge from a light gray midpoint
+            // between Okabe-Ito pairs, so the ends differ in hue and lightness
+            ColorTheme::GradientDeuteranopia => {
+                // Blue -> Light gray -> Orange
+                ramp(&[(0, 114, 178), (200, 200, 200), (230, 159, 0)], progress)
+            }
+            ColorTheme::GradientTritanopia => {
+                // Vermillion -> Light gray -> Bluish green
+                ramp(&[(213, 94, 0), (200, 200, 200), (0, 158, 115)], progress)
+            }
+            ColorTheme::Mono => {
+                // Gray -> White
+                ramp(&[(120, 120, 120), (255, 255, 255)], progress)
+            }
             // Static themes just return their color
             _ => self.color(),
         }
     }
 
+    /// Get the lowest contrast ratio between this theme's colors and a background.
+    /// Dynamic themes are sampled across their gradient.
+    pub fn min_contrast_against(self, background: Color) -> f32 {
+        const SAMPLES: usize = 16;
+        let bg = color_to_rgb(background);
+        (0..SAMPLES)
+            .map(|i| {
+                let fg = color_to_rgb(self.color_at_position(i, i, SAMPLES, SAMPLES));
+                contrast_ratio(fg, bg)
+            })
+            .fold(f32::INFINITY, f32::min)
+    }
+
     /// Get display name for the theme.
     pub fn display_name(self) -> &'static str {
         match self {
             ColorTheme::Cyan => "Cyan",
             ColorTheme::Green => "Green",
             ColorTheme::White => "White",
+            ColorTheme::Gray => "Gray",
             ColorTheme::Magenta => "Magenta",
             ColorTheme::Yellow => "Yellow",
             ColorTheme::Red => "Red",
@@ -507,29 +1190,217 @@ impl ColorTheme {
             ColorTheme::GradientOcean => "Ocean",
             ColorTheme::GradientNeon => "Neon",
             ColorTheme::GradientFire => "Fire",
+            ColorTheme::GradientDeuteranopia => "Deuteranopia",
+            ColorTheme::GradientTritanopia => "Tritanopia",
+            ColorTheme::Mono => "Mono",
         }
     }
 }
 
+/// Get the color `progress` (0.0 - 1.0) of the way along evenly spaced RGB stops.
+fn ramp(stops: &[(u8, u8, u8)], progress: f32) -> Color {
+    let segments = stops.len().saturating_sub(1);
+    if segments == 0 {
+        let (r, g, b) = stops.first().copied().unwrap_or_default();
+        return Color::Rgb(r, g, b);
+    }
+    let position = progress.clamp(0.0, 1.0) * segments as f32;
+    let idx = (position as usize).min(segments - 1);
+    let (r, g, b) = mix_rgb(stops[idx], stops[idx + 1], position - idx as f32);
+    Color::Rgb(r, g, b)
+}
+
+impl FromStr for ColorTheme {
+    type Err = String;
+
+    /// Parse a theme by variant or display name, ignoring case and separators.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = normalize_name(s);
+        ALL_THEMES
+            .iter()
+            .copied()
+            .find(|theme| {
+                normalize_name(&format!("{theme:?}")) == name
+                    || normalize_name(theme.display_name()) == name
+            })
+            .ok_or_else(|| format!("unknown color theme '{s}'"))
+    }
+}
+
+/// Colors the terminal can show.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ColorDepth {
+    /// Detect from the environment.
+    #[default]
+    Auto,
+    /// 24-bit RGB.
+    TrueColor,
+    /// The 16 standard ANSI colors.
+    Ansi16,
+}
+
+/// Brightness of the terminal's background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TerminalBackground {
+    /// Ask the terminal, assuming dark if it doesn't answer.
+    #[default]
+    Auto,
+    /// Dark background.
+    Dark,
+    /// Light background.
+    Light,
+}
+
+impl TerminalBackground {
+    /// Classify a background color as dark or light.
+    pub fn from_rgb(rgb: (u8, u8, u8)) -> Self {
+        if relative_luminance(rgb) > 0.4 {
+            TerminalBackground::Light
+        } else {
+            TerminalBackground::Dark
+        }
+    }
+}
+
+/// The 16 ANSI colors with their usual (xterm) RGB values.
+const ANSI16_PALETTE: &[(Color, (u8, u8, u8))] = &[
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Map an RGB color to the nearest of the 16 ANSI colors; other colors pass through.
+///
+/// Green shades (like the matrix rain) get a dedicated ramp so a pale head still
+/// stands out as bright white over a two-step green trail.
+pub fn quantize_ansi16(color: Color) -> Color {
+    let Color::Rgb(r, g, b) = color else {
+        return color;
+    };
+
+    let greenish = g > r && g > b && r.abs_diff(b) < 48;
+    if greenish {
+        return match (r.min(b), g) {
+            (150.., _) => Color::White,
+            (_, 150..) => Color::LightGreen,
+            (_, 48..) => Color::Green,
+            _ => Color::Black,
+        };
+    }
+
+    let distance = |(pr, pg, pb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    ANSI16_PALETTE
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map_or(color, |(ansi, _)| *ansi)
+}
+
+/// Per-frame animation parameters shared by every glyph.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AnimationContext {
+    /// Animation style.
+    pub style: AnimationStyle,
+    /// Animation speed.
+    pub speed: AnimationSpeed,
+    /// Speed of the hue rotation for shifting animation.
+    pub shift_speed: AnimationSpeed,
+    /// Whether shifting may rotate hue (off keeps a dynamic theme's own colors).
+    pub shift_hue: bool,
+    /// Time since the animation started in milliseconds.
+    pub elapsed_ms: u64,
+    /// Current flash intensity for reactive animation (0.0 to 1.0).
+    pub flash_intensity: f32,
+    /// Color the reactive flash blends toward, or `None` to brighten instead.
+    pub flash_color: Option<(u8, u8, u8)>,
+    /// Lowest brightness the pulse dips to (0.0 to 1.0).
+    pub pulse_min_brightness: f32,
+}
+
+/// Common period of the animation cycles in milliseconds. It's a multiple of every
+/// built-in period, so reducing elapsed time modulo it doesn't change any animation.
+pub const ANIMATION_PERIOD_MS: u64 = 3_600_000;
+
+/// Reduce an elapsed time to its place within [`ANIMATION_PERIOD_MS`]. Keeps
+/// timing math precise in `f32` however long the clock has been running.
+pub fn animation_phase_ms(elapsed_ms: u64) -> u64 {
+    elapsed_ms % ANIMATION_PERIOD_MS
+}
+
+/// Get the time from one reduced elapsed time to a later one, across the wrap at
+/// [`ANIMATION_PERIOD_MS`].
+pub fn animation_phase_delta(earlier_ms: u64, later_ms: u64) -> u64 {
+    let earlier_ms = animation_phase_ms(earlier_ms);
+    (animation_phase_ms(later_ms) + ANIMATION_PERIOD_MS - earlier_ms) % ANIMATION_PERIOD_MS
+}
+
 /// Apply animation transformations to a color.
-pub fn apply_animation(
-    base_color: Color,
-    animation_style: AnimationStyle,
-    speed: AnimationSpeed,
-    elapsed_ms: u64,
-    x: usize,
-    width: usize,
-    flash_intensity: f32,
-) -> Color {
-    match animation_style {
+/// `x` is the glyph's column and `width` the total width, used by position-based styles.
+pub fn apply_animation(base_color: Color, ctx: &AnimationContext, x: usize, width: usize) -> Color {
+    let speed = ctx.speed;
+    let elapsed_ms = animation_phase_ms(ctx.elapsed_ms);
+    match ctx.style {
         AnimationStyle::None => base_color,
-        AnimationStyle::Shifting => apply_shifting(base_color, elapsed_ms, speed),
-        AnimationStyle::Pulsing => apply_pulsing(base_color, elapsed_ms, speed),
+        AnimationStyle::Shifting if ctx.shift_hue => {
+            apply_shifting(base_color, elapsed_ms, ctx.shift_speed)
+        }
+        AnimationStyle::Shifting => base_color,
+        AnimationStyle::Pulsing => {
+            apply_pulsing(base_color, elapsed_ms, speed, ctx.pulse_min_brightness)
+        }
         AnimationStyle::Wave => apply_wave(base_color, elapsed_ms, speed, x, width),
-        AnimationStyle::Reactive => apply_reactive(base_color, flash_intensity),
+        AnimationStyle::Reactive => {
+            apply_reactive(base_color, ctx.flash_intensity, ctx.flash_color)
+        }
+        // Typewriter hides glyphs rather than recoloring them, see `is_typewriter_revealed`
+        AnimationStyle::Typewriter => base_color,
+        AnimationStyle::Wobble => apply_wobble(base_color, elapsed_ms, speed, x),
+    }
+}
+
+/// Apply animation transformations to a whole row of colors.
+/// Each color's index is its column, and the row's length is the width for position-based styles.
+pub fn animate_line(base_colors: &[Color], ctx: &AnimationContext) -> Vec<Color> {
+    match ctx.style {
+        // Styles that never recolor skip the per-cell work entirely
+        AnimationStyle::None | AnimationStyle::Typewriter => base_colors.to_vec(),
+        AnimationStyle::Shifting if !ctx.shift_hue => base_colors.to_vec(),
+        _ => {
+            let width = base_colors.len();
+            base_colors
+                .iter()
+                .enumerate()
+                .map(|(x, &color)| apply_animation(color, ctx, x, width))
+                .collect()
+        }
     }
 }
 
+/// Check if a column is revealed by the typewriter animation.
+/// Columns from `reveal_from` to `width` type in left-to-right as `progress` goes 0.0 to 1.0.
+pub fn is_typewriter_revealed(x: usize, reveal_from: usize, width: usize, progress: f32) -> bool {
+    if x < reveal_from || progress >= 1.0 {
+        return true;
+    }
+    let span = width.saturating_sub(reveal_from).max(1) as f32;
+    ((x - reveal_from) as f32) < progress * span
+}
+
 /// Shift hue over time.
 fn apply_shifting(color: Color, elapsed_ms: u64, speed: AnimationSpeed) -> Color {
     let (r, g, b) = color_to_rgb(color);
@@ -544,19 +1415,29 @@ fn apply_shifting(color: Color, elapsed_ms: u64, speed: AnimationSpeed) -> Color
 }
 
 /// Pulse brightness using sine wave.
-fn apply_pulsing(color: Color, elapsed_ms: u64, speed: AnimationSpeed) -> Color {
+fn apply_pulsing(
+    color: Color,
+    elapsed_ms: u64,
+    speed: AnimationSpeed,
+    min_brightness: f32,
+) -> Color {
     let (r, g, b) = color_to_rgb(color);
 
     let period_ms = speed.pulse_period_ms();
     let phase = (elapsed_ms % period_ms) as f32 / period_ms as f32;
     let brightness = 0.5 + 0.5 * (phase * 2.0 * std::f32::consts::PI).sin();
 
-    // Apply brightness (minimum 30% to stay visible)
-    let factor = 0.3 + 0.7 * brightness;
+    // Apply brightness, never dipping below the floor so text stays visible
+    let floor = if min_brightness.is_nan() {
+        0.3
+    } else {
+        min_brightness.clamp(0.0, 1.0)
+    };
+    let factor = floor + (1.0 - floor) * brightness;
     Color::Rgb(
-        (r as f32 * factor) as u8,
-        (g as f32 * factor) as u8,
-        (b as f32 * factor) as u8,
+        to_channel(r as f32 * factor),
+        to_channel(g as f32 * factor),
+        to_channel(b as f32 * factor),
     )
 }
 
@@ -582,27 +1463,62 @@ fn apply_wave(
     let brightness = 0.6 + 0.4 * wave;
 
     Color::Rgb(
-        (r as f32 * brightness) as u8,
-        (g as f32 * brightness) as u8,
-        (b as f32 * brightness) as u8,
+        to_channel(r as f32 * brightness),
+        to_channel(g as f32 * brightness),
+        to_channel(b as f32 * brightness),
     )
 }
 
-/// Apply flash intensity for reactive animation.
-fn apply_reactive(color: Color, flash_intensity: f32) -> Color {
+/// Get a column's wobble (-1.0 to 1.0). Each column has its own phase, so
+/// neighbors move out of step like hand-drawn frames.
+fn wobble(x: usize, elapsed_ms: u64, speed: AnimationSpeed) -> f32 {
+    let period_ms = speed.wobble_period_ms();
+    let time_phase = (elapsed_ms % period_ms) as f32 / period_ms as f32;
+    let x_phase = ((x as u32).wrapping_mul(2_654_435_761) >> 16) as f32 / 65_536.0;
+    ((time_phase + x_phase) * std::f32::consts::TAU).sin()
+}
+
+/// Get the vertical draw offset of a column for the wobble animation: -1 (up),
+/// 0 or 1 (down), off-center only near the peaks so the text stays readable.
+pub fn wobble_offset(x: usize, elapsed_ms: u64, speed: AnimationSpeed) -> i8 {
+    match wobble(x, elapsed_ms, speed) {
+        w if w > 0.9 => -1,
+        w if w < -0.9 => 1,
+        _ => 0,
+    }
+}
+
+/// Vary a column's brightness slightly with its wobble.
+fn apply_wobble(color: Color, elapsed_ms: u64, speed: AnimationSpeed, x: usize) -> Color {
     let (r, g, b) = color_to_rgb(color);
+    let brightness = 0.85 + 0.15 * wobble(x, elapsed_ms, speed);
+    Color::Rgb(
+        to_channel(r as f32 * brightness),
+        to_channel(g as f32 * brightness),
+        to_channel(b as f32 * brightness),
+    )
+}
+
+/// Apply flash intensity for reactive animation, blending toward `flash_color`
+/// if set and brightening otherwise.
+fn apply_reactive(color: Color, flash_intensity: f32, flash_color: Option<(u8, u8, u8)>) -> Color {
+    let (r, g, b) = color_to_rgb(color);
+    if let Some(flash_color) = flash_color {
+        let (r, g, b) = mix_rgb((r, g, b), flash_color, flash_intensity);
+        return Color::Rgb(r, g, b);
+    }
 
     // Boost brightness based on flash intensity
     let factor = 1.0 + flash_intensity;
     Color::Rgb(
-        (r as f32 * factor).min(255.0) as u8,
-        (g as f32 * factor).min(255.0) as u8,
-        (b as f32 * factor).min(255.0) as u8,
+        to_channel(r as f32 * factor),
+        to_channel(g as f32 * factor),
+        to_channel(b as f32 * factor),
     )
 }
 
 /// Extract RGB values from a Color.
-fn color_to_rgb(color: Color) -> (u8, u8, u8) {
+pub fn color_to_rgb(color: Color) -> (u8, u8, u8) {
     match color {
         Color::Rgb(r, g, b) => (r, g, b),
         Color::Red => (255, 0, 0),
@@ -616,83 +1532,461 @@ fn color_to_rgb(color: Color) -> (u8, u8, u8) {
     }
 }
 
-/// Convert RGB to HSL.
-fn rgb_to_hsl(r: u8, g: u8, b: u8) -> (f32, f32, f32) {
-    let r = r as f32 / 255.0;
-    let g = g as f32 / 255.0;
-    let b = b as f32 / 255.0;
-
-    let max = r.max(g).max(b);
-    let min = r.min(g).min(b);
-    let l = (max + min) / 2.0;
-
-    if max == min {
-        return (0.0, 0.0, l);
+/// Scale a color's brightness by `factor` (0.0 - 1.0). The terminal default color is
+/// left alone, since its brightness is unknown.
+pub fn dim(color: Color, factor: f32) -> Color {
+    if color == Color::Reset {
+        return color;
     }
+    let (r, g, b) = color_to_rgb(color);
+    Color::Rgb(
+        to_channel(r as f32 * factor),
+        to_channel(g as f32 * factor),
+        to_channel(b as f32 * factor),
+    )
+}
 
-    let d = max - min;
-    let s = if l > 0.5 {
-        d / (2.0 - max - min)
+/// Check if a minute of the day falls in the window from `start` up to `end`, which
+/// wraps past midnight when `start` is later than `end`. Equal ends are an empty window.
+pub fn in_time_window(minute_of_day: u32, start: u32, end: u32) -> bool {
+    if start <= end {
+        (start..end).contains(&minute_of_day)
     } else {
-        d / (max + min)
+        minute_of_day >= start || minute_of_day < end
+    }
+}
+
+/// Get the relative luminance of an RGB color (0.0 - 1.0).
+fn relative_luminance((r, g, b): (u8, u8, u8)) -> f32 {
+    let channel = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
     };
+    0.2126 * channel(r) + 0.7152 * channel(g) + 0.0722 * channel(b)
+}
+
+/// Get the contrast ratio between two RGB colors (1.0 - 21.0).
+pub fn contrast_ratio(a: (u8, u8, u8), b: (u8, u8, u8)) -> f32 {
+    let la = relative_luminance(a);
+    let lb = relative_luminance(b);
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Contrast ratio the glyph contrast boost aims for (WCAG AA for normal text).
+pub const MIN_GLYPH_CONTRAST: f32 = 4.5;
 
-    let h = if max == r {
-        ((g - b) / d + if g < b { 6.0 } else { 0.0 }) * 60.0
-    } else if max == g {
-        ((b - r) / d + 2.0) * 60.0
+/// Lighten or darken a foreground color, keeping its hue, just enough to reach
+/// `min_ratio` contrast against a background. Colors with enough contrast are kept;
+/// when even white or black falls short, that extreme is returned.
+pub fn boost_contrast(fg: (u8, u8, u8), bg: (u8, u8, u8), min_ratio: f32) -> (u8, u8, u8) {
+    if contrast_ratio(fg, bg) >= min_ratio {
+        return fg;
+    }
+    let (h, s, l) = rgb_to_hsl(fg.0, fg.1, fg.2);
+    // Head for whichever end of the lightness scale stands out more
+    let target = if contrast_ratio((255, 255, 255), bg) >= contrast_ratio((0, 0, 0), bg) {
+        1.0
     } else {
-        ((r - g) / d + 4.0) * 60.0
+        0.0
     };
+    let contrasts = |l: f32| contrast_ratio(hsl_to_rgb(h, s, l), bg) >= min_ratio;
+    if !contrasts(target) {
+        return hsl_to_rgb(h, s, target);
+    }
+    // Binary search for the smallest lightness change that is enough
+    let (mut near, mut far) = (l, target);
+    for _ in 0..16 {
+        let mid = (near + far) / 2.0;
+        if contrasts(mid) {
+            far = mid;
+        } else {
+            near = mid;
+        }
+    }
+    hsl_to_rgb(h, s, far)
+}
 
-    (h, s, l)
+/// Check if colon should be visible in the blink cycle.
+/// Returns true during the "on" phase: the first `on_fraction` of each `period_ms`.
+pub fn is_colon_visible(elapsed_ms: u64, period_ms: u64, on_fraction: f32) -> bool {
+    let period_ms = period_ms.max(1);
+    let phase = (elapsed_ms % period_ms) as f32 / period_ms as f32;
+    phase < on_fraction.clamp(0.0, 1.0)
+}
+
+/// Get the colon brightness for a smooth blink (0.0 - 1.0): full at the start of each
+/// period, fading out to nothing halfway through and back in by the end.
+pub fn colon_fade_intensity(elapsed_ms: u64, period_ms: u64) -> f32 {
+    let period_ms = period_ms.max(1);
+    let phase = (elapsed_ms % period_ms) as f32 / period_ms as f32;
+    0.5 + 0.5 * (phase * std::f32::consts::TAU).cos()
 }
 
-/// Convert HSL to RGB.
-fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (u8, u8, u8) {
-    if s == 0.0 {
-        let v = (l * 255.0) as u8;
-        return (v, v, v);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_theme_and_format() {
+        assert_eq!("rainbow".parse(), Ok(ColorTheme::Rainbow));
+        assert_eq!("Gradient-Warm".parse(), Ok(ColorTheme::GradientWarm));
+        assert_eq!("rainbow v".parse(), Ok(ColorTheme::RainbowVertical));
+        assert!("plaid".parse::<ColorTheme>().is_err());
+
+        assert_eq!("12h".parse(), Ok(TimeFormat::TwelveHour));
+        assert_eq!("TwentyFourHour".parse(), Ok(TimeFormat::TwentyFourHour));
+        assert!("13h".parse::<TimeFormat>().is_err());
     }
 
-    let q = if l < 0.5 {
-        l * (1.0 + s)
-    } else {
-        l + s - l * s
-    };
-    let p = 2.0 * l - q;
+    #[test]
+    fn test_colon_duty_cycle() {
+        // Default: on for the first half of each second
+        assert!(is_colon_visible(0, 1000, 0.5));
+        assert!(is_colon_visible(499, 1000, 0.5));
+        assert!(!is_colon_visible(500, 1000, 0.5));
+        assert!(is_colon_visible(1200, 1000, 0.5));
 
-    let h = h / 360.0;
+        // Snappy 400ms blink with a short off phase
+        assert!(is_colon_visible(250, 400, 0.75));
+        assert!(!is_colon_visible(300, 400, 0.75));
+        assert!(is_colon_visible(400, 400, 0.75));
 
-    let r = hue_to_rgb(p, q, h + 1.0 / 3.0);
-    let g = hue_to_rgb(p, q, h);
-    let b = hue_to_rgb(p, q, h - 1.0 / 3.0);
+        // Degenerate settings don't panic
+        assert!(!is_colon_visible(10, 0, 0.0));
+        assert!(is_colon_visible(10, 1000, 2.0));
+    }
 
-    ((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
+    #[test]
+    fn test_time_precision_fraction() {
+        assert_eq!(TimePrecision::Seconds.fraction_str(987_654_321), "");
+        assert_eq!(TimePrecision::Tenths.fraction_str(987_654_321), ".9");
+        assert_eq!(TimePrecision::Hundredths.fraction_str(987_654_321), ".98");
+        assert_eq!(TimePrecision::Hundredths.fraction_str(50_000_000), ".05");
+        assert_eq!(TimePrecision::Hundredths.fraction_str(1_500_000_000), ".99");
+    }
 
-fn hue_to_rgb(p: f32, q: f32, mut t: f32) -> f32 {
-    if t < 0.0 {
-        t += 1.0;
+    #[test]
+    fn test_format_elapsed() {
+        assert_eq!(format_elapsed(3_725, ':'), "01:02:05");
+        assert_eq!(format_elapsed(86_400 * 12 + 59, ':'), "12:00:00:59");
+        assert_eq!(format_elapsed(-5, ':'), "00:00:00");
+        assert_eq!(DisplayMode::Since.next(), DisplayMode::Digital);
     }
-    if t > 1.0 {
-        t -= 1.0;
+
+    #[test]
+    fn test_in_time_window() {
+        // 22:00 - 07:00 crosses midnight
+        let (start, end) = (22 * 60, 7 * 60);
+        assert!(in_time_window(23 * 60, start, end));
+        assert!(in_time_window(0, start, end));
+        assert!(!in_time_window(7 * 60, start, end));
+        assert!(!in_time_window(12 * 60, start, end));
+        assert!(in_time_window(12 * 60, 9 * 60, 17 * 60));
+        assert!(!in_time_window(12 * 60, 9 * 60, 9 * 60));
     }
 
-    if t < 1.0 / 6.0 {
-        p + (q - p) * 6.0 * t
-    } else if t < 1.0 / 2.0 {
-        q
-    } else if t < 2.0 / 3.0 {
-        p + (q - p) * (2.0 / 3.0 - t) * 6.0
-    } else {
-        p
+    #[test]
+    fn test_quantize_ansi16_matrix_ramp() {
+        // Default matrix head and trail
+        assert_eq!(quantize_ansi16(Color::Rgb(200, 255, 200)), Color::White);
+        assert_eq!(quantize_ansi16(Color::Rgb(0, 200, 0)), Color::LightGreen);
+        assert_eq!(quantize_ansi16(Color::Rgb(0, 80, 0)), Color::Green);
+        // Everything else takes the nearest palette color
+        assert_eq!(quantize_ansi16(Color::Rgb(250, 10, 5)), Color::LightRed);
+        assert_eq!(quantize_ansi16(Color::Rgb(10, 10, 20)), Color::Black);
+        assert_eq!(quantize_ansi16(Color::Cyan), Color::Cyan);
     }
-}
 
-/// Check if colon should be visible in the blink cycle.
-/// Returns true during the "on" phase (first 500ms of each second).
-pub fn is_colon_visible(elapsed_ms: u64) -> bool {
-    let phase = (elapsed_ms % 1000) as f32 / 1000.0;
-    phase < 0.5
+    #[test]
+    fn test_terminal_background() {
+        assert_eq!(
+            TerminalBackground::from_rgb((253, 246, 227)),
+            TerminalBackground::Light
+        );
+        assert_eq!(
+            TerminalBackground::from_rgb((40, 42, 54)),
+            TerminalBackground::Dark
+        );
+        assert_eq!(ColorTheme::White.for_terminal(false), ColorTheme::Gray);
+        assert_eq!(ColorTheme::White.for_terminal(true), ColorTheme::White);
+    }
+
+    #[test]
+    fn test_animation_phase_survives_long_uptimes() {
+        let color = Color::Rgb(200, 80, 40);
+        // Thirty days in, at the same place in the cycle as 1234 ms
+        let huge = 30 * 24 * ANIMATION_PERIOD_MS + 1234;
+        assert_eq!(animation_phase_ms(huge), 1234);
+        assert_eq!(animation_phase_delta(ANIMATION_PERIOD_MS - 100, 50), 150);
+        assert_eq!(animation_phase_delta(100, 250), 150);
+        assert_eq!(animation_phase_delta(250, 250), 0);
+        for speed in [
+            AnimationSpeed::Slow,
+            AnimationSpeed::Medium,
+            AnimationSpeed::Fast,
+        ] {
+            assert_eq!(
+                apply_shifting(color, animation_phase_ms(huge), speed),
+                apply_shifting(color, 1234, speed)
+            );
+            assert_eq!(
+                apply_wave(color, animation_phase_ms(huge), speed, 3, 10),
+                apply_wave(color, 1234, speed, 3, 10)
+            );
+            // Every built-in cycle fits the common period exactly, so wrapping is seamless
+            for period in [
+                speed.shift_cycle_ms(),
+                speed.pulse_period_ms(),
+                speed.wave_period_ms(),
+                speed.wobble_period_ms(),
+                speed.star_twinkle_period_ms(),
+                speed.life_step_period_ms(),
+                speed.gradient_scroll_period_ms(),
+            ] {
+                assert_eq!(ANIMATION_PERIOD_MS % period, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_wobble_offset() {
+        let offsets: Vec<i8> = (0..200)
+            .map(|t| wobble_offset(3, t * 10, AnimationSpeed::Medium))
+            .collect();
+        assert!(offsets.iter().all(|offset| (-1..=1).contains(offset)));
+        assert!(offsets.contains(&-1) && offsets.contains(&0) && offsets.contains(&1));
+
+        // Columns are out of step with each other
+        assert!((0..20).any(|x| {
+            wobble_offset(x, 0, AnimationSpeed::Medium)
+                != wobble_offset(x + 1, 0, AnimationSpeed::Medium)
+        }));
+    }
+
+    #[test]
+    fn test_dim() {
+        assert_eq!(dim(Color::Rgb(200, 100, 50), 0.5), Color::Rgb(100, 50, 25));
+        assert_eq!(dim(Color::Cyan, 0.5), Color::Rgb(0, 127, 127));
+        assert_eq!(dim(Color::Reset, 0.5), Color::Reset);
+    }
+
+    #[test]
+    fn test_colon_fade_intensity() {
+        assert!((colon_fade_intensity(0, 1000) - 1.0).abs() < 1e-6);
+        assert!(colon_fade_intensity(500, 1000) < 1e-6);
+        assert!((colon_fade_intensity(250, 1000) - 0.5).abs() < 1e-3);
+        assert!((colon_fade_intensity(1000, 1000) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_parse_alarm() {
+        let alarm = Alarm::parse("07:30").unwrap();
+        assert_eq!((alarm.hour, alarm.minute), (7, 30));
+        assert!(alarm.matches(7, 30, 6));
+
+        let weekdays = Alarm::parse("07:30 Mon-Fri").unwrap();
+        assert!(weekdays.matches(7, 30, 0));
+        assert!(weekdays.matches(7, 30, 4));
+        assert!(!weekdays.matches(7, 30, 5));
+
+        let weekend = Alarm::parse("09:00 Sat,Sun").unwrap();
+        assert_eq!(weekend.weekdays, 0b110_0000);
+
+        assert!(Alarm::parse("25:00").is_none());
+        assert!(Alarm::parse("07:30 Someday").is_none());
+    }
+
+    #[test]
+    fn test_color_blind_themes_cycle() {
+        for theme in [
+            ColorTheme::GradientDeuteranopia,
+            ColorTheme::GradientTritanopia,
+            ColorTheme::Mono,
+        ] {
+            assert!(ColorTheme::all().contains(&theme));
+            assert_eq!(theme.next().prev(), theme);
+            assert_eq!(theme.prev().next(), theme);
+            assert_eq!(theme.display_name().parse::<ColorTheme>(), Ok(theme));
+            assert!(theme.is_dynamic());
+        }
+        assert_eq!(ColorTheme::Mono.next(), ColorTheme::Cyan);
+
+        // Deuteranopia runs from blue to orange
+        let ends = |theme: ColorTheme| {
+            (
+                theme.color_at_position(0, 0, 10, 1),
+                theme.color_at_position(10, 0, 10, 1),
+            )
+        };
+        assert_eq!(
+            ends(ColorTheme::GradientDeuteranopia),
+            (Color::Rgb(0, 114, 178), Color::Rgb(230, 159, 0))
+        );
+    }
+
+    #[test]
+    fn test_gradients_stay_in_range_at_edges() {
+        // Each gradient ramps one way; its score should never step backwards
+        let score = |theme: ColorTheme, (r, g, b): (u8, u8, u8)| -> i32 {
+            let (r, g, b) = (i32::from(r), i32::from(g), i32::from(b));
+            match theme {
+                ColorTheme::GradientWarm => g,
+                ColorTheme::GradientCool => g - b,
+                ColorTheme::GradientOcean => r + g - b,
+                ColorTheme::GradientNeon => g - r,
+                ColorTheme::GradientFire => r + g,
+                _ => unreachable!(),
+            }
+        };
+
+        for theme in [
+            ColorTheme::GradientWarm,
+            ColorTheme::GradientCool,
+            ColorTheme::GradientOcean,
+            ColorTheme::GradientNeon,
+            ColorTheme::GradientFire,
+        ] {
+            for width in [0, 1, 2, 3, 7, 50, 301] {
+                let scores: Vec<i32> = (0..=width + 2)
+                    .map(|x| score(theme, color_to_rgb(theme.color_at_position(x, 0, width, 1))))
+                    .collect();
+                assert!(
+                    scores.windows(2).all(|w| w[0] <= w[1]),
+                    "{theme:?} width {width}: {scores:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_date_rule_weekdays() {
+        let weekend = DateRule::parse("Sat,Sun").unwrap();
+        assert_eq!(weekend, DateRule::Weekdays(0b110_0000));
+        // 2026-10-17 is a Saturday
+        assert!(weekend.matches(10, 17, 5));
+        assert!(!weekend.matches(10, 16, 4));
+        assert_eq!(
+            DateRule::parse("Mon-Fri"),
+            Some(DateRule::Weekdays(0b001_1111))
+        );
+        assert!(DateRule::parse("Someday").is_none());
+
+        // Full names work, other words starting like a day don't
+        assert_eq!(
+            DateRule::parse("saturday,Sunday"),
+            Some(DateRule::Weekdays(0b110_0000))
+        );
+        assert!(DateRule::parse("Monkey").is_none());
+        assert!(DateRule::parse("Sat,Sunny").is_none());
+        assert!(DateRule::parse("Mo").is_none());
+    }
+
+    #[test]
+    fn test_date_rule_ranges_wrap_new_year() {
+        let december = DateRule::parse("12-01..12-31").unwrap();
+        assert!(december.matches(12, 1, 0));
+        assert!(december.matches(12, 31, 0));
+        assert!(!december.matches(11, 30, 0));
+        assert!(!december.matches(1, 1, 0));
+
+        let holidays = DateRule::parse("12-20..01-06").unwrap();
+        assert!(holidays.matches(12, 25, 0));
+        assert!(holidays.matches(1, 1, 0));
+        assert!(holidays.matches(1, 6, 0));
+        assert!(!holidays.matches(1, 7, 0));
+        assert!(!holidays.matches(12, 19, 0));
+
+        assert!(DateRule::parse("13-01..12-31").is_none());
+        assert!(DateRule::parse("12-01..").is_none());
+    }
+
+    #[test]
+    fn test_theme_schedule_wraps_overnight() {
+        let schedule = ThemeSchedule::new(vec![
+            (18 * 60, ColorTheme::GradientCool),
+            (6 * 60, ColorTheme::GradientWarm),
+        ]);
+        assert_eq!(
+            schedule.active(12 * 60).unwrap().1,
+            ColorTheme::GradientWarm
+        );
+        assert_eq!(
+            schedule.active(18 * 60).unwrap().1,
+            ColorTheme::GradientCool
+        );
+        assert_eq!(schedule.active(3 * 60).unwrap().1, ColorTheme::GradientCool);
+        assert!(ThemeSchedule::default().active(0).is_none());
+    }
+
+    #[test]
+    fn test_animate_line_matches_per_cell() {
+        let base: Vec<Color> = (0..12)
+            .map(|x| ColorTheme::Rainbow.color_at_position(x, 0, 12, 1))
+            .collect();
+        for style in ALL_ANIMATION_STYLES {
+            let ctx = AnimationContext {
+                style: *style,
+                speed: AnimationSpeed::Medium,
+                shift_speed: AnimationSpeed::Fast,
+                shift_hue: true,
+                elapsed_ms: 1234,
+                flash_intensity: 0.5,
+                flash_color: None,
+                pulse_min_brightness: 0.3,
+            };
+            let expected: Vec<Color> = base
+                .iter()
+                .enumerate()
+                .map(|(x, &c)| apply_animation(c, &ctx, x, base.len()))
+                .collect();
+            assert_eq!(animate_line(&base, &ctx), expected, "{style:?}");
+        }
+    }
+
+    #[test]
+    fn test_reactive_flash_modes() {
+        let base = Color::Rgb(40, 80, 120);
+        // Brightening by default
+        assert_eq!(apply_reactive(base, 0.0, None), base);
+        assert_eq!(apply_reactive(base, 1.0, None), Color::Rgb(80, 160, 240));
+        // Blending toward the flash color
+        let white = Some((255, 255, 255));
+        assert_eq!(apply_reactive(base, 0.0, white), base);
+        assert_eq!(apply_reactive(base, 1.0, white), Color::Rgb(255, 255, 255));
+        assert_eq!(
+            apply_reactive(base, 0.5, Some((0, 0, 0))),
+            Color::Rgb(20, 40, 60)
+        );
+    }
+
+    #[test]
+    fn test_boost_contrast() {
+        let white = (255, 255, 255);
+        let black = (0, 0, 0);
+
+        // Enough contrast already
+        assert_eq!(boost_contrast(black, white, MIN_GLYPH_CONTRAST), black);
+
+        // Yellow on white darkens just enough, keeping its hue
+        let boosted = boost_contrast((255, 255, 0), white, MIN_GLYPH_CONTRAST);
+        let ratio = contrast_ratio(boosted, white);
+        assert!((MIN_GLYPH_CONTRAST..MIN_GLYPH_CONTRAST + 0.2).contains(&ratio));
+        assert_eq!(boosted.0, boosted.1);
+        assert_eq!(boosted.2, 0);
+
+        // Dark blue on black lightens
+        let boosted = boost_contrast((0, 0, 60), black, MIN_GLYPH_CONTRAST);
+        assert!(contrast_ratio(boosted, black) >= MIN_GLYPH_CONTRAST);
+        assert!(boosted.2 > 60);
+
+        // A mid gray background can't reach 21:1, so the best extreme is used
+        assert_eq!(
+            boost_contrast((120, 120, 120), (128, 128, 128), 21.0),
+            black
+        );
+    }
 }
@@ -0,0 +1,329 @@
+//! On-disk cache of parsed custom fonts.
+//!
+//! Entries are keyed by file path, modification time and size, so a font file is only
+//! re-parsed after it changes. Warnings from parsing are kept with the font so they
+//! still show when it comes from the cache. The cache is a plain line-based text file:
+//!
+//! ```text
+//! sigye-font-cache 4
+//! font <TAB> path <TAB> mtime_secs <TAB> mtime_nanos <TAB> size <TAB> height <TAB> rtl <TAB> char_count <TAB> warning_count <TAB> name
+//! <codepoint>
+//! <height glyph lines>
+//! ...
+//! <warning_count warning lines>
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, Metadata};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use sigye_core::{Warnings, write_atomic};
+
+use crate::font::Font;
+
+/// First line of a cache file, bumped when the format changes.
+const CACHE_HEADER: &str = "sigye-font-cache 4";
+
+/// Identity of a font file on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified_secs: u64,
+    modified_nanos: u32,
+    size: u64,
+}
+
+impl FileStamp {
+    fn from_metadata(metadata: &Metadata) -> Option<Self> {
+        let modified = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+        Some(Self {
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+            size: metadata.len(),
+        })
+    }
+}
+
+/// A cached font and the warnings from parsing it.
+type CacheEntry = (FileStamp, Font, Warnings);
+
+/// Cache of parsed fonts stored in a file.
+#[derive(Debug)]
+pub struct FontCache {
+    path: PathBuf,
+    entries: HashMap<PathBuf, CacheEntry>,
+    /// Font files looked up or stored this session; others are dropped on save.
+    used: HashSet<PathBuf>,
+    dirty: bool,
+}
+
+impl FontCache {
+    /// Load the cache from a file. A missing or unreadable cache starts empty.
+    pub fn load(path: &Path) -> Self {
+        let entries = fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| decode(&contents))
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            entries,
+            used: HashSet::new(),
+            dirty: false,
+        }
+    }
+
+    /// Create an empty cache that will overwrite the file at `path` (for a rescan).
+    pub fn empty(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            entries: HashMap::new(),
+            used: HashSet::new(),
+            dirty: true,
+        }
+    }
+
+    /// Get the cached font and its parse warnings for a file if it hasn't changed
+    /// since it was cached.
+    pub(crate) fn get(&mut self, file: &Path, metadata: &Metadata) -> Option<(Font, Warnings)> {
+        let stamp = FileStamp::from_metadata(metadata)?;
+        let (cached_stamp, font, warnings) = self.entries.get(file)?;
+        if *cached_stamp != stamp {
+            return None;
+        }
+        self.used.insert(file.to_path_buf());
+        Some((font.clone(), warnings.clone()))
+    }
+
+    /// Store a freshly parsed font and its parse warnings for a file.
+    pub(crate) fn insert(
+        &mut self,
+        file: &Path,
+        metadata: &Metadata,
+        font: &Font,
+        warnings: &[String],
+    ) {
+        let Some(stamp) = FileStamp::from_metadata(metadata) else {
+            return;
+        };
+        self.entries
+            .insert(file.to_path_buf(), (stamp, font.clone(), warnings.to_vec()));
+        self.used.insert(file.to_path_buf());
+        self.dirty = true;
+    }
+
+    /// Write the cache back to disk if anything changed, dropping fonts no longer present.
+    /// The file is replaced atomically, so an interrupted save leaves the old cache.
+    pub fn save(&mut self) -> io::Result<()> {
+        let before = self.entries.len();
+        self.entries.retain(|file, _| self.used.contains(file));
+        if !self.dirty && self.entries.len() == before {
+            return Ok(());
+        }
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_atomic(&self.path, &encode(&self.entries))?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+/// Serialize cache entries, skipping any whose path, name or warnings can't be
+/// stored one per line.
+fn encode(entries: &HashMap<PathBuf, CacheEntry>) -> String {
+    let mut out = String::from(CACHE_HEADER);
+    out.push('\n');
+
+    for (file, (stamp, font, warnings)) in entries {
+        let path = file.to_string_lossy();
+        if path.contains(['\t', '\n'])
+            || font.name.contains('\n')
+            || warnings.iter().any(|warning| warning.contains('\n'))
+        {
+            continue;
+        }
+
+        out.push_str(&format!(
+            "font\t{path}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+            stamp.modified_secs,
+            stamp.modified_nanos,
+            stamp.size,
+            font.height,
+            u8::from(font.rtl),
+            font.chars.len(),
+            warnings.len(),
+            font.name
+        ));
+        for (ch, lines) in &font.chars {
+            out.push_str(&format!("{}\n", u32::from(*ch)));
+            for line in lines.iter().take(font.height) {
+                out.push_str(line);
+                out.push('\n');
+            }
+            // Pad short glyphs so every glyph has exactly `height` lines
+            for _ in lines.len()..font.height {
+                out.push('\n');
+            }
+        }
+        for warning in warnings {
+            out.push_str(warning);
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Parse cache file contents, or `None` if the file is malformed or from another version.
+fn decode(contents: &str) -> Option<HashMap<PathBuf, CacheEntry>> {
+    let mut lines = contents.split('\n');
+    if lines.next()? != CACHE_HEADER {
+        return None;
+    }
+
+    let mut entries = HashMap::new();
+    while let Some(line) = lines.next() {
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut fields = line.splitn(10, '\t');
+        if fields.next()? != "font" {
+            return None;
+        }
+        let path = PathBuf::from(fields.next()?);
+        let stamp = FileStamp {
+            modified_secs: fields.next()?.parse().ok()?,
+            modified_nanos: fields.next()?.parse().ok()?,
+            size: fields.next()?.parse().ok()?,
+        };
+        let height: usize = fields.next()?.parse().ok()?;
+        let rtl = fields.next()? == "1";
+        let char_count: usize = fields.next()?.parse().ok()?;
+        let warning_count: usize = fields.next()?.parse().ok()?;
+        let name = fields.next()?.to_string();
+
+        let mut chars = HashMap::with_capacity(char_count);
+        for _ in 0..char_count {
+            let ch = char::from_u32(lines.next()?.parse().ok()?)?;
+            let glyph = (0..height)
+                .map(|_| lines.next().map(str::to_string))
+                .collect::<Option<Vec<_>>>()?;
+            chars.insert(ch, glyph);
+        }
+        let warnings = (0..warning_count)
+            .map(|_| lines.next().map(str::to_string))
+            .collect::<Option<Warnings>>()?;
+
+        entries.insert(
+            path,
+            (
+                stamp,
+                Font {
+                    name,
+                    height,
+                    rtl,
+                    chars,
+                },
+                warnings,
+            ),
+        );
+    }
+
+    Some(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let font = crate::parse_flf("Standard", crate::bundled::STANDARD_FLF).unwrap();
+        let stamp = FileStamp {
+            modified_secs: 1_700_000_000,
+            modified_nanos: 42,
+            size: 1234,
+        };
+        let warnings = vec!["file ends partway through the glyph for 'z'".to_string()];
+        let mut entries = HashMap::new();
+        entries.insert(
+            PathBuf::from("/fonts/Standard.flf"),
+            (stamp, font.clone(), warnings.clone()),
+        );
+
+        let decoded = decode(&encode(&entries)).unwrap();
+        let (decoded_stamp, decoded_font, decoded_warnings) =
+            &decoded[Path::new("/fonts/Standard.flf")];
+        assert_eq!(*decoded_stamp, stamp);
+        assert_eq!(*decoded_warnings, warnings);
+        assert_eq!(decoded_font.name, font.name);
+        assert_eq!(decoded_font.height, font.height);
+        assert_eq!(decoded_font.rtl, font.rtl);
+        assert_eq!(decoded_font.chars, font.chars);
+    }
+
+    #[test]
+    fn test_registry_uses_cache() {
+        let dir = std::env::temp_dir().join(format!("sigye-font-cache-{}", std::process::id()));
+        let fonts_dir = dir.join("fonts");
+        fs::create_dir_all(&fonts_dir).unwrap();
+        fs::write(fonts_dir.join("Custom.flf"), crate::bundled::STANDARD_FLF).unwrap();
+        let cache_path = dir.join("font-cache");
+
+        let mut cache = FontCache::load(&cache_path);
+        let mut registry = crate::FontRegistry::new();
+        registry.load_custom_fonts_cached(&fonts_dir, &mut cache);
+        cache.save().unwrap();
+        assert!(registry.has_font("Custom"));
+        assert_eq!(
+            registry.font_source("Custom"),
+            Some(crate::FontSource::Custom)
+        );
+
+        // A second load is served from the cache without parsing
+        let mut cache = FontCache::load(&cache_path);
+        let metadata = fs::metadata(fonts_dir.join("Custom.flf")).unwrap();
+        assert!(
+            cache
+                .get(&fonts_dir.join("Custom.flf"), &metadata)
+                .is_some()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_cached_font_keeps_warnings() {
+        let dir = std::env::temp_dir().join(format!("sigye-font-warn-{}", std::process::id()));
+        let fonts_dir = dir.join("fonts");
+        fs::create_dir_all(&fonts_dir).unwrap();
+        // Cut the file off partway through the ASCII glyphs
+        let cut: Vec<&str> = crate::bundled::STANDARD_FLF.lines().take(200).collect();
+        fs::write(fonts_dir.join("Cut.flf"), cut.join("\n")).unwrap();
+        let cache_path = dir.join("font-cache");
+
+        let mut cache = FontCache::load(&cache_path);
+        let warnings = crate::FontRegistry::new().load_custom_fonts_cached(&fonts_dir, &mut cache);
+        cache.save().unwrap();
+        assert_eq!(warnings.len(), 1);
+
+        // Loading from the cache reports the same problem again
+        let mut cache = FontCache::load(&cache_path);
+        let cached = crate::FontRegistry::new().load_custom_fonts_cached(&fonts_dir, &mut cache);
+        let metadata = fs::metadata(fonts_dir.join("Cut.flf")).unwrap();
+        assert!(cache.get(&fonts_dir.join("Cut.flf"), &metadata).is_some());
+        assert_eq!(cached, warnings);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_rejects_other_versions() {
+        assert!(decode("sigye-font-cache 1\n").is_none());
+        assert!(decode("sigye-font-cache 3\n").is_none());
+        assert!(decode("").is_none());
+    }
+}
@@ -2,6 +2,9 @@
 
 use std::collections::HashMap;
 
+/// Characters a font needs to draw any time the clock shows.
+pub const CLOCK_CHARS: &str = "0123456789: APM";
+
 /// A FIGlet font containing character definitions.
 #[derive(Debug, Clone)]
 pub struct Font {
@@ -9,18 +12,45 @@ pub struct Font {
     pub name: String,
     /// Height in lines.
     pub height: usize,
+    /// Whether the font reads right to left (FIGlet print direction 1).
+    pub rtl: bool,
     /// Character definitions.
     pub chars: HashMap<char, Vec<String>>,
 }
 
+/// How glyphs are laid out when rendering text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TextLayout {
+    /// Lay the characters out right to left.
+    pub rtl: bool,
+    /// Blank columns inserted between adjacent glyphs.
+    pub letter_spacing: usize,
+}
+
 impl Font {
-    /// Render text using this font.
+    /// Render text using this font, left to right whatever the font's own direction.
     ///
     /// Returns a vector of strings, one for each line of the output.
     pub fn render_text(&self, text: &str) -> Vec<String> {
+        self.render_text_with(text, TextLayout::default())
+    }
+
+    /// Render text with an explicit layout, ignoring the font's own direction.
+    pub fn render_text_with(&self, text: &str, layout: TextLayout) -> Vec<String> {
         let mut lines: Vec<String> = vec![String::new(); self.height];
 
-        for ch in text.chars() {
+        let mut ordered: Vec<char> = text.chars().collect();
+        if layout.rtl {
+            ordered.reverse();
+        }
+        for (idx, ch) in ordered.into_iter().enumerate() {
+            // Gaps go between glyphs only, never after the last one
+            if idx > 0 && layout.letter_spacing > 0 {
+                for line in &mut lines {
+                    line.extend(std::iter::repeat_n(' ', layout.letter_spacing));
+                }
+            }
+
             if let Some(char_lines) = self.chars.get(&ch) {
                 for (i, char_line) in char_lines.iter().enumerate() {
                     if i < lines.len() {
@@ -40,6 +70,99 @@ impl Font {
         lines
     }
 
+    /// Render text like [`Font::render_text`], also returning the characters the font
+    /// doesn't define (each listed once, in order of first appearance).
+    pub fn render_text_checked(&self, text: &str) -> (Vec<String>, Vec<char>) {
+        (self.render_text(text), self.missing_chars(text))
+    }
+
+    /// Get the characters of `text` the font doesn't define, each listed once in
+    /// order of first appearance.
+    pub fn missing_chars(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+        for ch in text.chars() {
+            if !self.chars.contains_key(&ch) && !missing.contains(&ch) {
+                missing.push(ch);
+            }
+        }
+        missing
+    }
+
+    /// Check whether the font defines every character of `text`.
+    pub fn covers(&self, text: &str) -> bool {
+        text.chars().all(|ch| self.chars.contains_key(&ch))
+    }
+
+    /// Get a horizontally mirrored copy of the font.
+    ///
+    /// Each glyph row is reversed, with direction-dependent characters like `/` and `(`
+    /// swapped for their counterparts so the art reflects correctly. Widths are unchanged.
+    pub fn mirrored(&self) -> Font {
+        let chars = self
+            .chars
+            .iter()
+            .map(|(&ch, lines)| {
+                let width = lines
+                    .iter()
+                    .map(|line| line.chars().count())
+                    .max()
+                    .unwrap_or(0);
+                let mirrored = lines
+                    .iter()
+                    .map(|line| {
+                        // Pad ragged rows first so the glyph stays aligned once flipped
+                        let padding = width - line.chars().count();
+                        std::iter::repeat_n(' ', padding)
+                            .chain(line.chars().rev().map(mirror_char))
+                            .collect()
+                    })
+                    .collect();
+                (ch, mirrored)
+            })
+            .collect();
+
+        Font {
+            name: self.name.clone(),
+            height: self.height,
+            rtl: self.rtl,
+            chars,
+        }
+    }
+
+    /// Get a copy of the font without the top and bottom rows that are blank in every
+    /// glyph of `used`, so text made of those characters sits snugly. At least one row
+    /// is kept.
+    pub fn trimmed(&self, used: &str) -> Font {
+        let row_blank = |row: usize| {
+            used.chars()
+                .filter_map(|ch| self.chars.get(&ch))
+                .all(|lines| lines.get(row).is_none_or(|line| line.trim().is_empty()))
+        };
+        let top = (0..self.height).take_while(|&row| row_blank(row)).count();
+        let bottom = (top..self.height)
+            .rev()
+            .take_while(|&row| row_blank(row))
+            .count();
+        let end = (self.height - bottom).max(top + 1).min(self.height);
+        let top = top.min(end.saturating_sub(1));
+
+        let chars = self
+            .chars
+            .iter()
+            .map(|(&ch, lines)| {
+                let rows = lines.get(top..end.min(lines.len())).unwrap_or_default();
+                (ch, rows.to_vec())
+            })
+            .collect();
+
+        Font {
+            name: self.name.clone(),
+            height: end - top,
+            rtl: self.rtl,
+            chars,
+        }
+    }
+
     /// Get the width of a character.
     pub fn char_width(&self, ch: char) -> usize {
         self.chars
@@ -49,3 +172,127 @@ impl Font {
             .unwrap_or(0)
     }
 }
+
+/// Swap a character for its horizontal mirror image, if it has one.
+fn mirror_char(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '/' => '\\',
+        '\\' => '/',
+        _ => ch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bundled, parse_flf};
+
+    #[test]
+    fn test_render_text_checked_reports_missing() {
+        let font = parse_flf("Mini", bundled::MINI_FLF).unwrap();
+        assert!(!font.chars.contains_key(&'☃'));
+
+        let (lines, missing) = font.render_text_checked("1☃2☃");
+        assert_eq!(missing, vec!['☃']);
+        assert_eq!(lines, font.render_text("1☃2☃"));
+
+        let (_, missing) = font.render_text_checked("12:34");
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_trimmed_drops_blank_rows() {
+        let font = Font {
+            name: "Test".to_string(),
+            height: 4,
+            rtl: false,
+            chars: [
+                ('1', vec!["  ", "/|", " |", "  "]),
+                ('2', vec!["  ", "_ ", "/_", "  "]),
+                // Descender only used by other text
+                ('g', vec!["  ", "g ", "g ", "J "]),
+            ]
+            .map(|(ch, rows)| (ch, rows.into_iter().map(String::from).collect()))
+            .into(),
+        };
+
+        let trimmed = font.trimmed("12");
+        assert_eq!(trimmed.height, 2);
+        assert_eq!(trimmed.chars[&'1'], vec!["/|", " |"]);
+        assert_eq!(trimmed.chars[&'g'], vec!["g ", "g "]);
+        assert_eq!(font.trimmed("12g").height, 3);
+
+        // Text that is blank all the way down keeps one row
+        assert_eq!(font.trimmed(" ").height, 1);
+    }
+
+    #[test]
+    fn test_mirrored() {
+        let font = Font {
+            name: "Test".to_string(),
+            height: 2,
+            rtl: false,
+            chars: [('a', vec!["/-(".to_string(), "\\_".to_string()])].into(),
+        };
+        let mirrored = font.mirrored();
+        assert_eq!(mirrored.chars[&'a'], vec![")-\\", " _/"]);
+        assert_eq!(mirrored.char_width('a'), font.char_width('a'));
+
+        let standard = parse_flf("Standard", bundled::STANDARD_FLF).unwrap();
+        let mirrored = standard.mirrored();
+        for ch in standard.chars.keys() {
+            assert_eq!(mirrored.char_width(*ch), standard.char_width(*ch));
+        }
+    }
+
+    #[test]
+    fn test_rtl_reverses_character_order() {
+        let font = parse_flf("Ivrit", bundled::IVRIT_FLF).unwrap();
+        assert!(font.rtl);
+        let rtl = TextLayout {
+            rtl: true,
+            ..TextLayout::default()
+        };
+        assert_eq!(
+            font.render_text_with("12", rtl),
+            font.render_text_with("21", TextLayout::default())
+        );
+    }
+
+    #[test]
+    fn test_rtl_font_renders_ltr_by_default() {
+        let font = parse_flf("Ivrit", bundled::IVRIT_FLF).unwrap();
+        assert!(font.rtl);
+        assert_eq!(
+            font.render_text("12"),
+            font.render_text_with("12", TextLayout::default())
+        );
+        assert_ne!(font.render_text("12"), font.render_text("21"));
+    }
+
+    #[test]
+    fn test_letter_spacing_width() {
+        let font = parse_flf("Standard", bundled::STANDARD_FLF).unwrap();
+        let text = "12:34";
+        let layout = TextLayout {
+            letter_spacing: 2,
+            ..TextLayout::default()
+        };
+        let glyphs: usize = text.chars().map(|ch| font.char_width(ch)).sum();
+
+        for line in font.render_text_with(text, layout) {
+            assert_eq!(
+                line.chars().count(),
+                glyphs + 2 * (text.chars().count() - 1)
+            );
+        }
+    }
+}
@@ -3,13 +3,15 @@
 //! This crate provides FIGlet font parsing and rendering for the terminal clock.
 
 mod bundled;
+mod cache;
 mod font;
 mod parser;
 mod registry;
 
-pub use font::Font;
-pub use parser::{ParseError, parse_flf};
-pub use registry::FontRegistry;
+pub use cache::FontCache;
+pub use font::{CLOCK_CHARS, Font, TextLayout};
+pub use parser::{ParseError, parse_flf, parse_flf_with_warnings};
+pub use registry::{FontRegistry, FontSource};
 
 // Re-export bundled font constants for direct access
 pub use bundled::BUNDLED_FONTS;
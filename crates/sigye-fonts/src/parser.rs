@@ -37,35 +37,69 @@ struct FlfHeader {
     _max_length: usize,
     _old_layout: i32,
     comment_lines: usize,
+    /// 0 for left to right, 1 for right to left.
+    print_direction: i32,
 }
 
 /// Parse an FLF font file from string content.
 pub fn parse_flf(name: &str, content: &str) -> Result<Font, ParseError> {
-    let mut lines = content.lines();
+    parse_flf_with_warnings(name, content).map(|(font, _)| font)
+}
+
+/// Parse an FLF font file, also returning warnings about recoverable problems.
+///
+/// A file that ends partway through the ASCII glyphs still yields a usable font with the
+/// glyphs parsed so far; it only fails if not even the first glyph is complete.
+pub fn parse_flf_with_warnings(
+    name: &str,
+    content: &str,
+) -> Result<(Font, Vec<String>), ParseError> {
+    // TLF glyphs may carry terminal color/format codes, which would show up as stray
+    // characters, so they're removed up front
+    let cleaned: Vec<String>;
+    let lines: Vec<&str> = if content.starts_with(TLF_SIGNATURE) {
+        cleaned = content.lines().map(strip_control_codes).collect();
+        cleaned.iter().map(String::as_str).collect()
+    } else {
+        content.lines().collect()
+    };
 
     // Parse header
-    let header_line = lines.next().ok_or(ParseError::UnexpectedEndOfFile)?;
+    let header_line = lines.first().ok_or(ParseError::UnexpectedEndOfFile)?;
     let header = parse_header(header_line)?;
 
     // Skip comment lines
-    for _ in 0..header.comment_lines {
-        lines.next();
-    }
+    let mut pos = (1 + header.comment_lines).min(lines.len());
 
     // Parse characters
     let mut chars: HashMap<char, Vec<String>> = HashMap::new();
+    let mut warnings = Vec::new();
 
     // Standard ASCII characters start at 32 (space) and go to 126 (~)
     for ascii_code in 32u8..=126 {
-        let char_lines = parse_character(&mut lines, header.height, header.hardblank)?;
-        chars.insert(ascii_code as char, char_lines);
+        match parse_character(&lines[pos..], header.height, header.hardblank) {
+            Ok((char_lines, used)) => {
+                chars.insert(ascii_code as char, char_lines);
+                pos += used;
+            }
+            Err(ParseError::UnexpectedEndOfFile) if !chars.is_empty() => {
+                warnings.push(format!(
+                    "File ends early, glyphs from '{}' onward are missing",
+                    ascii_code as char
+                ));
+                break;
+            }
+            Err(e) => return Err(e),
+        }
     }
 
-    Ok(Font {
+    let font = Font {
         name: name.to_string(),
         height: header.height,
+        rtl: header.print_direction == 1,
         chars,
-    })
+    };
+    Ok((font, warnings))
 }
 
 /// Parse the FLF/TLF header line.
@@ -109,6 +143,8 @@ fn parse_header(line: &str) -> Result<FlfHeader, ParseError> {
     let comment_lines = parts[4]
         .parse()
         .map_err(|_| ParseError::InvalidHeader("Invalid comment_lines".to_string()))?;
+    // Print direction is optional and defaults to left to right
+    let print_direction = parts.get(5).and_then(|p| p.parse().ok()).unwrap_or(0);
 
     Ok(FlfHeader {
         hardblank,
@@ -117,37 +153,86 @@ fn parse_header(line: &str) -> Result<FlfHeader, ParseError> {
         _max_length: max_length,
         _old_layout: old_layout,
         comment_lines,
+        print_direction,
     })
 }
 
-/// Parse a single character from the FLF file.
-fn parse_character<'a>(
-    lines: &mut impl Iterator<Item = &'a str>,
+/// Remove ANSI escape sequences and other control characters from a line.
+fn strip_control_codes(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            match chars.next() {
+                // CSI: parameters up to a final byte in '@'..='~'
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // OSC: up to BEL or ST (ESC \)
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        } else if ch == '\t' {
+            out.push(' ');
+        } else if !ch.is_control() {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Parse a single character from the start of `lines`.
+///
+/// The glyph ends at the first line closed with `@@`, so a block with a missing or extra
+/// row doesn't shift every glyph after it. Blocks without end markers fall back to
+/// `height` lines. Returns the glyph, normalized to `height` rows, and the lines consumed.
+fn parse_character(
+    lines: &[&str],
     height: usize,
     hardblank: char,
-) -> Result<Vec<String>, ParseError> {
-    let mut char_lines = Vec::with_capacity(height);
-
-    for i in 0..height {
-        let line = lines.next().ok_or(ParseError::UnexpectedEndOfFile)?;
-
-        // Remove end markers (@ or @@)
-        // TLF format may have trailing whitespace after @ markers, so trim whitespace first
-        let cleaned = if i == height - 1 {
-            // Last line ends with @@
-            line.trim_end().trim_end_matches('@')
-        } else {
-            // Other lines end with @
-            line.trim_end().trim_end_matches('@')
-        };
-
-        // Replace hardblank with space
-        let final_line = cleaned.replace(hardblank, " ");
-
-        char_lines.push(final_line);
+) -> Result<(Vec<String>, usize), ParseError> {
+    // A marker further than `height - 1` rows past the expected end belongs to the next glyph
+    let window = (height * 2).saturating_sub(1);
+    let used = lines
+        .iter()
+        .take(window)
+        .position(|line| line.trim_end().ends_with("@@"))
+        .map_or(height, |end| end + 1);
+    if lines.len() < used {
+        return Err(ParseError::UnexpectedEndOfFile);
     }
 
-    Ok(char_lines)
+    let mut char_lines: Vec<String> = lines[..used]
+        .iter()
+        .take(height)
+        .map(|line| {
+            // Remove end markers (@ or @@)
+            // TLF format may have trailing whitespace after @ markers, so trim whitespace first
+            line.trim_end()
+                .trim_end_matches('@')
+                .replace(hardblank, " ")
+        })
+        .collect();
+
+    // Pad short glyphs with blank rows of the glyph's width so columns stay aligned
+    let width = char_lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    char_lines.resize(height, " ".repeat(width));
+
+    Ok((char_lines, used))
 }
 
 #[cfg(test)]
@@ -160,6 +245,10 @@ mod tests {
         assert_eq!(header.hardblank, '$');
         assert_eq!(header.height, 5);
         assert_eq!(header.comment_lines, 10);
+        assert_eq!(header.print_direction, 0);
+
+        let header = parse_header("flf2a$ 6 5 76 15 14 1 16271").unwrap();
+        assert_eq!(header.print_direction, 1);
     }
 
     #[test]
@@ -169,4 +258,58 @@ mod tests {
         assert_eq!(header.height, 8);
         assert_eq!(header.comment_lines, 4);
     }
+
+    /// Build a 2-row font where each glyph is its character followed by `-`, with custom
+    /// blocks for some characters.
+    fn test_font(overrides: &[(char, &str)]) -> String {
+        let mut content = String::from("flf2a$ 2 1 4 0 0\n");
+        for ch in (32u8..=126).map(char::from) {
+            match overrides.iter().find(|(c, _)| *c == ch) {
+                Some((_, block)) => content.push_str(block),
+                None => content.push_str(&format!("{ch}-@\n{ch}-@@\n")),
+            }
+        }
+        content
+    }
+
+    #[test]
+    fn test_malformed_glyph_does_not_desync() {
+        // '!' has a stray extra row and '"' is missing one
+        let content = test_font(&[('!', "!!@\n!!@\n  @@\n"), ('"', "\"\"@@\n")]);
+        let font = parse_flf("Test", &content).unwrap();
+
+        assert_eq!(font.chars[&'!'], vec!["!!", "!!"]);
+        assert_eq!(font.chars[&'"'], vec!["\"\"", "  "]);
+        assert_eq!(font.chars[&'#'], vec!["#-", "#-"]);
+        assert_eq!(font.chars[&'~'], vec!["~-", "~-"]);
+    }
+
+    #[test]
+    fn test_tlf_color_codes_are_stripped() {
+        let content = test_font(&[(
+            '0',
+            "\x1b[1;32m█\x1b[0m▀@\n\x1b]0;title\x07▀\x1b[31m█\x1b[m@@\n",
+        )])
+        .replacen("flf2a", "tlf2a", 1);
+        let font = parse_flf("Test", &content).unwrap();
+
+        assert_eq!(font.chars[&'0'], vec!["█▀", "▀█"]);
+        assert_eq!(font.chars[&'1'], vec!["1-", "1-"]);
+    }
+
+    #[test]
+    fn test_truncated_font_keeps_parsed_glyphs() {
+        let content = test_font(&[]);
+        // Cut the file off partway through 'z' (the fourth glyph from the end)
+        let cut = content.find("z-@@").unwrap();
+        let (font, warnings) = parse_flf_with_warnings("Test", &content[..cut]).unwrap();
+
+        assert_eq!(font.chars[&'y'], vec!["y-", "y-"]);
+        assert!(!font.chars.contains_key(&'z'));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'z'"));
+
+        // Nothing usable at all is still an error
+        assert!(parse_flf("Test", "flf2a$ 2 1 4 0 0\n").is_err());
+    }
 }
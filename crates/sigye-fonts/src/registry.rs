@@ -1,43 +1,135 @@
 //! Font registry for managing available fonts.
 
+use std::cell::OnceCell;
 use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 
+use sigye_core::{Warnings, normalize_name};
+
 use crate::bundled::BUNDLED_FONTS;
+use crate::cache::FontCache;
+#[cfg(test)]
+use crate::font::CLOCK_CHARS;
 use crate::font::Font;
-use crate::parser::parse_flf;
+use crate::parser::{parse_flf, parse_flf_with_warnings};
+
+/// Where a registered font came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontSource {
+    /// Built into sigye.
+    Bundled,
+    /// Loaded from a custom fonts directory.
+    Custom,
+}
+
+impl FontSource {
+    /// Get display name for the font source.
+    pub fn display_name(self) -> &'static str {
+        match self {
+            FontSource::Bundled => "bundled",
+            FontSource::Custom => "custom",
+        }
+    }
+}
+
+/// A registered font, parsed on first use.
+#[derive(Debug)]
+struct FontEntry {
+    /// Font file content to parse, if the font isn't loaded yet.
+    source: Option<&'static str>,
+    /// Parsed font (`None` inside if parsing failed).
+    font: OnceCell<Option<Font>>,
+}
+
+impl FontEntry {
+    /// Create an entry that parses `content` on first use.
+    fn lazy(content: &'static str) -> Self {
+        Self {
+            source: Some(content),
+            font: OnceCell::new(),
+        }
+    }
+
+    /// Create an entry for an already parsed font.
+    fn loaded(font: Font) -> Self {
+        Self {
+            source: None,
+            font: OnceCell::from(Some(font)),
+        }
+    }
+
+    /// Get the font, parsing it if this is the first use.
+    fn font(&self, name: &str) -> Option<&Font> {
+        self.font
+            .get_or_init(|| {
+                self.source
+                    .and_then(|content| parse_flf(name, content).ok())
+            })
+            .as_ref()
+    }
+}
 
 /// Registry of available fonts.
+///
+/// Bundled fonts are parsed lazily on first lookup.
 #[derive(Debug)]
 pub struct FontRegistry {
-    fonts: HashMap<String, Font>,
+    fonts: HashMap<String, FontEntry>,
+    /// Fonts that failed to load or loaded with problems, as `(path, message)` pairs.
+    load_errors: Vec<(String, String)>,
+    /// Whether custom fonts replace bundled fonts of the same name.
+    allow_override: bool,
 }
 
 impl FontRegistry {
-    /// Create a new registry with bundled fonts loaded.
+    /// Create a new registry with bundled fonts registered.
     pub fn new() -> Self {
-        let mut registry = Self {
-            fonts: HashMap::new(),
-        };
-
-        // Load all bundled fonts
-        for (name, content) in BUNDLED_FONTS {
-            match parse_flf(name, content) {
-                Ok(font) => {
-                    registry.fonts.insert(name.to_string(), font);
-                }
-                Err(e) => {
-                    eprintln!("Warning: Failed to load bundled font '{name}': {e}");
-                }
-            }
+        let fonts = BUNDLED_FONTS
+            .iter()
+            .map(|(name, content)| (name.to_string(), FontEntry::lazy(content)))
+            .collect();
+        Self {
+            fonts,
+            load_errors: Vec::new(),
+            allow_override: false,
         }
+    }
+
+    /// Set whether custom fonts loaded afterwards replace bundled fonts of the same name.
+    /// Either way, a name collision is reported in [`FontRegistry::load_errors`].
+    pub fn set_allow_override(&mut self, allow: bool) {
+        self.allow_override = allow;
+    }
 
-        registry
+    /// Load custom fonts from a directory, returning a warning for each problem.
+    pub fn load_custom_fonts(&mut self, fonts_dir: &Path) -> Warnings {
+        let start = self.load_errors.len();
+        self.load_custom_fonts_inner(fonts_dir, None);
+        self.warnings_since(start)
     }
 
-    /// Load custom fonts from a directory.
-    pub fn load_custom_fonts(&mut self, fonts_dir: &Path) {
+    /// Load custom fonts from a directory, reusing parsed fonts from `cache`
+    /// for files that haven't changed and storing newly parsed ones.
+    pub fn load_custom_fonts_cached(
+        &mut self,
+        fonts_dir: &Path,
+        cache: &mut FontCache,
+    ) -> Warnings {
+        let start = self.load_errors.len();
+        self.load_custom_fonts_inner(fonts_dir, Some(cache));
+        self.warnings_since(start)
+    }
+
+    /// Describe the load errors from index `start` on, one message each.
+    fn warnings_since(&self, start: usize) -> Warnings {
+        self.load_errors[start..]
+            .iter()
+            .map(|(path, error)| format!("Font '{path}': {error}"))
+            .collect()
+    }
+
+    fn load_custom_fonts_inner(&mut self, fonts_dir: &Path, mut cache: Option<&mut FontCache>) {
         if !fonts_dir.exists() {
             return;
         }
@@ -45,7 +137,8 @@ impl FontRegistry {
         let entries = match fs::read_dir(fonts_dir) {
             Ok(entries) => entries,
             Err(e) => {
-                eprintln!("Warning: Failed to read fonts directory: {e}");
+                self.load_errors
+                    .push((fonts_dir.display().to_string(), e.to_string()));
                 return;
             }
         };
@@ -58,38 +151,111 @@ impl FontRegistry {
             {
                 let name = stem.to_string_lossy().to_string();
 
-                // Skip if already loaded (bundled fonts take precedence)
-                if self.fonts.contains_key(&name) {
+                // Names that differ only in case or separators collide, since lookups
+                // ignore those. Bundled fonts win unless overriding; earlier
+                // directories always win.
+                let existing = self.resolve_name(&name).map(str::to_string);
+                let replaces = match existing {
+                    None => None,
+                    Some(existing)
+                        if self.fonts[&existing].source.is_some() && self.allow_override =>
+                    {
+                        Some(existing)
+                    }
+                    Some(existing) => {
+                        let winner = if self.fonts[&existing].source.is_some() {
+                            "the bundled font"
+                        } else {
+                            "a font from an earlier directory"
+                        };
+                        self.load_errors.push((
+                            path.display().to_string(),
+                            format!("skipped, {winner} named '{existing}' takes precedence"),
+                        ));
+                        continue;
+                    }
+                };
+
+                // Unchanged files come straight from the cache
+                let metadata = entry.metadata().ok();
+                if let (Some(cache), Some(metadata)) = (cache.as_deref_mut(), &metadata)
+                    && let Some((font, warnings)) = cache.get(&path, metadata)
+                {
+                    for warning in warnings {
+                        self.load_errors.push((path.display().to_string(), warning));
+                    }
+                    self.insert_custom(name, &path, font, replaces);
                     continue;
                 }
 
                 match fs::read_to_string(&path) {
-                    Ok(content) => match parse_flf(&name, &content) {
-                        Ok(font) => {
-                            self.fonts.insert(name, font);
+                    Ok(content) => match parse_flf_with_warnings(&name, &content) {
+                        Ok((font, warnings)) => {
+                            if let (Some(cache), Some(metadata)) = (cache.as_deref_mut(), &metadata)
+                            {
+                                cache.insert(&path, metadata, &font, &warnings);
+                            }
+                            for warning in warnings {
+                                self.load_errors.push((path.display().to_string(), warning));
+                            }
+                            self.insert_custom(name, &path, font, replaces);
                         }
                         Err(e) => {
-                            eprintln!("Warning: Failed to parse font '{}': {e}", path.display());
+                            self.load_errors
+                                .push((path.display().to_string(), e.to_string()));
                         }
                     },
                     Err(e) => {
-                        eprintln!("Warning: Failed to read font '{}': {e}", path.display());
+                        self.load_errors
+                            .push((path.display().to_string(), e.to_string()));
                     }
                 }
             }
         }
     }
 
-    /// Get a font by name.
+    /// Register a custom font, removing and noting the bundled font it replaces.
+    fn insert_custom(&mut self, name: String, path: &Path, font: Font, replaces: Option<String>) {
+        if let Some(replaced) = replaces {
+            self.fonts.remove(&replaced);
+            self.load_errors.push((
+                path.display().to_string(),
+                format!("replaces the bundled font '{replaced}'"),
+            ));
+        }
+        self.fonts.insert(name, FontEntry::loaded(font));
+    }
+
+    /// Get font loading problems as `(path, message)` pairs.
+    /// Failed fonts are skipped and partially readable ones kept, so the registry stays usable.
+    pub fn load_errors(&self) -> &[(String, String)] {
+        &self.load_errors
+    }
+
+    /// Get a font by name, parsing it on first use.
     pub fn get(&self, name: &str) -> Option<&Font> {
-        self.fonts.get(name)
+        let name = self.resolve_name(name)?;
+        self.fonts.get(name).and_then(|entry| entry.font(name))
+    }
+
+    /// Get the registered name a font name refers to, ignoring case and separators,
+    /// so `ansi_shadow` finds `ANSI Shadow`. Loading keeps at most one font per
+    /// such name, picked by source precedence, so the match is never ambiguous.
+    pub fn resolve_name(&self, name: &str) -> Option<&str> {
+        if let Some((key, _)) = self.fonts.get_key_value(name) {
+            return Some(key);
+        }
+        let normalized = normalize_name(name);
+        self.fonts
+            .keys()
+            .find(|key| normalize_name(key) == normalized)
+            .map(String::as_str)
     }
 
     /// Get a font by name, or the default font if not found.
     pub fn get_or_default(&self, name: &str) -> &Font {
-        self.fonts
-            .get(name)
-            .or_else(|| self.fonts.get("Standard"))
+        self.get(name)
+            .or_else(|| self.get("Standard"))
             .expect("Standard font should always be available")
     }
 
@@ -100,9 +266,22 @@ impl FontRegistry {
         names
     }
 
+    /// Get where a font came from, or `None` if it isn't registered.
+    pub fn font_source(&self, name: &str) -> Option<FontSource> {
+        // Only bundled fonts are registered unparsed
+        let name = self.resolve_name(name)?;
+        self.fonts.get(name).map(|entry| {
+            if entry.source.is_some() {
+                FontSource::Bundled
+            } else {
+                FontSource::Custom
+            }
+        })
+    }
+
     /// Check if a font exists.
     pub fn has_font(&self, name: &str) -> bool {
-        self.fonts.contains_key(name)
+        self.resolve_name(name).is_some()
     }
 
     /// Get the number of loaded fonts.
@@ -121,3 +300,186 @@ impl Default for FontRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_fonts_does_not_parse() {
+        let registry = FontRegistry::new();
+        assert_eq!(registry.list_fonts().len(), BUNDLED_FONTS.len());
+        assert!(
+            registry
+                .fonts
+                .values()
+                .all(|entry| entry.font.get().is_none())
+        );
+    }
+
+    #[test]
+    fn test_get_memoizes() {
+        let registry = FontRegistry::new();
+        let first = registry.get("Standard").unwrap();
+        let second = registry.get("Standard").unwrap();
+        assert!(std::ptr::eq(first, second));
+        assert!(registry.fonts["Big"].font.get().is_none());
+    }
+
+    #[test]
+    fn test_bad_custom_font_is_reported() {
+        let dir = std::env::temp_dir().join(format!("sigye-bad-font-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("Broken.flf"), "not a font").unwrap();
+
+        let mut registry = FontRegistry::new();
+        let warnings = registry.load_custom_fonts(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(!registry.has_font("Broken"));
+        assert_eq!(registry.font_source("Broken"), None);
+        assert_eq!(registry.font_source("Standard"), Some(FontSource::Bundled));
+        assert_eq!(registry.load_errors().len(), 1);
+        assert!(registry.load_errors()[0].0.ends_with("Broken.flf"));
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Font '") && warnings[0].contains("Broken.flf':"));
+    }
+
+    #[test]
+    fn test_custom_font_named_like_bundled() {
+        let dir = std::env::temp_dir().join(format!("sigye-dup-font-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("Standard.flf"),
+            BUNDLED_FONTS
+                .iter()
+                .find(|(name, _)| *name == "Mini")
+                .unwrap()
+                .1,
+        )
+        .unwrap();
+
+        // Bundled wins by default, with a warning
+        let mut registry = FontRegistry::new();
+        registry.load_custom_fonts(&dir);
+        assert_eq!(registry.font_source("Standard"), Some(FontSource::Bundled));
+        assert_eq!(registry.load_errors().len(), 1);
+        assert!(registry.load_errors()[0].1.contains("bundled font"));
+
+        // Overriding replaces it, still with a warning
+        let mut registry = FontRegistry::new();
+        registry.set_allow_override(true);
+        registry.load_custom_fonts(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(registry.font_source("Standard"), Some(FontSource::Custom));
+        assert_eq!(registry.load_errors().len(), 1);
+        assert!(registry.load_errors()[0].1.starts_with("replaces"));
+    }
+
+    #[test]
+    fn test_custom_font_name_collides_ignoring_separators() {
+        let dir = std::env::temp_dir().join(format!("sigye-sep-font-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mini = BUNDLED_FONTS
+            .iter()
+            .find(|(name, _)| *name == "Mini")
+            .unwrap()
+            .1;
+        fs::write(dir.join("ansi_shadow.flf"), mini).unwrap();
+
+        let mut registry = FontRegistry::new();
+        registry.load_custom_fonts(&dir);
+        assert!(!registry.list_fonts().contains(&"ansi_shadow"));
+        assert_eq!(
+            registry.font_source("ansi_shadow"),
+            Some(FontSource::Bundled)
+        );
+        assert!(registry.load_errors()[0].1.contains("'ANSI Shadow'"));
+
+        // Overriding drops the bundled entry rather than keeping both
+        let mut registry = FontRegistry::new();
+        registry.set_allow_override(true);
+        registry.load_custom_fonts(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(!registry.list_fonts().contains(&"ANSI Shadow"));
+        assert_eq!(registry.resolve_name("ANSI Shadow"), Some("ansi_shadow"));
+        assert_eq!(registry.len(), BUNDLED_FONTS.len());
+    }
+
+    #[test]
+    fn test_custom_font_differing_in_case_resolves_by_source() {
+        let dir = std::env::temp_dir().join(format!("sigye-case-font-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mini = BUNDLED_FONTS
+            .iter()
+            .find(|(name, _)| *name == "Mini")
+            .unwrap()
+            .1;
+        fs::write(dir.join("standard.flf"), mini).unwrap();
+
+        // Bundled takes precedence whichever spelling is asked for
+        let mut registry = FontRegistry::new();
+        registry.load_custom_fonts(&dir);
+        for name in ["Standard", "standard", "STANDARD"] {
+            assert_eq!(registry.resolve_name(name), Some("Standard"));
+            assert_eq!(registry.font_source(name), Some(FontSource::Bundled));
+        }
+
+        // With overriding, the custom font answers to every spelling
+        let mut registry = FontRegistry::new();
+        registry.set_allow_override(true);
+        registry.load_custom_fonts(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+        for name in ["Standard", "standard", "STANDARD"] {
+            assert_eq!(registry.resolve_name(name), Some("standard"));
+            assert_eq!(registry.font_source(name), Some(FontSource::Custom));
+        }
+    }
+
+    #[test]
+    fn test_lookup_ignores_case_and_separators() {
+        let registry = FontRegistry::new();
+        assert_eq!(registry.resolve_name("Doom"), Some("Doom"));
+        assert_eq!(registry.resolve_name("doom"), Some("Doom"));
+        assert_eq!(registry.resolve_name("ansi shadow"), Some("ANSI Shadow"));
+        assert_eq!(registry.resolve_name("ansi_shadow"), Some("ANSI Shadow"));
+        assert_eq!(registry.resolve_name("big-money-ne"), Some("Big Money-ne"));
+        assert_eq!(registry.resolve_name("3d ascii"), Some("3D-ASCII"));
+        assert_eq!(registry.resolve_name("nope"), None);
+        assert!(registry.has_font("STANDARD"));
+        assert!(std::ptr::eq(
+            registry.get("small").unwrap(),
+            registry.get("Small").unwrap()
+        ));
+    }
+
+    #[test]
+    fn test_all_bundled_fonts_parse() {
+        // Listed fonts are no longer validated at startup, so guard them here
+        let registry = FontRegistry::new();
+        for name in registry.list_fonts() {
+            assert!(registry.get(name).is_some(), "{name} failed to parse");
+        }
+    }
+
+    #[test]
+    fn test_bundled_font_clock_coverage() {
+        let registry = FontRegistry::new();
+        let mut uncovered = Vec::new();
+        for name in registry.list_fonts() {
+            let font = registry.get(name).unwrap();
+            let missing = font.missing_chars(CLOCK_CHARS);
+            assert_eq!(font.covers(CLOCK_CHARS), missing.is_empty());
+            if !missing.is_empty() {
+                uncovered.push(format!("{name}: {missing:?}"));
+            }
+        }
+        if !uncovered.is_empty() {
+            eprintln!("Fonts missing clock characters:\n{}", uncovered.join("\n"));
+        }
+        // The default and fallback fonts must be able to draw any time
+        for name in ["Standard", "Small", "Mini"] {
+            assert!(registry.get(name).unwrap().covers(CLOCK_CHARS), "{name}");
+        }
+    }
+}
@@ -0,0 +1,556 @@
+//! Ratatui widget for the sigye clock.
+//!
+//! [`ClockWidget`] draws a time string in a big ASCII art font into any area of a
+//! buffer, with the same themes, animations and blinking as the sigye app.
+
+use std::collections::HashSet;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::Color,
+    widgets::{StatefulWidget, Widget},
+};
+use sigye_core::{
+    AnimationContext, AnimationStyle, BlinkMode, ClockAlign, ColorTheme, GradientAngle,
+    MIN_GLYPH_CONTRAST, animate_line, boost_contrast, colon_fade_intensity, color_to_rgb,
+    is_colon_visible, is_typewriter_revealed, mix_rgb, wobble_offset,
+};
+use sigye_fonts::{Font, FontRegistry, TextLayout};
+
+/// How far glyph shadows darken the fill color.
+const SHADOW_DARKNESS: f32 = 0.6;
+
+/// Big-font clock text, centered in its area (top-aligned if it is too short).
+///
+/// Spaces in the glyphs are skipped, so whatever is already in the buffer shows through.
+#[derive(Debug, Clone)]
+pub struct ClockWidget<'a> {
+    font: &'a Font,
+    text: &'a str,
+    theme: ColorTheme,
+    gradient_angle: GradientAngle,
+    blink_mode: BlinkMode,
+    anim: AnimationContext,
+    separator: char,
+    letter_spacing: usize,
+    rtl: bool,
+    align: ClockAlign,
+    colon_blink_period_ms: u64,
+    colon_on_fraction: f32,
+    colon_fade: bool,
+    fill: (u8, u8, u8),
+    shadow: bool,
+    contrast_boost: bool,
+    theme_transition: Option<(ColorTheme, f32)>,
+    brightness: f32,
+    typewriter_from: usize,
+    typewriter_progress: f32,
+}
+
+/// Where a [`ClockWidget`] was drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClockWidgetState {
+    /// Area covered by the text in the last render, clipped to the widget's area.
+    pub area: Rect,
+}
+
+impl<'a> ClockWidget<'a> {
+    /// Create a widget drawing `text` in a font.
+    pub fn new(font: &'a Font, text: &'a str) -> Self {
+        Self {
+            font,
+            text,
+            theme: ColorTheme::default(),
+            gradient_angle: GradientAngle::default(),
+            blink_mode: BlinkMode::default(),
+            anim: AnimationContext::default(),
+            separator: ':',
+            letter_spacing: 0,
+            rtl: false,
+            align: ClockAlign::default(),
+            colon_blink_period_ms: 1000,
+            colon_on_fraction: 0.5,
+            colon_fade: false,
+            fill: (0, 0, 0),
+            shadow: false,
+            contrast_boost: false,
+            theme_transition: None,
+            brightness: 1.0,
+            typewriter_from: 0,
+            typewriter_progress: 1.0,
+        }
+    }
+
+    /// Create a widget drawing `text` in a registered font, or the default font if missing.
+    pub fn from_registry(registry: &'a FontRegistry, font_name: &str, text: &'a str) -> Self {
+        Self::new(registry.get_or_default(font_name), text)
+    }
+
+    /// Set the color theme.
+    pub fn theme(mut self, theme: ColorTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Set the direction dynamic gradients run in.
+    pub fn gradient_angle(mut self, angle: GradientAngle) -> Self {
+        self.gradient_angle = angle;
+        self
+    }
+
+    /// Set which parts of the text blink.
+    pub fn blink_mode(mut self, blink_mode: BlinkMode) -> Self {
+        self.blink_mode = blink_mode;
+        self
+    }
+
+    /// Set the animation parameters for this frame.
+    pub fn animation(mut self, anim: AnimationContext) -> Self {
+        self.anim = anim;
+        self
+    }
+
+    /// Set the character separating hours, minutes and seconds.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Set the extra columns between glyphs.
+    pub fn letter_spacing(mut self, spacing: usize) -> Self {
+        self.letter_spacing = spacing;
+        self
+    }
+
+    /// Lay the text out right to left (defaults to left to right, whatever the font says).
+    pub fn rtl(mut self, rtl: bool) -> Self {
+        self.rtl = rtl;
+        self
+    }
+
+    /// Set whether the text is centered as a whole or on its first separator.
+    pub fn align(mut self, align: ClockAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Set the separator blink period and the fraction of it the separator is shown.
+    pub fn colon_blink(mut self, period_ms: u64, on_fraction: f32) -> Self {
+        self.colon_blink_period_ms = period_ms;
+        self.colon_on_fraction = on_fraction;
+        self
+    }
+
+    /// Fade the blinking separator toward the fill color instead of hiding it.
+    pub fn colon_fade(mut self, fade: bool) -> Self {
+        self.colon_fade = fade;
+        self
+    }
+
+    /// Set the color behind the clock, which fades and shadows are mixed toward.
+    pub fn fill(mut self, (r, g, b): (u8, u8, u8)) -> Self {
+        self.fill = (r, g, b);
+        self
+    }
+
+    /// Draw a drop shadow one cell down and right of the glyphs.
+    pub fn shadow(mut self, shadow: bool) -> Self {
+        self.shadow = shadow;
+        self
+    }
+
+    /// Lighten or darken glyphs that would be hard to read against the background
+    /// color already in the buffer under them.
+    pub fn contrast_boost(mut self, boost: bool) -> Self {
+        self.contrast_boost = boost;
+        self
+    }
+
+    /// Blend from a previous theme into the current one, `progress` (0.0 to 1.0) of the way.
+    pub fn theme_transition(mut self, from: ColorTheme, progress: f32) -> Self {
+        self.theme_transition = Some((from, progress));
+        self
+    }
+
+    /// Fade the glyphs toward the fill color, from invisible (0.0) to full color (1.0).
+    pub fn brightness(mut self, brightness: f32) -> Self {
+        self.brightness = brightness;
+        self
+    }
+
+    /// Reveal the text from the `from`th character onward, `progress` (0.0 to 1.0) of the way.
+    pub fn typewriter(mut self, from: usize, progress: f32) -> Self {
+        self.typewriter_from = from;
+        self.typewriter_progress = progress;
+        self
+    }
+}
+
+impl Widget for ClockWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        StatefulWidget::render(self, area, buf, &mut ClockWidgetState::default());
+    }
+}
+
+impl StatefulWidget for ClockWidget<'_> {
+    type State = ClockWidgetState;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut ClockWidgetState) {
+        let font = self.font;
+        let anim = &self.anim;
+        let blink_mode = self.blink_mode;
+        let colon_visible = is_colon_visible(
+            anim.elapsed_ms,
+            self.colon_blink_period_ms,
+            self.colon_on_fraction,
+        );
+        // A fading colon is dimmed toward the background instead of hidden
+        let colon_fade = (blink_mode == BlinkMode::Colon && self.colon_fade)
+            .then(|| colon_fade_intensity(anim.elapsed_ms, self.colon_blink_period_ms));
+
+        // Separators and decimal points missing from the font become gaps
+        let separator = self.separator;
+        let text: String = self
+            .text
+            .chars()
+            .map(|ch| {
+                let punctuation = ch == '.' || ch == separator;
+                if punctuation && !font.chars.contains_key(&ch) {
+                    ' '
+                } else {
+                    ch
+                }
+            })
+            .collect();
+        let rtl = self.rtl;
+        let spacing = self.letter_spacing;
+        let lines = font.render_text_with(
+            &text,
+            TextLayout {
+                rtl,
+                letter_spacing: spacing,
+            },
+        );
+        let height = lines.len();
+        let width = lines.first().map(|s| s.chars().count()).unwrap_or(0);
+
+        // Characters in the order they appear on screen
+        let visual_chars: Vec<char> = if rtl {
+            text.chars().rev().collect()
+        } else {
+            text.chars().collect()
+        };
+
+        // Typewriter reveal starts at the column of the first changed character.
+        // Right to left, the changed characters come first on screen.
+        let typewriter_column: usize = if rtl {
+            0
+        } else {
+            text.chars()
+                .take(self.typewriter_from)
+                .map(|ch| font.char_width(ch) + spacing)
+                .sum()
+        };
+
+        // Build separator position mask for blink effect
+        let colon_positions: Vec<bool> = if blink_mode == BlinkMode::Colon {
+            separator_mask(font, &visual_chars, separator, spacing, width)
+        } else {
+            vec![]
+        };
+
+        let text_width = width as u16;
+        let start_x = match self.align {
+            ClockAlign::Center => None,
+            ClockAlign::ColonCenter => {
+                separator_center(font, &visual_chars, separator, spacing, rtl).map(|center| {
+                    // Keep the whole text inside the area when it fits
+                    let left = (area.width / 2).saturating_sub(center as u16);
+                    area.x + left.min(area.width.saturating_sub(text_width))
+                })
+            }
+        }
+        .unwrap_or(area.x + (area.width.saturating_sub(text_width)) / 2);
+        let start_y = area.y + (area.height.saturating_sub(height as u16)) / 2;
+
+        // Visible glyph cells, drawn after their shadows
+        let mut glyph_cells: Vec<(Position, char, Color)> = Vec::new();
+        // Wobble nudges whole columns up or down, but only where the column stays in the area
+        let text_top = i32::from(start_y);
+        let text_bottom = text_top + height as i32;
+        let column_offset = |x: usize| -> i16 {
+            if anim.style != AnimationStyle::Wobble {
+                return 0;
+            }
+            let offset = i32::from(wobble_offset(x, anim.elapsed_ms, anim.speed));
+            let fits = text_top + offset >= i32::from(area.top())
+                && text_bottom + offset <= i32::from(area.bottom());
+            if fits { offset as i16 } else { 0 }
+        };
+
+        for (line_idx, line) in lines.iter().enumerate() {
+            let y_pos = start_y + line_idx as u16;
+            if y_pos >= area.bottom() {
+                break;
+            }
+
+            // Animate the whole row at once
+            let mut base = row_colors(self.theme, self.gradient_angle, line_idx, width, height);
+            if let Some((from, progress)) = self.theme_transition {
+                let from = row_colors(from, self.gradient_angle, line_idx, width, height);
+                base = blend_row_colors(&from, &base, progress);
+            }
+            let colors = animate_line(&base, anim);
+
+            for (char_idx, ch) in line.chars().enumerate() {
+                // Skip spaces to preserve background transparency
+                if ch == ' ' {
+                    continue;
+                }
+
+                let x_pos = start_x + char_idx as u16;
+                if x_pos >= area.right() {
+                    continue;
+                }
+
+                let y_pos = y_pos.saturating_add_signed(column_offset(char_idx));
+
+                // Apply blink by skipping colon (or all) characters during "off" phase
+                let is_colon = colon_positions.get(char_idx).copied().unwrap_or(false);
+                let should_hide = match blink_mode {
+                    BlinkMode::None => false,
+                    BlinkMode::Colon => is_colon && colon_fade.is_none() && !colon_visible,
+                    BlinkMode::All => !colon_visible,
+                };
+                if should_hide {
+                    continue;
+                }
+
+                // Typewriter hides columns that haven't been typed in yet
+                if !is_typewriter_revealed(
+                    char_idx,
+                    typewriter_column,
+                    width,
+                    self.typewriter_progress,
+                ) {
+                    continue;
+                }
+
+                if let Some(&color) = colors.get(char_idx) {
+                    // Fade on top of the animated color
+                    let color = match colon_fade {
+                        Some(intensity) if is_colon => {
+                            let (r, g, b) = mix_rgb(self.fill, color_to_rgb(color), intensity);
+                            Color::Rgb(r, g, b)
+                        }
+                        _ => color,
+                    };
+                    let color = if self.brightness < 1.0 {
+                        let (r, g, b) = mix_rgb(self.fill, color_to_rgb(color), self.brightness);
+                        Color::Rgb(r, g, b)
+                    } else {
+                        color
+                    };
+                    glyph_cells.push((Position::new(x_pos, y_pos), ch, color));
+                }
+            }
+        }
+
+        // Drop shadow one cell down and right, under the glyphs and inside the area
+        if self.shadow {
+            let (r, g, b) = mix_rgb(self.fill, (0, 0, 0), SHADOW_DARKNESS);
+            let shadow = Color::Rgb(r, g, b);
+            let glyph_positions: HashSet<Position> = glyph_cells
+                .iter()
+                .map(|(position, _, _)| *position)
+                .collect();
+            for (position, _, _) in &glyph_cells {
+                let shadow_position = Position::new(position.x + 1, position.y + 1);
+                if !area.contains(shadow_position) || glyph_positions.contains(&shadow_position) {
+                    continue;
+                }
+                if let Some(cell) = buf.cell_mut(shadow_position) {
+                    cell.set_char(' ');
+                    cell.set_bg(shadow);
+                }
+            }
+        }
+
+        for (position, ch, color) in glyph_cells {
+            if let Some(cell) = buf.cell_mut(position) {
+                // Only cells with a background color are checked; the terminal's own is unknown
+                let color = if self.contrast_boost && cell.bg != Color::Reset {
+                    let (r, g, b) = boost_contrast(
+                        color_to_rgb(color),
+                        color_to_rgb(cell.bg),
+                        MIN_GLYPH_CONTRAST,
+                    );
+                    Color::Rgb(r, g, b)
+                } else {
+                    color
+                };
+                cell.set_char(ch);
+                cell.set_fg(color);
+            }
+        }
+
+        state.area = Rect::new(start_x, start_y, text_width, height as u16).intersection(area);
+    }
+}
+
+/// Map columns of rendered text back to separator characters, for blinking them.
+/// `chars` are in the order they appear on screen.
+fn separator_mask(
+    font: &Font,
+    chars: &[char],
+    separator: char,
+    spacing: usize,
+    width: usize,
+) -> Vec<bool> {
+    let mut mask = vec![false; width];
+    let mut x_pos = 0;
+    for &ch in chars {
+        let char_width = font.char_width(ch);
+        if ch == separator {
+            for i in 0..char_width {
+                if x_pos + i < mask.len() {
+                    mask[x_pos + i] = true;
+                }
+            }
+        }
+        x_pos += char_width + spacing;
+    }
+    mask
+}
+
+/// Get the column at the middle of the first separator (in reading order) of rendered text.
+/// `chars` are in the order they appear on screen.
+fn separator_center(
+    font: &Font,
+    chars: &[char],
+    separator: char,
+    spacing: usize,
+    rtl: bool,
+) -> Option<usize> {
+    let index = if rtl {
+        chars.iter().rposition(|&ch| ch == separator)?
+    } else {
+        chars.iter().position(|&ch| ch == separator)?
+    };
+    let before: usize = chars[..index]
+        .iter()
+        .map(|&ch| font.char_width(ch) + spacing)
+        .sum();
+    Some(before + font.char_width(separator) / 2)
+}
+
+/// Get a theme's base color for each column of a row.
+pub fn row_colors(
+    theme: ColorTheme,
+    angle: GradientAngle,
+    y: usize,
+    width: usize,
+    height: usize,
+) -> Vec<Color> {
+    if theme.is_dynamic() {
+        (0..width)
+            .map(|x| theme.color_at_angle(x, y, width, height, angle))
+            .collect()
+    } else {
+        vec![theme.color(); width]
+    }
+}
+
+/// Mix two rows of colors cell by cell, from `from` at `t = 0.0` to `to` at `t = 1.0`.
+pub fn blend_row_colors(from: &[Color], to: &[Color], t: f32) -> Vec<Color> {
+    from.iter()
+        .zip(to)
+        .map(|(&from, &to)| {
+            let (r, g, b) = mix_rgb(color_to_rgb(from), color_to_rgb(to), t);
+            Color::Rgb(r, g, b)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_separator_mask_tracks_separator() {
+        let registry = FontRegistry::new();
+        let font = registry.get_or_default("Standard");
+        let chars: Vec<char> = "12.34".chars().collect();
+        let width: usize = chars.iter().map(|&ch| font.char_width(ch)).sum();
+
+        let mask = separator_mask(font, &chars, '.', 0, width);
+        let start = font.char_width('1') + font.char_width('2');
+        let end = start + font.char_width('.');
+        assert!(mask[start..end].iter().all(|&on| on));
+        assert_eq!(mask.iter().filter(|&&on| on).count(), end - start);
+
+        // A colon separator leaves the point alone
+        assert!(!separator_mask(font, &chars, ':', 0, width).contains(&true));
+    }
+
+    #[test]
+    fn test_renders_centered_and_reports_area() {
+        let registry = FontRegistry::new();
+        let font = registry.get_or_default("Standard");
+        let area = Rect::new(0, 0, 80, 10);
+        let mut buf = Buffer::empty(area);
+        let mut state = ClockWidgetState::default();
+
+        let widget = ClockWidget::from_registry(&registry, "Standard", "12:34");
+        StatefulWidget::render(widget, area, &mut buf, &mut state);
+
+        let lines = font.render_text("12:34");
+        let (width, height) = (lines[0].chars().count() as u16, lines.len() as u16);
+        assert_eq!(
+            state.area,
+            Rect::new((80 - width) / 2, (10 - height) / 2, width, height)
+        );
+        // Cells outside the text are left alone
+        assert_eq!(buf[(0, 0)].symbol(), " ");
+        assert!(
+            (state.area.top()..state.area.bottom())
+                .any(|y| (state.area.left()..state.area.right())
+                    .any(|x| buf[(x, y)].symbol() != " "))
+        );
+    }
+
+    #[test]
+    fn test_colon_center_pins_separator() {
+        let registry = FontRegistry::new();
+        let font = registry.get_or_default("Standard");
+        let area = Rect::new(0, 0, 80, 10);
+        let colon_column = |text: &str, align: ClockAlign| {
+            let mut buf = Buffer::empty(area);
+            let mut state = ClockWidgetState::default();
+            let widget = ClockWidget::new(font, text).align(align);
+            StatefulWidget::render(widget, area, &mut buf, &mut state);
+            let before: usize = text
+                .chars()
+                .take_while(|&ch| ch != ':')
+                .map(|ch| font.char_width(ch))
+                .sum();
+            state.area.x as usize + before
+        };
+
+        // Centering the whole text moves the colon when the hour gains a digit
+        assert_ne!(
+            colon_column("1:05 PM", ClockAlign::Center),
+            colon_column("12:05 PM", ClockAlign::Center)
+        );
+        let pinned = colon_column("1:05 PM", ClockAlign::ColonCenter);
+        assert_eq!(pinned, colon_column("12:05 PM", ClockAlign::ColonCenter));
+        assert_eq!(pinned + font.char_width(':') / 2, 40);
+
+        // Without a separator the text is centered as usual
+        assert_eq!(
+            colon_column("1205", ClockAlign::ColonCenter),
+            colon_column("1205", ClockAlign::Center)
+        );
+    }
+}
@@ -0,0 +1,175 @@
+//! Analog clock face rendering.
+
+use std::f32::consts::TAU;
+
+use chrono::{NaiveTime, Timelike};
+use ratatui::{
+    buffer::Buffer,
+    layout::{Position, Rect},
+};
+use sigye_core::{AnimationContext, ColorTheme, GradientAngle, apply_animation};
+
+/// Hand lengths as a fraction of the face radius.
+const HOUR_HAND_LENGTH: f32 = 0.5;
+const MINUTE_HAND_LENGTH: f32 = 0.8;
+const SECOND_HAND_LENGTH: f32 = 0.9;
+
+/// Analog clock face sized to fit an area.
+struct Face {
+    area: Rect,
+    center_x: i32,
+    center_y: i32,
+    /// Horizontal radius in columns (twice the rows, since cells are about 1:2).
+    radius_x: f32,
+    /// Vertical radius in rows.
+    radius_y: f32,
+}
+
+impl Face {
+    /// Fit the largest face into the area, or `None` if it is too small to draw.
+    fn fit(area: Rect) -> Option<Self> {
+        let radius_y = (area.height.min(area.width / 2).saturating_sub(1) / 2) as f32;
+        if radius_y < 2.0 {
+            return None;
+        }
+        Some(Self {
+            area,
+            center_x: i32::from(area.x + area.width / 2),
+            center_y: i32::from(area.y + area.height / 2),
+            radius_x: radius_y * 2.0,
+            radius_y,
+        })
+    }
+
+    /// Get the cell at a fraction of a full turn (0.0 is 12 o'clock) and of the radius.
+    fn point(&self, turn: f32, length: f32) -> (i32, i32) {
+        let angle = turn * TAU;
+        (
+            self.center_x + (angle.sin() * self.radius_x * length).round() as i32,
+            self.center_y - (angle.cos() * self.radius_y * length).round() as i32,
+        )
+    }
+
+    /// Get the width of the face in columns.
+    fn width(&self) -> u16 {
+        self.radius_x as u16 * 2 + 1
+    }
+
+    /// Get the leftmost column of the face.
+    fn left(&self) -> u16 {
+        (self.center_x - self.radius_x as i32).max(0) as u16
+    }
+}
+
+/// Render an analog clock face with hour, minute and second hands.
+/// Returns the columns the face spans as `(x, width)`, or `None` if it didn't fit.
+pub fn render(
+    buf: &mut Buffer,
+    area: Rect,
+    time: NaiveTime,
+    theme: ColorTheme,
+    angle: GradientAngle,
+    anim: &AnimationContext,
+) -> Option<(u16, u16)> {
+    let face = Face::fit(area)?;
+    let width = face.width() as usize;
+    let height = face.radius_y as usize * 2 + 1;
+    let left = i32::from(face.left());
+    let top = face.center_y - face.radius_y as i32;
+
+    // Color a cell by its position within the face so gradients span the whole clock
+    let plot = |buf: &mut Buffer, (x, y): (i32, i32), ch: char| {
+        if x < i32::from(face.area.left())
+            || x >= i32::from(face.area.right())
+            || y < i32::from(face.area.top())
+            || y >= i32::from(face.area.bottom())
+        {
+            return;
+        }
+        let col = (x - left).max(0) as usize;
+        let row = (y - top).max(0) as usize;
+        let base_color = if theme.is_dynamic() {
+            theme.color_at_angle(col, row, width, height, angle)
+        } else {
+            theme.color()
+        };
+        if let Some(cell) = buf.cell_mut(Position::new(x as u16, y as u16)) {
+            cell.set_char(ch);
+            cell.set_fg(apply_animation(base_color, anim, col, width));
+        }
+    };
+
+    // Tick marks, with larger ones at the quarter hours
+    for hour in 0..12 {
+        let ch = if hour % 3 == 0 { '●' } else { '•' };
+        plot(buf, face.point(hour as f32 / 12.0, 1.0), ch);
+    }
+
+    let seconds = time.second() as f32;
+    let minutes = time.minute() as f32 + seconds / 60.0;
+    let hours = (time.hour() % 12) as f32 + minutes / 60.0;
+
+    // Hands, drawn shortest to longest so the second hand stays on top
+    let hands = [
+        (hours / 12.0, HOUR_HAND_LENGTH, Some('█')),
+        (minutes / 60.0, MINUTE_HAND_LENGTH, None),
+        (seconds / 60.0, SECOND_HAND_LENGTH, Some('·')),
+    ];
+    for (turn, length, ch) in hands {
+        let end = face.point(turn, length);
+        let ch = ch.unwrap_or_else(|| line_char(turn));
+        for point in line_points((face.center_x, face.center_y), end) {
+            plot(buf, point, ch);
+        }
+    }
+
+    // Hub
+    plot(buf, (face.center_x, face.center_y), '◉');
+
+    Some((face.left(), face.width()))
+}
+
+/// Pick a line-drawing character matching the on-screen direction of a hand.
+/// The face is stretched to cancel out the cell aspect, so this is the true angle.
+fn line_char(turn: f32) -> char {
+    let angle = turn * TAU;
+    let degrees = angle
+        .cos()
+        .atan2(angle.sin())
+        .to_degrees()
+        .rem_euclid(180.0);
+    match degrees {
+        d if !(22.5..157.5).contains(&d) => '─',
+        d if d < 67.5 => '╱',
+        d if d < 112.5 => '│',
+        _ => '╲',
+    }
+}
+
+/// Get the cells along a line using Bresenham's algorithm.
+fn line_points((x0, y0): (i32, i32), (x1, y1): (i32, i32)) -> Vec<(i32, i32)> {
+    let dx = (x1 - x0).abs();
+    let dy = -(y1 - y0).abs();
+    let step_x = if x0 < x1 { 1 } else { -1 };
+    let step_y = if y0 < y1 { 1 } else { -1 };
+
+    let mut points = Vec::new();
+    let (mut x, mut y) = (x0, y0);
+    let mut err = dx + dy;
+    loop {
+        points.push((x, y));
+        if x == x1 && y == y1 {
+            break;
+        }
+        let err2 = 2 * err;
+        if err2 >= dy {
+            err += dy;
+            x += step_x;
+        }
+        if err2 <= dx {
+            err += dx;
+            y += step_y;
+        }
+    }
+    points
+}
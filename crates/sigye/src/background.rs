@@ -1,42 +1,145 @@
 //! Background animation rendering for the sigye clock.
 
+use std::rc::Rc;
+use std::time::Instant;
+
 use ratatui::{
     Frame,
+    buffer::Buffer,
+    layout::{Position, Rect},
     style::{Color, Style},
     text::{Line, Span},
     widgets::Paragraph,
 };
-use sigye_core::{AnimationSpeed, BackgroundStyle};
+use sigye_config::Config;
+use sigye_core::{
+    AnimationSpeed, BackgroundStyle, ColorTheme, ScrollDirection, animation_phase_delta,
+    animation_phase_ms, color_to_rgb, dim, hsl_to_rgb, mix_rgb, to_channel,
+};
 
+use crate::clock::Clock;
 use crate::system_metrics::SystemMetrics;
 
 /// Characters used for starfield background.
 const STAR_CHARS: &[char] = &['.', '*', '+', '·', '✦', '✧'];
 
-/// Characters used for matrix rain.
+/// Characters used for matrix rain when no charset is configured.
 const MATRIX_CHARS: &[char] = &[
     'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
     'チ', 'ツ', 'テ', 'ト', '0', '1', '2', '3', '4', '5', '6', '7', '8', '9',
 ];
 
-/// State for a single matrix rain column.
+/// Characters used for falling snow.
+const SNOW_CHARS: &[char] = &['·', '*', '❄'];
+
+/// Maximum horizontal sway of a snowflake in cells.
+const SNOW_SWAY: f32 = 1.0;
+
+/// Fraction of cells alive when seeding a Game of Life grid.
+const LIFE_SEED_DENSITY: u64 = 25;
+
+/// State for a single matrix rain (or snowfall) column.
 #[derive(Debug, Clone)]
 struct MatrixColumn {
     /// Current y position of the raindrop head.
     y: f32,
+    /// Horizontal offset from the column (used by snow sway).
+    x_offset: f32,
     /// Speed multiplier for this column.
     speed: f32,
-    /// Length of the trail.
+    /// Length of the trail (rows to wait above the screen for snow).
     trail_length: usize,
     /// Seed for character generation.
     char_seed: usize,
 }
 
+/// Small deterministic PRNG (SplitMix64) for seeded background patterns.
+#[derive(Debug, Clone)]
+struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Create a generator from a seed.
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Get the next random value.
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Get a random index in `0..n`.
+    fn next_index(&mut self, n: usize) -> usize {
+        (self.next_u64() % n.max(1) as u64) as usize
+    }
+}
+
+/// Hash a position and frame number into a deterministic value for the given seed.
+fn hash_position(seed: u64, x: u16, y: u16, frame_num: u64) -> u64 {
+    let key = seed
+        ^ u64::from(x).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ u64::from(y).wrapping_mul(0xC2B2_AE3D_27D4_EB4F)
+        ^ frame_num.wrapping_mul(0x1656_67B1_9E37_79F9);
+    SeededRng::new(key).next_u64()
+}
+
 /// Background animation state.
 #[derive(Debug)]
 pub struct BackgroundState {
+    /// Source of the background's time.
+    clock: Rc<dyn Clock>,
+    /// When the background started moving.
+    start: Instant,
+    /// Background time frozen while paused or held, if stopped.
+    stopped_at: Option<u64>,
+    /// How far the background lags its clock from being stopped, in milliseconds.
+    stopped_ms: u64,
+    /// Whether the pause key froze the background.
+    paused: bool,
+    /// Whether the background is held still, e.g. behind the settings dialog.
+    held: bool,
+    /// Seed for the background patterns.
+    seed: u64,
+    /// Fraction of starfield cells showing a star.
+    star_density: f32,
+    /// Configured matrix charset (kept to detect changes).
+    matrix_charset: String,
+    /// Glyphs the matrix rain picks from.
+    matrix_chars: Vec<char>,
+    /// Matrix rain trail color.
+    matrix_color: [u8; 3],
+    /// Shortest and longest matrix rain trails in rows.
+    matrix_trail: (usize, usize),
+    /// How far matrix rain heads are lightened toward white.
+    matrix_head_brightness: f32,
+    /// Theme color used for Game of Life cells.
+    theme_color: Color,
+    /// Brightness multiplier applied to all background colors.
+    brightness: f32,
+    /// Direction the gradient background scrolls in.
+    gradient_scroll: ScrollDirection,
+    /// Whether the gradient scrolls the opposite way.
+    gradient_reverse: bool,
+    /// Area kept clear of background glyphs, such as the space behind the clock.
+    exclusion: Option<Rect>,
+    /// Whether the terminal background is dark (stars are drawn dark on light ones).
+    dark_terminal: bool,
     /// Matrix rain column states.
     matrix_columns: Vec<MatrixColumn>,
+    /// Snowfall column states.
+    snow_columns: Vec<MatrixColumn>,
+    /// Game of Life cell ages, row-major (0 = dead, 1 = newborn, 2+ = stable).
+    life_cells: Vec<u8>,
+    /// Number of Game of Life seedings so far (varies each reseed).
+    life_generation: u64,
+    /// Time of the last Game of Life step in milliseconds.
+    life_last_step_ms: u64,
     /// Last known terminal width.
     last_width: u16,
     /// Last known terminal height.
@@ -45,48 +148,171 @@ pub struct BackgroundState {
     last_update_ms: u64,
 }
 
-impl Default for BackgroundState {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
 impl BackgroundState {
-    /// Create a new background state.
-    pub fn new() -> Self {
+    /// Create a new background state, starting from its first frame.
+    pub fn new(clock: Rc<dyn Clock>) -> Self {
         Self {
+            start: clock.instant(),
+            clock,
+            stopped_at: None,
+            stopped_ms: 0,
+            paused: false,
+            held: false,
+            seed: 0,
+            star_density: 0.03,
+            matrix_charset: String::new(),
+            matrix_chars: MATRIX_CHARS.to_vec(),
+            matrix_color: [0, 255, 0],
+            matrix_trail: (4, 11),
+            matrix_head_brightness: 0.78,
+            theme_color: Color::Cyan,
+            brightness: 0.5,
+            gradient_scroll: ScrollDirection::default(),
+            gradient_reverse: false,
+            exclusion: None,
+            dark_terminal: true,
             matrix_columns: Vec::new(),
+            snow_columns: Vec::new(),
+            life_cells: Vec::new(),
+            life_generation: 0,
+            life_last_step_ms: 0,
             last_width: 0,
             last_height: 0,
             last_update_ms: 0,
         }
     }
 
+    /// Apply background-related settings from the configuration, with `theme` the
+    /// theme on screen.
+    pub fn configure(&mut self, config: &Config, theme: ColorTheme) {
+        if config.background_seed != self.seed {
+            self.seed = config.background_seed;
+            // Force columns to be regenerated from the new seed
+            self.matrix_columns.clear();
+            self.snow_columns.clear();
+            self.life_cells.clear();
+            self.life_generation = 0;
+        }
+        self.star_density = config.star_density;
+        if config.matrix_charset != self.matrix_charset {
+            self.matrix_charset = config.matrix_charset.clone();
+            self.matrix_chars = config.matrix_charset.chars().collect();
+            if self.matrix_chars.is_empty() {
+                self.matrix_chars = MATRIX_CHARS.to_vec();
+            }
+        }
+        self.matrix_color = config.matrix_color;
+        let trail = (config.matrix_trail_min, config.matrix_trail_max);
+        if trail != self.matrix_trail {
+            self.matrix_trail = trail;
+            self.matrix_columns.clear();
+        }
+        self.matrix_head_brightness = config.matrix_head_brightness;
+        self.gradient_scroll = config.gradient_scroll;
+        self.gradient_reverse = config.gradient_reverse;
+        self.theme_color = theme.color();
+        self.brightness = config.background_brightness;
+    }
+
     /// Initialize or reinitialize matrix columns for the given dimensions.
     fn init_matrix_columns(&mut self, width: u16, height: u16) {
+        let mut rng = SeededRng::new(self.seed);
+        let (trail_min, trail_max) = self.matrix_trail;
+        let trail_spread = trail_max.saturating_sub(trail_min) + 1;
         self.matrix_columns = (0..width)
-            .map(|x| {
-                let x = x as usize;
-                let stagger = ((x * 7 + 3) % (height as usize * 2)) as f32;
+            .map(|_| {
+                let stagger = rng.next_index(height as usize * 2) as f32;
                 MatrixColumn {
                     // Stagger start positions so columns don't all start at top
                     y: -stagger,
+                    x_offset: 0.0,
                     // Vary speeds between columns
-                    speed: 0.3 + ((x * 13) % 10) as f32 / 15.0,
-                    // Vary trail lengths
-                    trail_length: 4 + (x * 11) % 8,
+                    speed: 0.3 + rng.next_index(10) as f32 / 15.0,
+                    // Vary trail lengths within the configured bounds
+                    trail_length: trail_min + rng.next_index(trail_spread),
                     // Seed for character selection
-                    char_seed: x * 17,
+                    char_seed: rng.next_u64() as usize,
                 }
             })
             .collect();
-        self.last_width = width;
-        self.last_height = height;
+    }
+
+    /// Initialize or reinitialize snowfall columns for the given dimensions.
+    fn init_snow_columns(&mut self, width: u16, height: u16) {
+        let mut rng = SeededRng::new(self.seed ^ 0x5A0F_1A4E);
+        self.snow_columns = (0..width)
+            .map(|_| MatrixColumn {
+                // Spread flakes over several screen heights so only some columns show one
+                y: height as f32 - rng.next_index(height as usize * 3 + 1) as f32,
+                x_offset: 0.0,
+                speed: 0.5 + rng.next_index(10) as f32 / 10.0,
+                trail_length: rng.next_index(height as usize * 2 + 1),
+                char_seed: rng.next_u64() as usize,
+            })
+            .collect();
+    }
+
+    /// Seed the Game of Life grid with random live cells.
+    fn init_life_cells(&mut self, width: u16, height: u16) {
+        let mut rng = SeededRng::new(self.seed ^ self.life_generation.wrapping_mul(0x1F3D_5B79));
+        self.life_generation = self.life_generation.wrapping_add(1);
+        self.life_cells = (0..width as usize * height as usize)
+            .map(|_| u8::from(rng.next_u64() % 100 < LIFE_SEED_DENSITY))
+            .collect();
+    }
+
+    /// Advance the Game of Life by one generation (edges wrap around).
+    fn step_life(&mut self, width: u16, height: u16) {
+        let (w, h) = (width as usize, height as usize);
+        if w == 0 || h == 0 {
+            return;
+        }
+
+        let next: Vec<u8> = (0..w * h)
+            .map(|idx| {
+                let (x, y) = (idx % w, idx / w);
+                let mut neighbors = 0;
+                for dy in [h - 1, 0, 1] {
+                    for dx in [w - 1, 0, 1] {
+                        if (dx, dy) == (0, 0) {
+                            continue;
+                        }
+                        let nidx = ((y + dy) % h) * w + (x + dx) % w;
+                        if self.life_cells[nidx] > 0 {
+                            neighbors += 1;
+                        }
+                    }
+                }
+
+                let age = self.life_cells[idx];
+                match (age > 0, neighbors) {
+                    (true, 2 | 3) => age.saturating_add(1),
+                    (false, 3) => 1,
+                    _ => 0,
+                }
+            })
+            .collect();
+        self.life_cells = next;
+
+        // Reseed when the population dies out
+        if self.life_cells.iter().all(|&age| age == 0) {
+            self.init_life_cells(width, height);
+        }
+    }
+
+    /// Step the Game of Life at the speed-derived interval.
+    fn update_life(&mut self, elapsed_ms: u64, width: u16, height: u16, speed: AnimationSpeed) {
+        let period = speed.life_step_period_ms();
+        if animation_phase_delta(self.life_last_step_ms, elapsed_ms) >= period {
+            self.life_last_step_ms = elapsed_ms;
+            self.step_life(width, height);
+        }
     }
 
     /// Update matrix column positions.
     fn update_matrix(&mut self, elapsed_ms: u64, height: u16, speed: AnimationSpeed) {
-        let delta_ms = elapsed_ms.saturating_sub(self.last_update_ms);
+        // Elapsed time wraps around after long uptimes
+        let delta_ms = animation_phase_delta(self.last_update_ms, elapsed_ms);
         self.last_update_ms = elapsed_ms;
 
         let fall_speed = speed.matrix_fall_speed();
@@ -102,41 +328,189 @@ impl BackgroundState {
         }
     }
 
-    /// Render the background to the frame.
+    /// Update snowfall positions and sway.
+    fn update_snow(&mut self, elapsed_ms: u64, height: u16, speed: AnimationSpeed) {
+        let delta_ms = animation_phase_delta(self.last_update_ms, elapsed_ms);
+        self.last_update_ms = elapsed_ms;
+
+        let fall_speed = speed.snow_fall_speed();
+        let delta_y = (delta_ms as f32 / 50.0) * fall_speed;
+        let time = elapsed_ms as f32 / 1000.0;
+
+        for col in &mut self.snow_columns {
+            col.y += delta_y * col.speed;
+            // Each flake sways with its own phase
+            let phase = (col.char_seed % 1000) as f32;
+            col.x_offset = (time * col.speed + phase).sin() * SNOW_SWAY;
+            // Respawn above the screen once the flake has fallen past the bottom
+            if col.y > height as f32 {
+                col.y = -(col.trail_length as f32);
+                col.char_seed = col.char_seed.wrapping_add(1);
+            }
+        }
+    }
+
+    /// Forget the cached dimensions so column and cell states are rebuilt on the next
+    /// render, such as after the terminal is resized.
+    pub fn invalidate(&mut self) {
+        self.last_width = 0;
+        self.last_height = 0;
+    }
+
+    /// Keep an area clear of background glyphs, or stop doing so with `None`.
+    pub fn set_exclusion(&mut self, exclusion: Option<Rect>) {
+        self.exclusion = exclusion;
+    }
+
+    /// Set whether the terminal background is dark, to pick visible colors.
+    pub fn set_dark_terminal(&mut self, dark: bool) {
+        self.dark_terminal = dark;
+    }
+
+    /// Get the time the background has been moving, which stands still while it
+    /// is paused or held.
+    pub fn elapsed_ms(&self) -> u64 {
+        match self.stopped_at {
+            Some(stopped_at) => stopped_at,
+            None => {
+                let elapsed_ms = self.clock.elapsed(self.start).as_millis() as u64;
+                // Bounded so backgrounds doing float math stay smooth after days of uptime
+                animation_phase_delta(self.stopped_ms, animation_phase_ms(elapsed_ms))
+            }
+        }
+    }
+
+    /// Freeze or resume the background along with the other animations.
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.update_stopped();
+    }
+
+    /// Hold the background still, e.g. behind the settings dialog.
+    pub fn hold(&mut self) {
+        self.held = true;
+        self.update_stopped();
+    }
+
+    /// Let the background move again from where it was held, unless it is paused.
+    pub fn release(&mut self) {
+        self.held = false;
+        self.update_stopped();
+    }
+
+    /// Check if the background is held still.
+    pub fn is_held(&self) -> bool {
+        self.held
+    }
+
+    /// Stop or restart background time to match the pause and hold.
+    fn update_stopped(&mut self) {
+        let stopped = self.paused || self.held;
+        if stopped && self.stopped_at.is_none() {
+            self.stopped_at = Some(self.elapsed_ms());
+        } else if !stopped && let Some(stopped_at) = self.stopped_at.take() {
+            let elapsed_ms = self.clock.elapsed(self.start).as_millis() as u64;
+            self.stopped_ms = animation_phase_delta(stopped_at, elapsed_ms);
+        }
+    }
+
+    /// Render the background into an area of the frame.
     pub fn render(
         &mut self,
         frame: &mut Frame,
+        area: Rect,
         style: BackgroundStyle,
-        elapsed_ms: u64,
         speed: AnimationSpeed,
         metrics: Option<&SystemMetrics>,
     ) {
-        if style == BackgroundStyle::None {
+        // Zero brightness hides the background without changing the style
+        if style == BackgroundStyle::None || self.brightness <= 0.0 {
             return;
         }
 
-        let area = frame.area();
+        let elapsed_ms = self.elapsed_ms();
+        self.render_style(frame, area, style, elapsed_ms, speed, metrics);
+
+        // Blank the excluded area, keeping any fill color behind it
+        if let Some(exclusion) = self.exclusion {
+            let buf = frame.buffer_mut();
+            let clear = exclusion.intersection(area);
+            for y in clear.top()..clear.bottom() {
+                for x in clear.left()..clear.right() {
+                    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                        cell.set_char(' ');
+                    }
+                }
+            }
+        }
+
+        // Dim everything drawn so far (only the background at this point)
+        if self.brightness < 1.0 {
+            let buf = frame.buffer_mut();
+            for y in area.top()..area.bottom() {
+                for x in area.left()..area.right() {
+                    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                        cell.set_fg(dim(cell.fg, self.brightness));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Render the given background style to the frame at full brightness.
+    fn render_style(
+        &mut self,
+        frame: &mut Frame,
+        area: Rect,
+        style: BackgroundStyle,
+        elapsed_ms: u64,
+        speed: AnimationSpeed,
+        metrics: Option<&SystemMetrics>,
+    ) {
         let width = area.width;
         let height = area.height;
 
         // Handle reactive backgrounds separately
         if style.is_reactive() {
             if let Some(m) = metrics {
-                self.render_reactive(frame, style, elapsed_ms, speed, m);
+                self.render_reactive(frame, area, style, elapsed_ms, speed, m);
             }
             return;
         }
 
-        // Reinitialize if dimensions changed
-        if style == BackgroundStyle::MatrixRain
-            && (width != self.last_width || height != self.last_height)
-        {
-            self.init_matrix_columns(width, height);
+        // Reinitialize column states if dimensions changed
+        if width != self.last_width || height != self.last_height {
+            self.matrix_columns.clear();
+            self.snow_columns.clear();
+            self.life_cells.clear();
+            self.last_width = width;
+            self.last_height = height;
         }
 
-        // Update matrix state
-        if style == BackgroundStyle::MatrixRain {
-            self.update_matrix(elapsed_ms, height, speed);
+        // Update falling column state
+        match style {
+            BackgroundStyle::MatrixRain => {
+                if self.matrix_columns.is_empty() {
+                    self.init_matrix_columns(width, height);
+                    self.last_update_ms = elapsed_ms;
+                }
+                self.update_matrix(elapsed_ms, height, speed);
+            }
+            BackgroundStyle::Snow => {
+                if self.snow_columns.is_empty() {
+                    self.init_snow_columns(width, height);
+                    self.last_update_ms = elapsed_ms;
+                }
+                self.update_snow(elapsed_ms, height, speed);
+            }
+            BackgroundStyle::GameOfLife => {
+                if self.life_cells.is_empty() {
+                    self.init_life_cells(width, height);
+                    self.life_last_step_ms = elapsed_ms;
+                }
+                self.update_life(elapsed_ms, width, height, speed);
+            }
+            _ => {}
         }
 
         let lines: Vec<Line> = (0..height)
@@ -152,6 +526,7 @@ impl BackgroundState {
     }
 
     /// Render a single background character at the given position.
+    #[allow(clippy::too_many_arguments)]
     fn render_char(
         &self,
         x: u16,
@@ -169,6 +544,8 @@ impl BackgroundState {
             BackgroundStyle::GradientWave => {
                 self.render_gradient_char(x, y, width, height, elapsed_ms, speed)
             }
+            BackgroundStyle::Snow => self.render_snow_char(x, y),
+            BackgroundStyle::GameOfLife => self.render_life_char(x, y, width),
             // Reactive backgrounds are handled separately in render_reactive()
             BackgroundStyle::SystemPulse
             | BackgroundStyle::ResourceWave
@@ -185,27 +562,27 @@ impl BackgroundState {
         elapsed_ms: u64,
         speed: AnimationSpeed,
     ) -> Span<'static> {
-        let x = x as usize;
-        let y = y as usize;
         let period = speed.star_twinkle_period_ms();
         let frame_num = elapsed_ms / period;
 
-        // Use deterministic "random" based on position and time
-        let seed = (x.wrapping_mul(31))
-            .wrapping_add(y.wrapping_mul(17))
-            .wrapping_add(frame_num as usize);
+        // Star placement is fixed for a seed; brightness twinkles over time
+        let star = hash_position(self.seed, x, y, 0);
 
-        // Only show stars at ~3% of positions
-        if seed % 100 < 3 {
-            let char_idx = seed % STAR_CHARS.len();
+        // Only show stars at the configured fraction of positions
+        if (star % 10_000) as f32 / 10_000.0 < self.star_density {
+            let char_idx = (star / 10_000) as usize % STAR_CHARS.len();
             let ch = STAR_CHARS[char_idx];
 
-            // Vary brightness based on position
-            let brightness = (seed % 3) as u8;
-            let color = match brightness {
-                0 => Color::Rgb(60, 60, 80),    // Dim
-                1 => Color::Rgb(100, 100, 140), // Medium
-                _ => Color::Rgb(150, 150, 200), // Bright
+            // Vary brightness based on position and time
+            let brightness = hash_position(self.seed, x, y, frame_num + 1) % 3;
+            // "Bright" means most visible, which is darkest on a light terminal
+            let color = match (brightness, self.dark_terminal) {
+                (0, true) => Color::Rgb(60, 60, 80),    // Dim
+                (1, true) => Color::Rgb(100, 100, 140), // Medium
+                (_, true) => Color::Rgb(150, 150, 200), // Bright
+                (0, false) => Color::Rgb(190, 190, 205),
+                (1, false) => Color::Rgb(130, 130, 165),
+                (_, false) => Color::Rgb(70, 70, 120),
             };
 
             Span::styled(ch.to_string(), Style::new().fg(color))
@@ -233,15 +610,19 @@ impl BackgroundState {
             let intensity = 1.0 - (distance_from_head / col.trail_length as f32);
 
             // Select character based on position and seed
-            let char_idx = (col.char_seed.wrapping_add(y as usize)) % MATRIX_CHARS.len();
-            let ch = MATRIX_CHARS[char_idx];
+            let char_idx = (col.char_seed.wrapping_add(y as usize)) % self.matrix_chars.len();
+            let ch = self.matrix_chars[char_idx];
 
-            // Head is bright white-green, trail fades to dark green
+            // Head is a lightened trail color, trail fades to a dark shade
+            let [r, g, b] = self.matrix_color;
             let color = if distance_from_head < 1.0 {
-                Color::Rgb(200, 255, 200) // Bright head
+                let lighten =
+                    |c: u8| to_channel(c as f32 + (255 - c) as f32 * self.matrix_head_brightness);
+                Color::Rgb(lighten(r), lighten(g), lighten(b)) // Bright head
             } else {
-                let g = (80.0 + 120.0 * intensity) as u8;
-                Color::Rgb(0, g, 0)
+                let factor = (80.0 + 120.0 * intensity) / 255.0;
+                let scale = |c: u8| to_channel(c as f32 * factor);
+                Color::Rgb(scale(r), scale(g), scale(b))
             };
 
             Span::styled(ch.to_string(), Style::new().fg(color))
@@ -250,6 +631,51 @@ impl BackgroundState {
         }
     }
 
+    /// Render a snowflake character, checking neighboring columns for swaying flakes.
+    fn render_snow_char(&self, x: u16, y: u16) -> Span<'static> {
+        let start = x.saturating_sub(SNOW_SWAY.ceil() as u16) as usize;
+        let end =
+            (x as usize + SNOW_SWAY.ceil() as usize).min(self.snow_columns.len().saturating_sub(1));
+
+        for col_x in start..=end {
+            let Some(col) = self.snow_columns.get(col_x) else {
+                break;
+            };
+            let flake_x = (col_x as f32 + col.x_offset).round();
+            if flake_x == x as f32 && col.y.round() == y as f32 {
+                // Faster flakes are closer: bigger glyph and brighter white
+                let closeness = ((col.speed - 0.5) / 0.9).clamp(0.0, 1.0);
+                let char_idx = ((closeness * SNOW_CHARS.len() as f32) as usize + col.char_seed % 2)
+                    .min(SNOW_CHARS.len() - 1);
+                let v = to_channel(150.0 + 105.0 * closeness);
+                return Span::styled(
+                    SNOW_CHARS[char_idx].to_string(),
+                    Style::new().fg(Color::Rgb(v, v, v)),
+                );
+            }
+        }
+
+        Span::raw(" ")
+    }
+
+    /// Render a Game of Life cell, with newborn cells slightly fainter than stable ones.
+    fn render_life_char(&self, x: u16, y: u16, width: u16) -> Span<'static> {
+        let idx = y as usize * width as usize + x as usize;
+        let age = self.life_cells.get(idx).copied().unwrap_or(0);
+        if age == 0 {
+            return Span::raw(" ");
+        }
+
+        let (r, g, b) = color_to_rgb(self.theme_color);
+        let factor = if age == 1 { 0.2 } else { 0.3 };
+        let color = Color::Rgb(
+            to_channel(r as f32 * factor),
+            to_channel(g as f32 * factor),
+            to_channel(b as f32 * factor),
+        );
+        Span::styled("▓", Style::new().fg(color))
+    }
+
     /// Render a gradient wave character.
     fn render_gradient_char(
         &self,
@@ -266,8 +692,21 @@ impl BackgroundState {
         let x_norm = x as f32 / width.max(1) as f32;
         let y_norm = y as f32 / height.max(1) as f32;
 
-        // Create a diagonal wave pattern
-        let wave = ((x_norm + y_norm * 0.5 + time_phase) * 2.0 * std::f32::consts::PI).sin();
+        // The wave moves toward lower positions as the phase grows, so flipping the
+        // axis sign flips the scroll direction
+        let position = match self.gradient_scroll {
+            ScrollDirection::Up => y_norm,
+            ScrollDirection::Down => -y_norm,
+            ScrollDirection::Left => x_norm,
+            ScrollDirection::Right => -x_norm,
+            ScrollDirection::Diagonal => x_norm + y_norm * 0.5,
+        };
+        let position = if self.gradient_reverse {
+            -position
+        } else {
+            position
+        };
+        let wave = ((position + time_phase) * 2.0 * std::f32::consts::PI).sin();
         let intensity = (wave + 1.0) / 2.0; // Normalize to 0..1
 
         // Use block characters with varying density
@@ -285,7 +724,8 @@ impl BackgroundState {
         let hue_offset = time_phase * 360.0;
         let base_hue = (x_norm * 60.0 + hue_offset) % 360.0;
 
-        let color = hsl_to_rgb(base_hue, 0.7, 0.15 + intensity * 0.2);
+        let (r, g, b) = hsl_to_rgb(base_hue, 0.7, 0.15 + intensity * 0.2);
+        let color = Color::Rgb(r, g, b);
 
         if ch == ' ' {
             Span::raw(" ")
@@ -298,6 +738,7 @@ impl BackgroundState {
     fn render_reactive(
         &mut self,
         frame: &mut Frame,
+        area: Rect,
         style: BackgroundStyle,
         elapsed_ms: u64,
         speed: AnimationSpeed,
@@ -305,13 +746,17 @@ impl BackgroundState {
     ) {
         match style {
             BackgroundStyle::SystemPulse => {
-                self.render_system_pulse(frame, elapsed_ms, speed, metrics)
+                self.render_system_pulse(frame, area, elapsed_ms, speed, metrics)
             }
             BackgroundStyle::ResourceWave => {
-                self.render_resource_wave(frame, elapsed_ms, speed, metrics)
+                self.render_resource_wave(frame, area, elapsed_ms, speed, metrics)
+            }
+            BackgroundStyle::DataFlow => {
+                self.render_data_flow(frame, area, elapsed_ms, speed, metrics)
+            }
+            BackgroundStyle::HeatMap => {
+                self.render_heat_map(frame, area, elapsed_ms, speed, metrics)
             }
-            BackgroundStyle::DataFlow => self.render_data_flow(frame, elapsed_ms, speed, metrics),
-            BackgroundStyle::HeatMap => self.render_heat_map(frame, elapsed_ms, speed, metrics),
             _ => {}
         }
     }
@@ -320,11 +765,11 @@ impl BackgroundState {
     fn render_system_pulse(
         &self,
         frame: &mut Frame,
+        area: Rect,
         elapsed_ms: u64,
         speed: AnimationSpeed,
         metrics: &SystemMetrics,
     ) {
-        let area = frame.area();
         let width = area.width as f32;
         let height = area.height as f32;
 
@@ -386,11 +831,11 @@ impl BackgroundState {
     fn render_resource_wave(
         &self,
         frame: &mut Frame,
+        area: Rect,
         elapsed_ms: u64,
         speed: AnimationSpeed,
         metrics: &SystemMetrics,
     ) {
-        let area = frame.area();
         let width = area.width as f32;
         let height = area.height as f32;
 
@@ -438,12 +883,11 @@ impl BackgroundState {
     fn render_data_flow(
         &self,
         frame: &mut Frame,
+        area: Rect,
         elapsed_ms: u64,
         speed: AnimationSpeed,
         metrics: &SystemMetrics,
     ) {
-        let area = frame.area();
-
         // Network rate controls particle density and speed
         let net_combined = (metrics.network_rx_rate + metrics.network_tx_rate) / 2.0;
         let color = resource_to_color(net_combined);
@@ -489,11 +933,11 @@ impl BackgroundState {
     fn render_heat_map(
         &self,
         frame: &mut Frame,
+        area: Rect,
         elapsed_ms: u64,
         speed: AnimationSpeed,
         metrics: &SystemMetrics,
     ) {
-        let area = frame.area();
         let width = area.width;
         let height = area.height;
 
@@ -551,6 +995,26 @@ impl BackgroundState {
     }
 }
 
+/// Lighten the RGB colors already drawn in `area` toward white by `amount` (0.0 - 1.0).
+pub fn lighten(buf: &mut Buffer, area: Rect, amount: f32) {
+    let lighten_color = |color: Color| match color {
+        Color::Rgb(r, g, b) => {
+            let (r, g, b) = mix_rgb((r, g, b), (255, 255, 255), amount);
+            Color::Rgb(r, g, b)
+        }
+        other => other,
+    };
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                let (fg, bg) = (cell.fg, cell.bg);
+                cell.set_fg(lighten_color(fg));
+                cell.set_bg(lighten_color(bg));
+            }
+        }
+    }
+}
+
 /// Map a resource value (0.0-1.0) to a color from cool blue to warm red.
 fn resource_to_color(value: f32) -> Color {
     let value = value.clamp(0.0, 1.0);
@@ -562,47 +1026,189 @@ fn resource_to_color(value: f32) -> Color {
     let saturation = 0.6 + (value * 0.4);
     let lightness = 0.15 + (value * 0.25);
 
-    hsl_to_rgb(hue, saturation, lightness)
+    let (r, g, b) = hsl_to_rgb(hue, saturation, lightness);
+    Color::Rgb(r, g, b)
 }
 
-/// Convert HSL to RGB color.
-fn hsl_to_rgb(h: f32, s: f32, l: f32) -> Color {
-    if s == 0.0 {
-        let v = (l * 255.0) as u8;
-        return Color::Rgb(v, v, v);
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Local;
+
+    use super::*;
+    use crate::clock::ManualClock;
+
+    fn test_clock() -> Rc<dyn Clock> {
+        Rc::new(ManualClock::new(Local::now()))
     }
 
-    let q = if l < 0.5 {
-        l * (1.0 + s)
-    } else {
-        l + s - l * s
-    };
-    let p = 2.0 * l - q;
+    #[test]
+    fn test_time_stops_while_paused_or_held() {
+        let clock = ManualClock::new(Local::now());
+        let mut state = BackgroundState::new(Rc::new(clock.clone()));
+        clock.advance(Duration::from_millis(500));
+        assert_eq!(state.elapsed_ms(), 500);
+
+        state.hold();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(state.elapsed_ms(), 500);
+
+        // Paused while held, releasing alone doesn't start it again
+        state.set_paused(true);
+        state.release();
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(state.elapsed_ms(), 500);
+
+        state.set_paused(false);
+        clock.advance(Duration::from_millis(200));
+        assert_eq!(state.elapsed_ms(), 700);
+    }
 
-    let h = h / 360.0;
+    #[test]
+    fn test_same_seed_same_pattern() {
+        let mut a = BackgroundState::new(test_clock());
+        let mut b = BackgroundState::new(test_clock());
+        a.seed = 42;
+        b.seed = 42;
+        a.init_matrix_columns(20, 10);
+        b.init_matrix_columns(20, 10);
+        for (ca, cb) in a.matrix_columns.iter().zip(&b.matrix_columns) {
+            assert_eq!(ca.y, cb.y);
+            assert_eq!(ca.char_seed, cb.char_seed);
+        }
+        assert_eq!(hash_position(42, 3, 4, 5), hash_position(42, 3, 4, 5));
+        assert_ne!(hash_position(42, 3, 4, 5), hash_position(43, 3, 4, 5));
+    }
 
-    let r = hue_to_rgb(p, q, h + 1.0 / 3.0);
-    let g = hue_to_rgb(p, q, h);
-    let b = hue_to_rgb(p, q, h - 1.0 / 3.0);
+    #[test]
+    fn test_matrix_trails_respect_bounds() {
+        let mut state = BackgroundState::new(test_clock());
+        for (min, max) in [(4, 11), (1, 1), (20, 40)] {
+            state.configure(
+                &Config {
+                    matrix_trail_min: min,
+                    matrix_trail_max: max,
+                    ..Config::default()
+                },
+                ColorTheme::default(),
+            );
+            state.init_matrix_columns(200, 30);
+            let lengths: Vec<usize> = state
+                .matrix_columns
+                .iter()
+                .map(|c| c.trail_length)
+                .collect();
+            assert!(
+                lengths.iter().all(|len| (min..=max).contains(len)),
+                "{min}..={max}"
+            );
+            // Wide ranges actually get used
+            if max - min > 4 {
+                assert!(
+                    lengths.contains(&min) && lengths.contains(&max),
+                    "{min}..={max}"
+                );
+            }
+        }
+    }
 
-    Color::Rgb((r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8)
-}
+    #[test]
+    fn test_matrix_column_wraps() {
+        let mut state = BackgroundState::new(test_clock());
+        state.matrix_columns = vec![MatrixColumn {
+            y: 0.0,
+            x_offset: 0.0,
+            speed: 1.0,
+            trail_length: 4,
+            char_seed: 0,
+        }];
+
+        // At medium speed a column falls a row every 50ms and wraps once its
+        // trail has left a 10-row screen (past row 14)
+        state.update_matrix(700, 10, AnimationSpeed::Medium);
+        assert_eq!(state.matrix_columns[0].y, 14.0);
+        state.update_matrix(750, 10, AnimationSpeed::Medium);
+        assert_eq!(state.matrix_columns[0].y, -4.0);
+        assert_eq!(state.matrix_columns[0].char_seed, 1);
+    }
 
-fn hue_to_rgb(p: f32, q: f32, mut t: f32) -> f32 {
-    if t < 0.0 {
-        t += 1.0;
+    #[test]
+    fn test_gradient_scroll_direction() {
+        let sample = |state: &BackgroundState| -> Vec<Span<'static>> {
+            (0..20)
+                .map(|x| state.render_gradient_char(x, 3, 20, 10, 250, AnimationSpeed::Medium))
+                .collect()
+        };
+        let mut state = BackgroundState::new(test_clock());
+        state.gradient_scroll = ScrollDirection::Left;
+        let left = sample(&state);
+        state.gradient_scroll = ScrollDirection::Up;
+        assert_ne!(sample(&state), left);
+
+        // Reversing a direction matches the opposite one
+        state.gradient_scroll = ScrollDirection::Right;
+        let right = sample(&state);
+        state.gradient_scroll = ScrollDirection::Left;
+        state.gradient_reverse = true;
+        assert_eq!(sample(&state), right);
     }
-    if t > 1.0 {
-        t -= 1.0;
+
+    #[test]
+    fn test_resize_reinitializes_matrix_columns() {
+        let mut state = BackgroundState::new(test_clock());
+        state.brightness = 1.0;
+        let mut draw = |width: u16| {
+            let mut terminal =
+                ratatui::Terminal::new(ratatui::backend::TestBackend::new(width, 10)).unwrap();
+            terminal
+                .draw(|frame| {
+                    let area = frame.area();
+                    state.render(
+                        frame,
+                        area,
+                        BackgroundStyle::MatrixRain,
+                        AnimationSpeed::Medium,
+                        None,
+                    );
+                })
+                .unwrap();
+        };
+        draw(20);
+        draw(30);
+        assert_eq!(state.matrix_columns.len(), 30);
+
+        state.invalidate();
+        assert_eq!(state.last_width, 0);
     }
 
-    if t < 1.0 / 6.0 {
-        p + (q - p) * 6.0 * t
-    } else if t < 1.0 / 2.0 {
-        q
-    } else if t < 2.0 / 3.0 {
-        p + (q - p) * (2.0 / 3.0 - t) * 6.0
-    } else {
-        p
+    #[test]
+    fn test_lighten() {
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_style(area, Style::new().bg(Color::Rgb(0, 0, 0)));
+        buf[(1, 0)].set_fg(Color::Red);
+
+        lighten(&mut buf, area, 0.5);
+        assert_eq!(buf[(0, 0)].bg, Color::Rgb(127, 127, 127));
+        // Named colors are left alone
+        assert_eq!(buf[(1, 0)].fg, Color::Red);
+    }
+
+    #[test]
+    fn test_life_blinker_oscillates() {
+        let mut state = BackgroundState::new(test_clock());
+        // Horizontal blinker in the middle of a 5x5 grid
+        state.life_cells = vec![0; 25];
+        for x in 1..4 {
+            state.life_cells[2 * 5 + x] = 2;
+        }
+
+        state.step_life(5, 5);
+        let alive: Vec<usize> = (0..25).filter(|&i| state.life_cells[i] > 0).collect();
+        assert_eq!(alive, vec![7, 12, 17]);
+        // The center survives and ages, the new ends are newborn
+        assert_eq!(state.life_cells[12], 3);
+        assert_eq!(state.life_cells[7], 1);
     }
 }
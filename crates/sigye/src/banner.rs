@@ -0,0 +1,71 @@
+//! Dismissable warning banner shown at the top of the screen.
+
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    style::{Color, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+/// Warning messages waiting to be acknowledged.
+#[derive(Debug, Default)]
+pub struct Banner {
+    messages: Vec<String>,
+}
+
+impl Banner {
+    /// Create a banner showing `messages`, hidden while there are none.
+    pub fn new(messages: Vec<String>) -> Self {
+        Self { messages }
+    }
+
+    /// Add a message to the banner.
+    pub fn push(&mut self, message: impl Into<String>) {
+        self.messages.push(message.into());
+    }
+
+    /// Check if there is anything to show.
+    pub fn is_visible(&self) -> bool {
+        !self.messages.is_empty()
+    }
+
+    /// Hide the banner and drop its messages.
+    pub fn dismiss(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Render the banner across the top of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        if self.messages.is_empty() {
+            return;
+        }
+
+        let width = area.width.saturating_sub(4);
+        let height = (self.messages.len() as u16 + 3).min(area.height);
+        let banner_area = Rect::new(area.x + 2, area.y, width, height);
+
+        frame.render_widget(Clear, banner_area);
+
+        let block = Block::default()
+            .title(" Warning ")
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        let mut lines: Vec<Line> = self
+            .messages
+            .iter()
+            .map(|message| Line::from(message.as_str()).yellow())
+            .collect();
+        lines.push(
+            Line::from(vec![
+                Span::styled("x", Style::default().fg(Color::Yellow).bold()),
+                Span::styled(" dismiss", Style::default().dark_gray()),
+            ])
+            .centered(),
+        );
+
+        frame.render_widget(Paragraph::new(lines).block(block), banner_area);
+    }
+}
@@ -0,0 +1,162 @@
+//! Command-line argument parsing.
+
+use std::path::PathBuf;
+
+use sigye_core::TimeFormat;
+
+/// Usage text printed for `--help`.
+const USAGE: &str = "\
+Usage: sigye [OPTIONS]
+
+Options:
+      --fonts-dir <DIR>  Load custom fonts from DIR instead of the configured
+                         directories (may be repeated)
+      --listen <PATH>    Accept remote control commands on a Unix socket at PATH
+      --label <TEXT>     Show TEXT above the clock instead of the configured label
+      --font <NAME>      Use font NAME instead of the configured font
+      --format <12h|24h> Use this time format instead of the configured one
+      --json             Print the current state as JSON and exit
+      --measure          Print the size the clock needs as WIDTHxHEIGHT and exit
+                         (as JSON with --json)
+      --with-date        Count the date line in the --measure size
+      --with-footer      Count the help line in the --measure size
+      --rescan-fonts     Re-parse custom fonts, ignoring the font cache
+      --list-fonts       Print available fonts with their height and source, and exit
+      --list-themes      Print available color themes and exit
+      --list-animations  Print available animation styles and exit
+      --text <TEXT>      Print TEXT in the configured font and theme and exit
+                         (\\n starts a new line)
+      --no-color         Print --text without colors
+      --gallery          Print sample text in every font and exit
+      --gallery-text <TEXT>
+                         Sample text for --gallery (implies it; default \"12:34\")
+      --record-gif <PATH>
+                         Record the clock to an animated GIF at PATH and exit
+      --seconds <N>      Length of the --record-gif recording (default 5)
+  -h, --help             Print help";
+
+/// Command-line options.
+#[derive(Debug, Default)]
+pub struct Args {
+    /// Custom font directories overriding the configured ones.
+    pub fonts_dirs: Vec<String>,
+    /// Unix socket path to accept remote control commands on.
+    pub listen: Option<PathBuf>,
+    /// Label overriding the configured one for this session.
+    pub label: Option<String>,
+    /// Font overriding the configured one for this session.
+    pub font: Option<String>,
+    /// Time format overriding the configured one for this session.
+    pub format: Option<TimeFormat>,
+    /// Print the current state as JSON instead of starting the clock.
+    pub json: bool,
+    /// Print the size the clock needs instead of starting it.
+    pub measure: bool,
+    /// Include the date line in the measured size.
+    pub with_date: bool,
+    /// Include the help line in the measured size.
+    pub with_footer: bool,
+    /// Ignore the parsed font cache and re-read every custom font.
+    pub rescan_fonts: bool,
+    /// Print the available fonts instead of starting the clock.
+    pub list_fonts: bool,
+    /// Print the color themes instead of starting the clock.
+    pub list_themes: bool,
+    /// Print the animation styles instead of starting the clock.
+    pub list_animations: bool,
+    /// Text to print as a banner instead of starting the clock.
+    pub text: Option<String>,
+    /// Print the banner text without ANSI colors.
+    pub no_color: bool,
+    /// Sample text to print in every font instead of starting the clock.
+    pub gallery: Option<String>,
+    /// Path to record an animated GIF to instead of starting the clock.
+    pub record_gif: Option<PathBuf>,
+    /// Length of the GIF recording in seconds, if not the default.
+    pub seconds: Option<u32>,
+    /// Print usage and exit.
+    pub help: bool,
+    /// Panic right after entering the terminal UI, to check the terminal is
+    /// restored (hidden from the usage text).
+    pub panic_test: bool,
+}
+
+impl Args {
+    /// Parse the process arguments.
+    pub fn parse() -> Result<Self, String> {
+        Self::parse_from(std::env::args().skip(1))
+    }
+
+    /// Parse arguments, excluding the program name.
+    pub fn parse_from(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--fonts-dir" => match args.next() {
+                    Some(dir) => parsed.fonts_dirs.push(dir),
+                    None => return Err(format!("'--fonts-dir' needs a directory\n\n{USAGE}")),
+                },
+                other if other.starts_with("--fonts-dir=") => {
+                    parsed
+                        .fonts_dirs
+                        .push(other["--fonts-dir=".len()..].to_string());
+                }
+                "--listen" => match args.next() {
+                    Some(path) => parsed.listen = Some(PathBuf::from(path)),
+                    None => return Err(format!("'--listen' needs a socket path\n\n{USAGE}")),
+                },
+                "--label" => match args.next() {
+                    Some(label) => parsed.label = Some(label),
+                    None => return Err(format!("'--label' needs some text\n\n{USAGE}")),
+                },
+                "--font" => match args.next() {
+                    Some(font) => parsed.font = Some(font),
+                    None => return Err(format!("'--font' needs a font name\n\n{USAGE}")),
+                },
+                "--format" => match args.next().map(|format| format.parse::<TimeFormat>()) {
+                    Some(Ok(format)) => parsed.format = Some(format),
+                    Some(Err(e)) => return Err(format!("'--format': {e}\n\n{USAGE}")),
+                    None => return Err(format!("'--format' needs 12h or 24h\n\n{USAGE}")),
+                },
+                "--json" => parsed.json = true,
+                "--measure" => parsed.measure = true,
+                "--with-date" => parsed.with_date = true,
+                "--with-footer" => parsed.with_footer = true,
+                "--rescan-fonts" => parsed.rescan_fonts = true,
+                "--list-fonts" => parsed.list_fonts = true,
+                "--list-themes" => parsed.list_themes = true,
+                "--list-animations" => parsed.list_animations = true,
+                "--text" => match args.next() {
+                    Some(text) => parsed.text = Some(text),
+                    None => return Err(format!("'--text' needs some text\n\n{USAGE}")),
+                },
+                "--no-color" => parsed.no_color = true,
+                "--gallery" => {
+                    parsed.gallery.get_or_insert_with(|| "12:34".to_string());
+                }
+                "--gallery-text" => match args.next() {
+                    Some(text) => parsed.gallery = Some(text),
+                    None => return Err(format!("'--gallery-text' needs some text\n\n{USAGE}")),
+                },
+                "--record-gif" => match args.next() {
+                    Some(path) => parsed.record_gif = Some(PathBuf::from(path)),
+                    None => return Err(format!("'--record-gif' needs a file path\n\n{USAGE}")),
+                },
+                "--seconds" => match args.next().map(|n| n.parse::<u32>()) {
+                    Some(Ok(seconds)) if seconds > 0 => parsed.seconds = Some(seconds),
+                    _ => return Err(format!("'--seconds' needs a positive number\n\n{USAGE}")),
+                },
+                "-h" | "--help" => parsed.help = true,
+                "--panic-test" => parsed.panic_test = true,
+                other => return Err(format!("unknown argument '{other}'\n\n{USAGE}")),
+            }
+        }
+        Ok(parsed)
+    }
+
+    /// Get the usage text.
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+}
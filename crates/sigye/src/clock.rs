@@ -0,0 +1,67 @@
+//! Time source for the app, swappable so time-based behavior can be tested.
+
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Local};
+
+/// Source of wall-clock and monotonic time.
+pub trait Clock: std::fmt::Debug {
+    /// Get the current local time.
+    fn now(&self) -> DateTime<Local>;
+
+    /// Get the current monotonic instant.
+    fn instant(&self) -> Instant;
+
+    /// Get the time passed since an earlier instant from this clock.
+    fn elapsed(&self, since: Instant) -> Duration {
+        self.instant().saturating_duration_since(since)
+    }
+}
+
+/// Clock backed by the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Local> {
+        Local::now()
+    }
+
+    fn instant(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clock that only moves when advanced, for tests and recordings.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    start: DateTime<Local>,
+    start_instant: Instant,
+    offset: std::rc::Rc<std::cell::Cell<Duration>>,
+}
+
+impl ManualClock {
+    /// Create a clock stopped at a local time.
+    pub fn new(start: DateTime<Local>) -> Self {
+        Self {
+            start,
+            start_instant: Instant::now(),
+            offset: Default::default(),
+        }
+    }
+
+    /// Move the clock forward. Clones share the same time.
+    pub fn advance(&self, by: Duration) {
+        self.offset.set(self.offset.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Local> {
+        self.start + self.offset.get()
+    }
+
+    fn instant(&self) -> Instant {
+        self.start_instant + self.offset.get()
+    }
+}
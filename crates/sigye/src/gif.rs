@@ -0,0 +1,305 @@
+//! Minimal animated GIF encoder.
+//!
+//! Frames share one fixed 256-color palette (a 6x6x6 color cube plus a gray ramp),
+//! so no per-recording quantization pass is needed. Pixel data is LZW-compressed
+//! as the GIF89a format requires, and the animation loops forever.
+
+use std::collections::HashMap;
+
+use crate::raster::Image;
+
+/// Levels of each channel in the color cube.
+const CUBE_LEVELS: [u8; 6] = [0, 51, 102, 153, 204, 255];
+
+/// Number of grays after the color cube.
+const GRAY_COUNT: usize = 40;
+
+/// Widest LZW code the format allows.
+const MAX_CODE_BITS: u8 = 12;
+
+/// Builds a looping GIF one frame at a time, so only the compressed data is kept.
+pub struct GifEncoder {
+    out: Vec<u8>,
+    width: u16,
+    height: u16,
+    /// Palette index for each color seen so far.
+    nearest_cache: HashMap<(u8, u8, u8), u8>,
+}
+
+impl GifEncoder {
+    /// Start a GIF of the given size in pixels.
+    pub fn new(width: u16, height: u16) -> Self {
+        let mut out = Vec::new();
+        out.extend_from_slice(b"GIF89a");
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        // Global color table of 256 entries, 8 bits per channel
+        out.extend_from_slice(&[0xF7, 0, 0]);
+        for (r, g, b) in palette() {
+            out.extend_from_slice(&[r, g, b]);
+        }
+        // Loop forever
+        out.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+        Self {
+            out,
+            width,
+            height,
+            nearest_cache: HashMap::new(),
+        }
+    }
+
+    /// Add a frame shown for `delay_cs` hundredths of a second.
+    /// The image must be the size given to [`GifEncoder::new`].
+    pub fn add_frame(&mut self, image: &Image, delay_cs: u16) {
+        // Graphic control extension with the frame delay
+        self.out.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+        self.out.extend_from_slice(&delay_cs.to_le_bytes());
+        self.out.extend_from_slice(&[0x00, 0x00]);
+
+        // Image descriptor covering the whole screen, no local color table
+        self.out.push(0x2C);
+        self.out.extend_from_slice(&[0, 0, 0, 0]);
+        self.out.extend_from_slice(&self.width.to_le_bytes());
+        self.out.extend_from_slice(&self.height.to_le_bytes());
+        self.out.push(0x00);
+
+        let cache = &mut self.nearest_cache;
+        let indices: Vec<u8> = image
+            .pixels
+            .iter()
+            .map(|&rgb| *cache.entry(rgb).or_insert_with(|| nearest(rgb)))
+            .collect();
+        self.out.push(8);
+        for block in lzw_compress(&indices).chunks(255) {
+            self.out.push(block.len() as u8);
+            self.out.extend_from_slice(block);
+        }
+        self.out.push(0x00);
+    }
+
+    /// End the GIF and get its bytes.
+    pub fn finish(mut self) -> Vec<u8> {
+        self.out.push(0x3B);
+        self.out
+    }
+}
+
+/// Build the fixed palette: the color cube followed by grays.
+fn palette() -> Vec<(u8, u8, u8)> {
+    let mut palette = Vec::with_capacity(256);
+    for r in CUBE_LEVELS {
+        for g in CUBE_LEVELS {
+            for b in CUBE_LEVELS {
+                palette.push((r, g, b));
+            }
+        }
+    }
+    for i in 0..GRAY_COUNT {
+        let v = (8 + i * 6) as u8;
+        palette.push((v, v, v));
+    }
+    palette
+}
+
+/// Get the palette index closest to a color, from the cube or the gray ramp.
+fn nearest((r, g, b): (u8, u8, u8)) -> u8 {
+    let level = |c: u8| (usize::from(c) + 25) / 51;
+    let cube_index = level(r) * 36 + level(g) * 6 + level(b);
+    let gray = (u32::from(r) + u32::from(g) + u32::from(b)) / 3;
+    let gray_index = 216 + ((gray.saturating_sub(8) as usize + 3) / 6).min(GRAY_COUNT - 1);
+
+    let distance = |(pr, pg, pb): (u8, u8, u8)| {
+        let d = |a: u8, b: u8| (i32::from(a) - i32::from(b)).pow(2);
+        d(r, pr) + d(g, pg) + d(b, pb)
+    };
+    if distance(palette_entry(gray_index)) < distance(palette_entry(cube_index)) {
+        gray_index as u8
+    } else {
+        cube_index as u8
+    }
+}
+
+/// Get the color of a palette index without building the palette.
+fn palette_entry(index: usize) -> (u8, u8, u8) {
+    if index < 216 {
+        (
+            CUBE_LEVELS[index / 36],
+            CUBE_LEVELS[index / 6 % 6],
+            CUBE_LEVELS[index % 6],
+        )
+    } else {
+        let v = (8 + (index - 216) * 6) as u8;
+        (v, v, v)
+    }
+}
+
+/// Compress 8-bit palette indices with GIF's variable-width LZW.
+fn lzw_compress(indices: &[u8]) -> Vec<u8> {
+    const CLEAR: u16 = 256;
+    const END: u16 = 257;
+
+    let mut writer = BitWriter::default();
+    let mut dictionary: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = END + 1;
+    let mut code_bits = 9;
+    writer.write(CLEAR, code_bits);
+
+    let Some((&first, rest)) = indices.split_first() else {
+        writer.write(END, code_bits);
+        return writer.finish();
+    };
+    let mut prefix = u16::from(first);
+    for &index in rest {
+        if let Some(&code) = dictionary.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        writer.write(prefix, code_bits);
+        if next_code < 1 << MAX_CODE_BITS {
+            dictionary.insert((prefix, index), next_code);
+            // The decoder widens its codes one code later than the encoder adds them
+            if next_code == 1 << code_bits && code_bits < MAX_CODE_BITS {
+                code_bits += 1;
+            }
+            next_code += 1;
+        } else {
+            // The table is full, so start over
+            writer.write(CLEAR, code_bits);
+            dictionary.clear();
+            next_code = END + 1;
+            code_bits = 9;
+        }
+        prefix = u16::from(index);
+    }
+    writer.write(prefix, code_bits);
+    writer.write(END, code_bits);
+    writer.finish()
+}
+
+/// Packs codes least significant bit first, as GIF expects.
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u8) {
+        self.buffer |= u32::from(code) << self.bits;
+        self.bits += width;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Decompress GIF LZW data, to check the encoder against.
+    fn lzw_decompress(data: &[u8]) -> Vec<u8> {
+        let mut codes = Vec::new();
+        let (mut buffer, mut bits, mut pos) = (0u32, 0u8, 0);
+        let mut code_bits = 9;
+        let mut table: Vec<Vec<u8>> = (0..=255).map(|i| vec![i as u8]).collect();
+        table.extend([vec![], vec![]]);
+        let mut previous: Option<Vec<u8>> = None;
+        loop {
+            while bits < code_bits {
+                buffer |= u32::from(data[pos]) << bits;
+                pos += 1;
+                bits += 8;
+            }
+            let code = (buffer & ((1 << code_bits) - 1)) as usize;
+            buffer >>= code_bits;
+            bits -= code_bits;
+
+            match code {
+                256 => {
+                    table.truncate(258);
+                    code_bits = 9;
+                    previous = None;
+                }
+                257 => return codes,
+                _ => {
+                    let entry = match (table.get(code), &previous) {
+                        (Some(entry), _) => entry.clone(),
+                        (None, Some(prev)) => [prev.clone(), vec![prev[0]]].concat(),
+                        (None, None) => panic!("bad code {code}"),
+                    };
+                    if let Some(prev) = previous
+                        && table.len() < 1 << MAX_CODE_BITS
+                    {
+                        table.push([prev, vec![entry[0]]].concat());
+                    }
+                    codes.extend_from_slice(&entry);
+                    if table.len() == 1 << code_bits && code_bits < MAX_CODE_BITS {
+                        code_bits += 1;
+                    }
+                    previous = Some(entry);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_lzw_round_trip() {
+        let inputs: Vec<Vec<u8>> = vec![
+            vec![7],
+            vec![1, 1, 1, 1, 1, 1, 1, 1],
+            (0..20_000)
+                .map(|i| ((i * 7 + i / 13) % 256) as u8)
+                .collect(),
+            (0..50_000).map(|i| (i / 100 % 3) as u8).collect(),
+        ];
+        for input in inputs {
+            assert_eq!(lzw_decompress(&lzw_compress(&input)), input);
+        }
+    }
+
+    #[test]
+    fn test_nearest_palette_color() {
+        assert_eq!(
+            palette_entry(usize::from(nearest((255, 0, 0)))),
+            (255, 0, 0)
+        );
+        assert_eq!(palette_entry(usize::from(nearest((0, 0, 0)))), (0, 0, 0));
+        assert_eq!(
+            palette_entry(usize::from(nearest((20, 20, 20)))),
+            (20, 20, 20)
+        );
+        assert_eq!(palette().len(), 256);
+    }
+
+    #[test]
+    fn test_encode_structure() {
+        let frame = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![(255, 0, 0), (0, 0, 255)],
+        };
+        let mut encoder = GifEncoder::new(2, 1);
+        encoder.add_frame(&frame, 5);
+        encoder.add_frame(&frame, 5);
+        let gif = encoder.finish();
+        assert!(gif.starts_with(b"GIF89a\x02\x00\x01\x00"));
+        assert_eq!(gif.last(), Some(&0x3B));
+        assert_eq!(
+            gif.windows(4)
+                .filter(|w| *w == [0x21, 0xF9, 0x04, 0x00])
+                .count(),
+            2
+        );
+    }
+}
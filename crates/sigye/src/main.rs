@@ -1,64 +1,269 @@
 //! sigye - A terminal clock application with configurable fonts.
 
+mod analog;
 mod background;
+mod banner;
+mod cli;
+mod clock;
+mod gif;
+mod markup;
+mod pomodoro;
+mod raster;
+mod remote;
 mod settings;
+mod state;
 mod system_metrics;
+mod termbg;
+mod toast;
 
-use std::time::{Duration, Instant};
+use std::borrow::Cow;
+use std::io::{IsTerminal, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use chrono::Local;
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use ratatui::{
-    DefaultTerminal, Frame,
-    layout::{Constraint, Layout, Position},
-    style::Stylize,
+    DefaultTerminal, Frame, Terminal,
+    backend::TestBackend,
+    buffer::Buffer,
+    layout::{Position, Rect},
+    style::{Color, Style, Stylize},
     text::Line,
+    widgets::{Block, StatefulWidget},
 };
-use sigye_config::Config;
+use sigye_config::{Config, expand_path};
 use sigye_core::{
-    AnimationSpeed, AnimationStyle, BackgroundStyle, ColorTheme, TimeFormat, apply_animation,
-    is_colon_visible,
+    Alarm, AnimationContext, AnimationStyle, BlinkMode, ColorDepth, ColorTheme, DateRule,
+    DisplayMode, ReactiveGranularity, RefreshMode, TerminalBackground, ThemeSchedule, TimeFormat,
+    TimePrecision, Warnings, animate_line, animation_phase_ms, color_to_rgb, dim, format_elapsed,
+    in_time_window, parse_time_of_day, quantize_ansi16,
 };
-use sigye_fonts::FontRegistry;
+use sigye_fonts::{CLOCK_CHARS, Font, FontCache, FontRegistry, TextLayout};
+use sigye_widget::{ClockWidget, ClockWidgetState, blend_row_colors, row_colors};
 
 use background::BackgroundState;
+use banner::Banner;
+use cli::Args;
+use clock::{Clock, ManualClock, SystemClock};
+use pomodoro::Pomodoro;
+use remote::RemoteControl;
 use settings::SettingsDialog;
+use state::AppState;
 use system_metrics::SystemMonitor;
+use toast::Toast;
+
+/// Minimum contrast ratio between clock text and the fill color before warning.
+const MIN_CONTRAST: f32 = 2.0;
+
+/// Progressively smaller fonts to use when the configured one is too tall for the terminal.
+const FALLBACK_FONTS: &[&str] = &["Small", "Mini"];
+
+/// Tallest font `--gallery` shows, in rows.
+const GALLERY_MAX_HEIGHT: usize = 16;
+
+/// Columns and rows of background kept clear around the clock with `safe_zone` on.
+const SAFE_ZONE_MARGIN: (u16, u16) = (2, 1);
+
+/// Most digits the `g` go-to prompt takes.
+const MAX_GOTO_DIGITS: usize = 3;
+
+/// Reactive flash strength for a key press with `flash_on_keypress` on.
+const KEYPRESS_FLASH: f32 = 0.5;
+
+/// How long to wait after the last quick change before saving the config.
+const QUICK_SAVE_DELAY: Duration = Duration::from_secs(1);
+
+/// How long a toast like the new animation speed stays on screen.
+const TOAST_DURATION: Duration = Duration::from_millis(1500);
+
+/// How long the toast for a ringing alarm stays on screen.
+const ALARM_TOAST_DURATION: Duration = Duration::from_secs(10);
+
+/// How far a full flash lightens the background with `frame_flash` on.
+const FRAME_FLASH_STRENGTH: f32 = 0.15;
+
+/// Longest wait for input with `refresh_mode = "Manual"`, so remote commands still get answered.
+const MANUAL_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Length of a `--record-gif` recording without `--seconds`.
+const DEFAULT_RECORD_SECONDS: u32 = 5;
+
+/// Most frames a `--record-gif` recording keeps, so long or fast recordings stay small.
+const MAX_GIF_FRAMES: u32 = 600;
 
 fn main() -> color_eyre::Result<()> {
+    // Must come before ratatui::init, whose panic hook restores the terminal and then
+    // calls the hook installed here, so the report lands on a usable screen
     color_eyre::install()?;
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", Args::usage());
+        return Ok(());
+    }
+    if args.measure {
+        let setup = ClockSetup::load(&args);
+        if let Some(font) = args.font.as_deref()
+            && setup.font_registry.resolve_name(font).is_none()
+        {
+            eprintln!("Error: unknown font '{font}'");
+            std::process::exit(2);
+        }
+        let (width, height) = setup
+            .text()
+            .measure(&Local::now(), args.with_date, args.with_footer);
+        if args.json {
+            println!("{{\"width\":{width},\"height\":{height}}}");
+        } else {
+            println!("{width}x{height}");
+        }
+        return Ok(());
+    }
+    if args.json {
+        let setup = ClockSetup::load(&args);
+        println!("{}", setup.text().state(&Local::now()).to_json());
+        return Ok(());
+    }
+    if args.list_fonts {
+        print_listing(&ClockSetup::load(&args).text().font_list());
+        return Ok(());
+    }
+    if let Some(text) = &args.text {
+        // Color only goes to a terminal, and never when NO_COLOR is set
+        let color = !args.no_color
+            && std::env::var_os("NO_COLOR").is_none()
+            && std::io::stdout().is_terminal();
+        print_listing(&ClockSetup::load(&args).text().banner_text(text, color));
+        return Ok(());
+    }
+    if let Some(text) = &args.gallery {
+        print_listing(&ClockSetup::load(&args).text().gallery(text));
+        return Ok(());
+    }
+    if let Some(path) = &args.record_gif {
+        return record_gif(&args, path);
+    }
+    if args.list_themes {
+        let names: Vec<&str> = ColorTheme::all().iter().map(|t| t.display_name()).collect();
+        print_listing(&(names.join("\n") + "\n"));
+        return Ok(());
+    }
+    if args.list_animations {
+        let names: Vec<&str> = AnimationStyle::all()
+            .iter()
+            .map(|s| s.display_name())
+            .collect();
+        print_listing(&(names.join("\n") + "\n"));
+        return Ok(());
+    }
+
+    // A panic from here on leaves raw mode and the alternate screen through init's hook
     let terminal = ratatui::init();
-    let result = App::new().run(terminal);
+    if args.panic_test {
+        panic!("panic test");
+    }
+    let result = App::new(&args).run(terminal);
     ratatui::restore();
     result
 }
 
+/// Print a `--list-*` listing. A closed pipe (e.g. `| head`) isn't an error.
+fn print_listing(listing: &str) {
+    let _ = std::io::stdout().write_all(listing.as_bytes());
+}
+
+/// Render the clock off screen at the configured frame rate and save it as a GIF.
+fn record_gif(args: &Args, path: &std::path::Path) -> color_eyre::Result<()> {
+    // Like the clock itself, minus the remote socket and font cache update
+    let clock = ManualClock::new(Local::now());
+    let (config, mut warnings) = Config::load_result();
+    let fonts = FontSources::configured(args, &config, &mut warnings).read_only();
+    let mut app = App::with_config(args, config, warnings, &fonts, Rc::new(clock.clone()));
+    // The GIF's pixel size has to fit in 16 bits
+    let (width, height) = crossterm::terminal::size().unwrap_or((80, 24));
+    let width = width.min(u16::MAX / raster::CELL_WIDTH as u16);
+    let height = height.min(u16::MAX / raster::CELL_HEIGHT as u16);
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+
+    // GIF delays are in hundredths of a second and players treat under 2 as too fast,
+    // so step the clock by exactly the delay each frame shows for
+    let delay_cs = (app.config.frame_interval().as_millis() / 10).max(2) as u16;
+    let step = Duration::from_millis(u64::from(delay_cs) * 10);
+    let seconds = args.seconds.unwrap_or(DEFAULT_RECORD_SECONDS);
+    let (frames, limited) = gif_frame_count(seconds, delay_cs);
+    if limited {
+        eprintln!("Warning: recording limited to {MAX_GIF_FRAMES} frames");
+    }
+
+    let mut encoder = gif::GifEncoder::new(
+        width.saturating_mul(raster::CELL_WIDTH as u16),
+        height.saturating_mul(raster::CELL_HEIGHT as u16),
+    );
+    for _ in 0..frames {
+        terminal.draw(|frame| app.render(frame))?;
+        encoder.add_frame(&raster::rasterize(terminal.backend().buffer()), delay_cs);
+        clock.advance(step);
+    }
+    std::fs::write(path, encoder.finish())?;
+    println!("Wrote {frames} frames to {}", path.display());
+    Ok(())
+}
+
 /// The main application which holds the state and logic of the application.
 pub struct App {
     /// Is the application running?
     running: bool,
-    /// Current time format (12h or 24h).
-    time_format: TimeFormat,
-    /// Current color theme.
-    color_theme: ColorTheme,
-    /// Current animation style.
-    animation_style: AnimationStyle,
-    /// Current animation speed.
-    animation_speed: AnimationSpeed,
-    /// Whether colon blinks.
-    colon_blink: bool,
-    /// Current background style.
-    background_style: BackgroundStyle,
-    /// Current font name.
-    current_font: String,
+    /// Whether to redraw without waiting for the next frame.
+    redraw: bool,
+    /// Current settings, also used for persistence.
+    config: Config,
+    /// Where the config is saved, or `None` to keep changes to this session.
+    config_path: Option<PathBuf>,
+    /// When to save quick changes, once no more have come in for a moment.
+    save_due: Option<Instant>,
     /// Font registry containing all available fonts.
     font_registry: FontRegistry,
+    /// Warnings shown on screen until dismissed.
+    banner: Banner,
+    /// Socket accepting remote control commands, if `--listen` was given.
+    remote: Option<RemoteControl>,
+    /// Label shown above the clock (`--label` overrides the config for this session).
+    label: Option<String>,
+    /// strftime format for the big clock, if a valid one is configured.
+    time_format_str: Option<String>,
+    /// Font from `--font`, shown instead of the configured one until another is picked.
+    font_override: Option<String>,
+    /// Time format from `--format`, used instead of the configured one until toggled.
+    format_override: Option<TimeFormat>,
     /// Settings dialog state.
     settings_dialog: SettingsDialog,
-    /// Configuration for persistence.
-    config: Config,
+    /// Source of the current time.
+    clock: Rc<dyn Clock>,
     /// Animation start time.
     animation_start: Instant,
+    /// Animation time frozen by the pause key, if paused.
+    animation_paused_at: Option<u64>,
+    /// Total time animations have spent paused, in milliseconds.
+    animation_paused_ms: u64,
+    /// Background cells drawn once while held, reused until it moves again or the
+    /// preview changes it, so only the dialog and clock redraw.
+    held_background: Option<Buffer>,
+    /// When the last key was pressed (for idle auto-cycling).
+    last_input: Instant,
+    /// When idle auto-cycling last stepped, if it has started.
+    last_idle_cycle: Option<Instant>,
+    /// Settings from before idle cycling began, put back on the next key.
+    pre_idle: Option<IdleSnapshot>,
+    /// Number typed after `g`, while choosing a theme or font by number.
+    goto: Option<String>,
     /// Last recorded second (for reactive animation).
     last_second: u32,
     /// Last recorded minute (for reactive animation).
@@ -69,23 +274,102 @@ pub struct App {
     flash_intensity: f32,
     /// When the last flash started (for decay calculation).
     flash_start: Option<Instant>,
+    /// Short message in the top right corner that goes away by itself.
+    toast: Option<Toast>,
+    /// Parsed alarms from the configuration.
+    alarms: Vec<Alarm>,
+    /// Whether an alarm is currently ringing.
+    alarm_active: bool,
+    /// Minute the last alarm fired (to avoid re-triggering within it).
+    alarm_last_fired: Option<NaiveDateTime>,
+    /// Time-of-day theme thresholds from the configuration.
+    theme_schedule: ThemeSchedule,
+    /// Index of the schedule threshold last applied (the `c` key overrides until the next).
+    theme_schedule_index: Option<usize>,
+    /// Theme picked by the schedule, shown instead of the configured theme until chosen over.
+    scheduled_theme: Option<ColorTheme>,
+    /// Calendar rules and the themes they pick, first match first.
+    date_themes: Vec<(DateRule, ColorTheme)>,
+    /// Theme picked by a date rule for today, if any.
+    date_theme: Option<ColorTheme>,
+    /// Whether a theme was chosen by hand this session, which date themes then leave alone.
+    theme_chosen: bool,
+    /// Night dimming window as minutes since midnight `(start, end)`.
+    dim_window: Option<(u32, u32)>,
+    /// Start time the since display mode counts up from.
+    since: Option<chrono::DateTime<Local>>,
+    /// Colors the terminal supports, resolved from the config or environment.
+    color_depth: ColorDepth,
+    /// Whether the terminal background is dark (assumed until detected otherwise).
+    dark_terminal: bool,
+    /// Running pomodoro timer, replacing the clock while active.
+    pomodoro: Option<Pomodoro>,
+    /// Mirrored and/or trimmed copy of the clock font, with the settings it was built for.
+    clock_font_variant: Option<(ClockFontVariant, Font)>,
+    /// Theme drawn last frame, to notice when it changes.
+    shown_theme: Option<ColorTheme>,
+    /// Theme being crossfaded from and when the crossfade started.
+    theme_transition: Option<(ColorTheme, Instant)>,
+    /// Clock font drawn last frame, to notice when it changes.
+    shown_font: Option<String>,
+    /// Clock font being faded out and when the fade started.
+    font_transition: Option<(String, Instant)>,
+    /// Last rendered time string (for typewriter animation).
+    last_time_str: String,
+    /// When the typewriter reveal started.
+    typewriter_start: Option<Instant>,
+    /// Index of the first changed character in the time string (typewriter start).
+    typewriter_from: usize,
     /// Background animation state.
     background_state: BackgroundState,
+    /// Where the clock was drawn last frame, for the background safe zone.
+    clock_rect: Option<Rect>,
     /// System monitor for reactive backgrounds (lazy initialized).
     system_monitor: Option<SystemMonitor>,
 }
 
 impl App {
-    /// Construct a new instance of [`App`].
-    pub fn new() -> Self {
-        // Load configuration
-        let config = Config::load();
-
-        // Initialize font registry with bundled fonts
-        let mut font_registry = FontRegistry::new();
+    /// Construct a new instance of [`App`] for the terminal UI, with the user's config,
+    /// fonts and font cache, listening for remote commands if asked to.
+    pub fn new(args: &Args) -> Self {
+        // Problems are collected for the on-screen banner, since stderr is hidden by the
+        // alternate screen
+        let (config, mut warnings) = Config::load_result();
+        let fonts = FontSources::configured(args, &config, &mut warnings);
+        let mut app = Self::with_config(args, config, warnings, &fonts, Rc::new(SystemClock));
+        app.config_path = Some(Config::config_file_path());
+        app.remote = args
+            .listen
+            .as_deref()
+            .and_then(|path| match RemoteControl::listen(path) {
+                Ok(remote) => Some(remote),
+                Err(e) => {
+                    app.banner
+                        .push(format!("Failed to listen on {}: {e}", path.display()));
+                    None
+                }
+            });
+        app
+    }
 
-        // Load custom fonts from config directory
-        font_registry.load_custom_fonts(&Config::fonts_dir());
+    /// Construct a new instance of [`App`] from an already loaded config, showing
+    /// `warnings` in the banner. Nothing is saved until `config_path` is set, and no
+    /// remote socket is opened.
+    fn with_config(
+        args: &Args,
+        config: Config,
+        mut warnings: Warnings,
+        fonts: &FontSources,
+        clock: Rc<dyn Clock>,
+    ) -> Self {
+        let ClockSetup {
+            config,
+            font_registry,
+            label,
+            time_format_str,
+            font_override,
+            format_override,
+        } = ClockSetup::new(args, config, &mut warnings, fonts);
 
         // Get list of available fonts for settings dialog
         let available_fonts: Vec<String> = font_registry
@@ -97,8 +381,80 @@ impl App {
         // Create settings dialog
         let settings_dialog = SettingsDialog::new(available_fonts);
 
+        // Parse alarms, skipping invalid entries
+        let alarms = config
+            .alarms
+            .iter()
+            .filter_map(|spec| {
+                let alarm = Alarm::parse(spec);
+                if alarm.is_none() {
+                    warnings.push(format!("Invalid alarm '{spec}'"));
+                }
+                alarm
+            })
+            .collect();
+
+        // Parse the theme schedule, skipping invalid times
+        let theme_schedule = ThemeSchedule::new(
+            config
+                .auto_theme_schedule
+                .iter()
+                .filter_map(|(time, theme)| {
+                    let minute = parse_time_of_day(time);
+                    if minute.is_none() {
+                        warnings.push(format!("Invalid theme schedule time '{time}'"));
+                    }
+                    Some((minute?, *theme))
+                })
+                .collect(),
+        );
+
+        // Parse the date themes, skipping invalid rules
+        let date_themes = config
+            .date_themes
+            .iter()
+            .filter_map(|(rule, theme)| {
+                let parsed = DateRule::parse(rule);
+                if parsed.is_none() {
+                    warnings.push(format!("Invalid date theme rule '{rule}'"));
+                }
+                Some((parsed?, *theme))
+            })
+            .collect();
+
+        // Parse the start of the since display mode
+        let since = config.since.as_deref().and_then(|since| {
+            let start = NaiveDateTime::parse_from_str(since, "%Y-%m-%dT%H:%M:%S")
+                .ok()
+                .and_then(|naive| naive.and_local_timezone(Local).earliest());
+            if start.is_none() {
+                warnings.push(format!(
+                    "Invalid since time '{since}', showing the clock instead"
+                ));
+            }
+            start
+        });
+
+        // Parse the night dimming window, which needs both ends
+        let dim_window = match (&config.dim_start, &config.dim_end) {
+            (Some(start), Some(end)) => {
+                let window = parse_time_of_day(start).zip(parse_time_of_day(end));
+                if window.is_none() {
+                    warnings.push(format!("Invalid dim window '{start}' - '{end}'"));
+                }
+                window
+            }
+            _ => None,
+        };
+
+        let color_depth = match config.color_depth {
+            ColorDepth::Auto => detect_color_depth(),
+            depth => depth,
+        };
+        let dark_terminal = config.terminal_background != TerminalBackground::Light;
+
         // Get current time for initial state
-        let now = chrono::Local::now();
+        let now = clock.now();
 
         // Initialize system monitor if reactive background is selected
         let system_monitor = if config.background_style.is_reactive() {
@@ -111,252 +467,881 @@ impl App {
 
         Self {
             running: false,
-            time_format: config.time_format,
-            color_theme: config.color_theme,
-            animation_style: config.animation_style,
-            animation_speed: config.animation_speed,
-            colon_blink: config.colon_blink,
-            background_style: config.background_style,
-            current_font: config.font_name.clone(),
+            redraw: false,
+            config,
+            config_path: None,
+            save_due: None,
             font_registry,
+            banner: Banner::new(warnings),
+            remote: None,
+            label,
+            time_format_str,
+            font_override,
+            format_override,
             settings_dialog,
-            config,
-            animation_start: Instant::now(),
+            animation_start: clock.instant(),
+            animation_paused_at: None,
+            animation_paused_ms: 0,
+            held_background: None,
+            last_input: clock.instant(),
+            last_idle_cycle: None,
+            pre_idle: None,
+            goto: None,
             last_second: now.format("%S").to_string().parse().unwrap_or(0),
             last_minute: now.format("%M").to_string().parse().unwrap_or(0),
             last_hour: now.format("%H").to_string().parse().unwrap_or(0),
             flash_intensity: 0.0,
             flash_start: None,
-            background_state: BackgroundState::new(),
+            toast: None,
+            alarms,
+            alarm_active: false,
+            alarm_last_fired: None,
+            theme_schedule,
+            theme_schedule_index: None,
+            scheduled_theme: None,
+            date_themes,
+            date_theme: None,
+            theme_chosen: false,
+            dim_window,
+            since,
+            color_depth,
+            dark_terminal,
+            pomodoro: None,
+            shown_theme: None,
+            theme_transition: None,
+            shown_font: None,
+            font_transition: None,
+            last_time_str: String::new(),
+            clock_font_variant: None,
+            typewriter_start: None,
+            typewriter_from: 0,
+            background_state: BackgroundState::new(Rc::clone(&clock)),
+            clock_rect: None,
             system_monitor,
+            clock,
         }
     }
 
     /// Run the application's main loop.
+    /// Redraws are capped to the configured frame rate; input is handled in between.
     pub fn run(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
         self.running = true;
+        // Ask once, now that raw mode keeps the reply off the screen
+        if self.config.terminal_background == TerminalBackground::Auto
+            && let Some(rgb) = termbg::query_background()
+        {
+            self.dark_terminal = TerminalBackground::from_rgb(rgb) == TerminalBackground::Dark;
+        }
+        self.background_state.set_dark_terminal(self.dark_terminal);
+        let mut last_frame: Option<Instant> = None;
+        let mut last_second = None;
         while self.running {
-            terminal.draw(|frame| self.render(frame))?;
-            self.handle_crossterm_events()?;
+            let frame_interval = self.config.frame_interval();
+            let now = self.clock.now();
+            // A new second always redraws, whatever the frame rate; slower refresh modes
+            // wait for a new minute or a key
+            let due = match self.config.refresh_mode {
+                RefreshMode::RealTime => {
+                    last_second != Some(now.timestamp())
+                        || last_frame.is_none_or(|t| self.clock.elapsed(t) >= frame_interval)
+                }
+                RefreshMode::Minute => {
+                    last_second.is_none_or(|second| second / 60 != now.timestamp() / 60)
+                }
+                RefreshMode::Manual => last_frame.is_none(),
+            };
+            if self.redraw || due {
+                self.redraw = false;
+                last_frame = Some(self.clock.instant());
+                last_second = Some(now.timestamp());
+                terminal.draw(|frame| self.render(frame))?;
+            }
+
+            let until_frame = last_frame
+                .map(|t| frame_interval.saturating_sub(self.clock.elapsed(t)))
+                .unwrap_or_default();
+            let now = self.clock.now();
+            let input_poll = self.config.input_poll_interval();
+            let timeout = match self.config.refresh_mode {
+                RefreshMode::RealTime => poll_timeout(until_frame, input_poll, now.nanosecond()),
+                RefreshMode::Minute => {
+                    let until_minute = until_next_minute(now.second(), now.nanosecond());
+                    input_poll.map_or(until_minute, |poll| poll.min(until_minute))
+                }
+                RefreshMode::Manual => input_poll.unwrap_or(MANUAL_POLL_INTERVAL),
+            };
+            // Wake up in time for a pending save or to clear a toast
+            let wake_at = [self.save_due, self.toast.as_ref().map(Toast::expires_at)];
+            let timeout = wake_at.into_iter().flatten().fold(timeout, |timeout, at| {
+                timeout.min(at.saturating_duration_since(self.clock.instant()))
+            });
+            self.handle_crossterm_events(timeout)?;
+            self.handle_remote_commands();
+            self.save_if_due();
+            self.update_toast();
+        }
+        // Quick changes still waiting to be saved aren't lost on quit
+        if self.save_due.take().is_some() {
+            self.save_config();
         }
         Ok(())
     }
 
+    /// Apply any commands waiting on the remote control socket.
+    fn handle_remote_commands(&mut self) {
+        while let Some(request) = self.remote.as_ref().and_then(RemoteControl::try_recv) {
+            let result = self.run_remote_command(&request.command);
+            request.respond(result);
+        }
+    }
+
+    /// Apply one remote control command such as `theme rainbow` or `format 12h`.
+    /// Returns the command's output, if it has any.
+    fn run_remote_command(&mut self, command: &str) -> Result<Option<String>, String> {
+        let command = command.trim();
+        let (name, arg) = command
+            .split_once(char::is_whitespace)
+            .map_or((command, ""), |(name, arg)| (name, arg.trim()));
+        match name {
+            "theme" => {
+                self.config.color_theme = arg.parse()?;
+                self.mark_theme_chosen();
+            }
+            "font" => match self.font_registry.resolve_name(arg) {
+                Some(font) => {
+                    self.config.font_name = font.to_string();
+                    self.font_override = None;
+                }
+                None => return Err(format!("unknown font '{arg}'")),
+            },
+            "format" => {
+                self.config.time_format = arg.parse()?;
+                self.format_override = None;
+            }
+            "state" => return Ok(Some(self.state(&self.clock.now()).to_json())),
+            "quit" => self.quit(),
+            "" => return Err("empty command".to_string()),
+            other => return Err(format!("unknown command '{other}'")),
+        }
+        Ok(None)
+    }
+
+    /// Get the clock text view of the app's config and fonts.
+    fn text(&self) -> ClockText<'_> {
+        ClockText {
+            config: &self.config,
+            font_registry: &self.font_registry,
+            label: self.label.as_deref(),
+            time_format_str: self.time_format_str.as_deref(),
+            font_name: self
+                .font_override
+                .as_deref()
+                .unwrap_or(&self.config.font_name),
+            time_format: self.format_override.unwrap_or(self.config.time_format),
+        }
+    }
+
+    /// Snapshot what the clock shows for the `state` command, in the theme on screen.
+    fn state(&self, now: &chrono::DateTime<Local>) -> AppState {
+        AppState {
+            theme: self.base_theme(),
+            ..self.text().state(now)
+        }
+    }
+
     /// Renders the user interface.
     fn render(&mut self, frame: &mut Frame) {
-        let now = Local::now();
+        let now = self.clock.now();
 
         // Calculate animation elapsed time
-        let elapsed_ms = self.animation_start.elapsed().as_millis() as u64;
+        let elapsed_ms = self.animation_elapsed_ms();
 
         // Get metrics for reactive backgrounds
         let metrics = self.system_monitor.as_ref().map(|m| m.get_metrics());
 
-        // Render background first (behind everything else)
-        self.background_state.render(
-            frame,
-            self.background_style,
-            elapsed_ms,
-            self.animation_speed,
-            metrics.as_ref(),
-        );
+        // Fill with the solid background color, which shows through transparent cells
+        if let Some([r, g, b]) = self.config.bg_color.filter(|_| !self.config.transparent) {
+            let area = frame.area();
+            frame
+                .buffer_mut()
+                .set_style(area, Style::new().bg(Color::Rgb(r, g, b)));
+        }
+
+        // The optional border takes the outermost cells; everything else goes inside it
+        let full_area = frame.area();
+        let area = if self.config.show_border {
+            Block::bordered().inner(full_area)
+        } else {
+            full_area
+        };
 
         // Update flash intensity for reactive animation
         self.update_flash(&now);
 
-        // Get time components
-        let (hours, is_pm) = match self.time_format {
-            TimeFormat::TwentyFourHour => {
-                (now.format("%H").to_string().parse().unwrap_or(0), false)
+        // Render background first (behind everything else), clear of where the clock
+        // was last drawn
+        self.background_state
+            .configure(&self.config, self.color_theme());
+        let (margin_x, margin_y) = SAFE_ZONE_MARGIN;
+        let exclusion = self
+            .clock_rect
+            .filter(|_| self.config.safe_zone)
+            .map(|rect| {
+                Rect::new(
+                    rect.x.saturating_sub(margin_x),
+                    rect.y.saturating_sub(margin_y),
+                    rect.width + margin_x * 2,
+                    rect.height + margin_y * 2,
+                )
+            });
+        self.background_state.set_exclusion(exclusion);
+        match &self.held_background {
+            Some(held) if self.background_state.is_held() && held.area == area => {
+                frame.buffer_mut().merge(held);
             }
-            TimeFormat::TwelveHour => {
-                let h: u32 = now.format("%I").to_string().parse().unwrap_or(12);
-                let pm = now.format("%p").to_string() == "PM";
-                (h, pm)
+            _ => {
+                self.background_state.render(
+                    frame,
+                    area,
+                    self.config.background_style,
+                    self.config.animation_speed,
+                    metrics.as_ref(),
+                );
+                if self.background_state.is_held() {
+                    let mut held = Buffer::empty(area);
+                    for position in area.positions() {
+                        held[position] = frame.buffer_mut()[position].clone();
+                    }
+                    self.held_background = Some(held);
+                }
             }
-        };
-        let minutes: u32 = now.format("%M").to_string().parse().unwrap_or(0);
-        let seconds: u32 = now.format("%S").to_string().parse().unwrap_or(0);
+        }
+        if self.config.frame_flash && !self.config.transparent && self.flash_intensity > 0.0 {
+            background::lighten(
+                frame.buffer_mut(),
+                full_area,
+                self.flash_intensity * FRAME_FLASH_STRENGTH,
+            );
+        }
 
-        // Format date
-        let date_str = now.format("%A, %B %d, %Y").to_string();
+        self.update_alarms(&now);
+        self.update_pomodoro();
+        self.update_theme_schedule(&now);
+        self.update_date_theme(&now);
+        self.update_idle_cycle();
+
+        // A ringing alarm blinks the whole display with a full flash
+        let (animation_style, flash_intensity, blink_mode) = if self.alarm_active {
+            (AnimationStyle::Reactive, 1.0, BlinkMode::All)
+        } else if !self.config.refresh_mode.is_real_time() {
+            // Animations can't move when redrawing once a minute or less
+            (AnimationStyle::None, 0.0, BlinkMode::None)
+        } else {
+            (
+                self.config.animation_style,
+                self.flash_intensity,
+                self.config.blink_mode,
+            )
+        };
 
-        let color = self.color_theme.color();
-        let area = frame.area();
+        let theme = self.color_theme();
+        self.update_theme_transition(theme);
+        let anim = AnimationContext {
+            style: animation_style,
+            speed: self.config.animation_speed,
+            shift_speed: self
+                .config
+                .shift_speed
+                .unwrap_or(self.config.animation_speed),
+            shift_hue: self.config.animate_dynamic_themes || !theme.is_dynamic(),
+            elapsed_ms,
+            flash_intensity,
+            flash_color: self.config.flash_color.map(|[r, g, b]| (r, g, b)),
+            pulse_min_brightness: self.config.pulse_min_brightness,
+        };
 
-        // Build time string
-        let time_str = match self.time_format {
-            TimeFormat::TwentyFourHour => {
-                format!("{hours:02}:{minutes:02}:{seconds:02}")
-            }
-            TimeFormat::TwelveHour => {
-                let ampm = if is_pm { "PM" } else { "AM" };
-                format!("{hours:2}:{minutes:02}:{seconds:02} {ampm}")
-            }
-        };
-
-        // Get current font and render
-        let font = self.font_registry.get_or_default(&self.current_font);
-        let time_lines = font.render_text(&time_str);
-        let font_height = font.height as u16;
-
-        // Create vertical layout for centering
-        let chunks = Layout::vertical([
-            Constraint::Fill(1),             // Top padding
-            Constraint::Length(font_height), // Big digits (dynamic height)
-            Constraint::Length(2),           // Spacing
-            Constraint::Length(1),           // Date
-            Constraint::Fill(1),             // Bottom padding
-            Constraint::Length(1),           // Help text
-        ])
-        .split(area);
-
-        // Render big time
-        let height = time_lines.len();
-        let width = time_lines.first().map(|s| s.chars().count()).unwrap_or(0);
-
-        // Build colon position mask for blink effect
-        // Maps x-positions in rendered ASCII art back to colon characters in time_str
-        let colon_positions: Vec<bool> = if self.colon_blink {
-            let mut mask = vec![false; width];
-            let mut x_pos = 0;
-            for ch in time_str.chars() {
-                let char_width = font.char_width(ch);
-                if ch == ':' {
-                    for i in 0..char_width {
-                        if x_pos + i < mask.len() {
-                            mask[x_pos + i] = true;
-                        }
-                    }
+        // A running pomodoro shows its countdown and phase instead of the clock.
+        // The since mode counts up from its start, or shows the clock if there is none.
+        let since = self
+            .since
+            .filter(|_| self.config.display_mode == DisplayMode::Since);
+        let (time_str, date_str) = match (&self.pomodoro, since) {
+            (Some(pomodoro), _) => {
+                let mut label = pomodoro.phase().label().to_string();
+                if pomodoro.is_paused() {
+                    label.push_str(" · PAUSED");
                 }
-                x_pos += char_width;
+                let remaining = pomodoro
+                    .remaining_str(self.clock.instant())
+                    .replace(':', &self.config.separator.to_string());
+                (remaining, label)
             }
-            mask
+            (None, Some(since)) => (
+                format_elapsed((now - since).num_seconds(), self.config.separator),
+                since.format("since %A, %B %d, %Y").to_string(),
+            ),
+            (None, None) => (
+                self.text().clock_time_str(&now),
+                now.format("%A, %B %d, %Y").to_string(),
+            ),
+        };
+
+        let color = theme.color();
+        if self.config.show_border {
+            let mut block = Block::bordered().border_style(Style::new().fg(color));
+            if let Some(title) = &self.config.title {
+                block = block.title(Line::from(format!(" {title} ")).centered());
+            }
+            frame.render_widget(block, full_area);
+        }
+
+        // The analog face fills the space left by the text rows; a pomodoro countdown stays digital
+        let analog = self.config.display_mode == DisplayMode::Analog && self.pomodoro.is_none();
+        let extra_height = u16::from(self.config.show_extra_info);
+        let progress_height = u16::from(self.config.show_progress);
+        let label_height = u16::from(self.label.is_some());
+        // The help line comes back while typing a number after `g`, to show the prompt
+        let footer_height = u16::from(self.config.show_footer || self.goto.is_some());
+        let text_rows = label_height + progress_height + 2 + 1 + extra_height + footer_height;
+        let clock_font_name = self.fitting_font_name(area.height.saturating_sub(text_rows));
+        let clock_font_name = self.update_font_transition(clock_font_name);
+        self.update_clock_font(&clock_font_name);
+        let compact = if analog {
+            None
+        } else {
+            self.text().compact_split(&time_str)
+        };
+        let clock_height = if analog {
+            area.height
+                .saturating_sub(5 + footer_height + label_height + extra_height + progress_height)
         } else {
-            vec![]
+            // Wobbling columns get a spare row above and below to move into
+            let wobble_rows = if animation_style == AnimationStyle::Wobble {
+                2
+            } else {
+                0
+            };
+            let font = self.clock_font(&clock_font_name);
+            let seconds_height = compact.map_or(0, |_| self.text().seconds_font(font).height);
+            (font.height + seconds_height) as u16 + wobble_rows
+        };
+
+        let rows = clock_layout(
+            area,
+            label_height,
+            clock_height,
+            progress_height,
+            extra_height,
+            footer_height,
+        );
+
+        if let Some(label) = &self.label {
+            let (text, colors) = markup::flatten(&markup::parse(label));
+            self.render_colored_line(frame.buffer_mut(), rows.label, &text, &colors, &anim);
+        }
+
+        // Render the clock, remembering its columns to line up the progress bar
+        let (clock_x, clock_width) = if analog {
+            let angle = self.config.gradient_angle;
+            analog::render(
+                frame.buffer_mut(),
+                rows.clock,
+                now.time(),
+                theme,
+                angle,
+                &anim,
+            )
+            .unwrap_or((rows.clock.x, rows.clock.width))
+        } else if let Some((time, seconds)) = compact {
+            self.render_compact_digits(
+                frame.buffer_mut(),
+                rows.clock,
+                &clock_font_name,
+                (time, seconds),
+                blink_mode,
+                &anim,
+            )
+        } else {
+            self.render_digits(
+                frame.buffer_mut(),
+                rows.clock,
+                &clock_font_name,
+                &time_str,
+                blink_mode,
+                &anim,
+            )
         };
 
-        // Render time directly to buffer, skipping spaces to preserve background
-        let chunk = chunks[1];
-        let text_width = width as u16;
-        let start_x = chunk.x + (chunk.width.saturating_sub(text_width)) / 2;
+        self.clock_rect = Some(Rect::new(
+            clock_x,
+            rows.clock.y,
+            clock_width,
+            rows.clock.height,
+        ));
+
+        // Render progress through the current minute/hour/day, as wide as the clock
+        if self.config.show_progress {
+            let seconds_of_day = f64::from(now.num_seconds_from_midnight())
+                + f64::from(now.nanosecond().min(999_999_999)) / 1e9;
+            let fraction = self.config.progress_scope.fraction(seconds_of_day);
+            let bar_area = Rect::new(clock_x, rows.progress.y, clock_width, rows.progress.height)
+                .intersection(rows.progress);
+            self.render_progress_bar(frame.buffer_mut(), bar_area, fraction, &anim);
+        }
+
+        // Render date
+        self.render_text_line(frame.buffer_mut(), rows.date, &date_str, &anim);
+
+        // Render ISO week and day of year
+        if self.config.show_extra_info {
+            let extra_str = format!(
+                "Week {:02} · Day {:03}",
+                now.iso_week().week(),
+                now.ordinal()
+            );
+            self.render_text_line(frame.buffer_mut(), rows.extra, &extra_str, &anim);
+        }
 
-        let buf = frame.buffer_mut();
-        for (line_idx, line) in time_lines.iter().enumerate() {
-            let y_pos = chunk.y + line_idx as u16;
-            if y_pos >= chunk.y + chunk.height {
-                break;
+        // Render help text, which prompts for the number while going to a theme or font
+        let mut help_spans = Vec::new();
+        if let Some(number) = &self.goto {
+            help_spans.extend([
+                "go to ".dark_gray(),
+                format!("{number}_").bold().fg(color),
+                "  c".bold().fg(color),
+                " theme  ".dark_gray(),
+                "Enter".bold().fg(color),
+                " font  ".dark_gray(),
+                "Esc".bold().fg(color),
+                " cancel".dark_gray(),
+            ]);
+        } else {
+            if self.animation_paused_at.is_some() {
+                help_spans.extend(["⏸".bold().fg(color), "  ".dark_gray()]);
+            }
+            if self.alarm_active {
+                help_spans.extend(["d".bold().fg(color), " dismiss alarm  ".dark_gray()]);
             }
+            if self.pomodoro.is_some() {
+                help_spans.extend([
+                    "n".bold().fg(color),
+                    " skip  ".dark_gray(),
+                    "P".bold().fg(color),
+                    " stop  ".dark_gray(),
+                ]);
+            }
+            help_spans.extend([
+                "q".bold().fg(color),
+                " quit  ".dark_gray(),
+                "t".bold().fg(color),
+                " 12/24h  ".dark_gray(),
+                "m".bold().fg(color),
+                " mode  ".dark_gray(),
+                "c".bold().fg(color),
+                " color  ".dark_gray(),
+                "1-9/g".bold().fg(color),
+                " jump  ".dark_gray(),
+                "a".bold().fg(color),
+                " anim  ".dark_gray(),
+                "b".bold().fg(color),
+                " bg  ".dark_gray(),
+                "+/-".bold().fg(color),
+                " speed  ".dark_gray(),
+                "0".bold().fg(color),
+                " restart  ".dark_gray(),
+                "p".bold().fg(color),
+                " pomodoro  ".dark_gray(),
+                "s".bold().fg(color),
+                " settings".dark_gray(),
+            ]);
+        }
+        if rows.help.height > 0 {
+            let help = Line::from(help_spans).centered();
+            frame.render_widget(help, rows.help);
+        }
 
-            for (char_idx, ch) in line.chars().enumerate() {
-                // Skip spaces to preserve background transparency
-                if ch == ' ' {
-                    continue;
+        // Night dimming scales everything drawn so far; dialogs stay readable
+        if let Some((start, end)) = self.dim_window
+            && in_time_window(now.hour() * 60 + now.minute(), start, end)
+        {
+            let level = self.config.dim_level;
+            let buf = frame.buffer_mut();
+            let dim_area = buf.area;
+            for y in dim_area.top()..dim_area.bottom() {
+                for x in dim_area.left()..dim_area.right() {
+                    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                        let (fg, bg) = (cell.fg, cell.bg);
+                        cell.set_fg(dim(fg, level));
+                        cell.set_bg(dim(bg, level));
+                    }
                 }
+            }
+        }
 
-                let x_pos = start_x + char_idx as u16;
-                if x_pos >= chunk.x + chunk.width {
-                    continue;
+        if let Some(toast) = self.visible_toast() {
+            toast.render(frame, area, color, self.clock.instant());
+        }
+
+        // Render settings dialog if visible
+        self.banner.render(frame, area);
+        self.settings_dialog.render(frame, area, color);
+
+        // Without truecolor, map RGB colors to the 16 ANSI colors ourselves
+        if self.color_depth == ColorDepth::Ansi16 {
+            let buf = frame.buffer_mut();
+            let quantize_area = buf.area;
+            for y in quantize_area.top()..quantize_area.bottom() {
+                for x in quantize_area.left()..quantize_area.right() {
+                    if let Some(cell) = buf.cell_mut(Position::new(x, y)) {
+                        let (fg, bg) = (cell.fg, cell.bg);
+                        cell.set_fg(quantize_ansi16(fg));
+                        cell.set_bg(quantize_ansi16(bg));
+                    }
                 }
+            }
+        }
 
-                // Apply colon blink by skipping colon characters during "off" phase
-                let is_colon = colon_positions.get(char_idx).copied().unwrap_or(false);
-                let should_hide = self.colon_blink && is_colon && !is_colon_visible(elapsed_ms);
-                if should_hide {
-                    continue;
+        // Leave empty cells as they were, so nothing is written over the terminal's background
+        if self.config.transparent {
+            for cell in &mut frame.buffer_mut().content {
+                if cell.symbol() == " " {
+                    cell.reset();
                 }
+            }
+        }
+    }
 
-                // Get base color
-                let base_color = if self.color_theme.is_dynamic() {
-                    self.color_theme
-                        .color_at_position(char_idx, line_idx, width, height)
-                } else {
-                    color
-                };
+    /// Render the time in the big font with [`ClockWidget`], skipping spaces to
+    /// preserve background. Returns the columns the text spans as `(x, width)`.
+    fn render_digits(
+        &mut self,
+        buf: &mut Buffer,
+        area: Rect,
+        font_name: &str,
+        time_str: &str,
+        blink_mode: BlinkMode,
+        anim: &AnimationContext,
+    ) -> (u16, u16) {
+        let typewriter_progress = self.update_typewriter(time_str);
+        let widget = self
+            .digits_widget(self.clock_font(font_name), time_str, blink_mode, anim)
+            .typewriter(self.typewriter_from, typewriter_progress);
+        let mut state = ClockWidgetState::default();
+        widget.render(area, buf, &mut state);
+        (state.area.x, state.area.width)
+    }
 
-                // Apply animation
-                let animated_color = apply_animation(
-                    base_color,
-                    self.animation_style,
-                    self.animation_speed,
-                    elapsed_ms,
-                    char_idx,
-                    width,
-                    self.flash_intensity,
-                );
+    /// Render the hours and minutes with the seconds below them in a smaller font,
+    /// returning the columns both cover.
+    fn render_compact_digits(
+        &mut self,
+        buf: &mut Buffer,
+        area: Rect,
+        font_name: &str,
+        (time_str, seconds_str): (&str, &str),
+        blink_mode: BlinkMode,
+        anim: &AnimationContext,
+    ) -> (u16, u16) {
+        let seconds_font = self.text().seconds_font(self.clock_font(font_name));
+        let seconds_font = if self.config.mirror {
+            Cow::Owned(seconds_font.mirrored())
+        } else {
+            Cow::Borrowed(seconds_font)
+        };
+        let seconds_height = (seconds_font.height as u16).min(area.height);
+        let seconds_area = Rect::new(
+            area.x,
+            area.bottom() - seconds_height,
+            area.width,
+            seconds_height,
+        );
+        let mut state = ClockWidgetState::default();
+        self.digits_widget(&seconds_font, seconds_str, blink_mode, anim)
+            .render(seconds_area, buf, &mut state);
 
-                // Write directly to buffer
-                if let Some(cell) = buf.cell_mut(Position::new(x_pos, y_pos)) {
-                    cell.set_char(ch);
-                    cell.set_fg(animated_color);
-                }
+        let time_area = Rect {
+            height: area.height - seconds_height,
+            ..area
+        };
+        let (x, width) = self.render_digits(buf, time_area, font_name, time_str, blink_mode, anim);
+        let left = x.min(state.area.x);
+        let right = (x + width).max(state.area.right());
+        (left, right - left)
+    }
+
+    /// Build the widget for a line of big digits with the configured look.
+    fn digits_widget<'a>(
+        &self,
+        font: &'a Font,
+        time_str: &'a str,
+        blink_mode: BlinkMode,
+        anim: &AnimationContext,
+    ) -> ClockWidget<'a> {
+        // A reflection also reverses the order of the glyphs
+        let rtl = self.config.text_direction.is_rtl(font.rtl) != self.config.mirror;
+        let fill = self
+            .config
+            .bg_color
+            .map_or((0, 0, 0), |[r, g, b]| (r, g, b));
+        let mut widget = ClockWidget::new(font, time_str)
+            .theme(self.color_theme())
+            .gradient_angle(self.config.gradient_angle)
+            .blink_mode(blink_mode)
+            .animation(*anim)
+            .separator(self.text().clock_separator(time_str))
+            .align(self.config.align)
+            .letter_spacing(self.config.letter_spacing)
+            .rtl(rtl)
+            .colon_blink(
+                self.config.colon_blink_period_ms,
+                self.config.colon_on_fraction,
+            )
+            .colon_fade(self.config.colon_fade)
+            .fill(fill)
+            .shadow(self.config.glyph_shadow)
+            .contrast_boost(self.config.contrast_boost)
+            .brightness(self.font_brightness());
+        if let Some((from, progress)) = self.theme_transition_progress() {
+            widget = widget.theme_transition(from, progress);
+        }
+        widget
+    }
+
+    /// Get the configured font, or a smaller fallback when it is taller than `rows`.
+    fn fitting_font_name(&self, rows: u16) -> String {
+        let font = self.font_registry.get_or_default(self.text().font_name);
+        if font.height <= usize::from(rows) {
+            return font.name.clone();
+        }
+        let smaller: Vec<&Font> = FALLBACK_FONTS
+            .iter()
+            .filter_map(|name| self.font_registry.get(name))
+            .filter(|fallback| fallback.height < font.height)
+            .collect();
+        // If nothing fits, clip the smallest font rather than the configured one
+        smaller
+            .iter()
+            .find(|fallback| fallback.height <= usize::from(rows))
+            .or(smaller.last())
+            .map_or_else(|| font.name.clone(), |fallback| fallback.name.clone())
+    }
+
+    /// Rebuild the mirrored/trimmed clock font if those settings or the font changed.
+    fn update_clock_font(&mut self, font_name: &str) {
+        // Trim to what the clock can show, so the height doesn't change with the time
+        let trim_to = self.config.trim_font_rows.then(|| {
+            let mut used = format!("0123456789APM{}", self.config.separator);
+            if self.config.precision != TimePrecision::Seconds {
+                used.push('.');
+            }
+            used.extend(self.time_format_str.as_deref().and_then(format_literals));
+            used
+        });
+        let variant = ClockFontVariant {
+            name: font_name.to_string(),
+            mirror: self.config.mirror,
+            trim_to,
+        };
+        if !variant.mirror && variant.trim_to.is_none() {
+            self.clock_font_variant = None;
+            return;
+        }
+        if self
+            .clock_font_variant
+            .as_ref()
+            .is_some_and(|(built, _)| *built == variant)
+        {
+            return;
+        }
+
+        let mut font = self.font_registry.get_or_default(font_name).clone();
+        if let Some(used) = &variant.trim_to {
+            font = font.trimmed(used);
+        }
+        if variant.mirror {
+            font = font.mirrored();
+        }
+        self.clock_font_variant = Some((variant, font));
+    }
+
+    /// Get the font for the big clock, mirrored and trimmed if enabled.
+    fn clock_font(&self, font_name: &str) -> &Font {
+        match &self.clock_font_variant {
+            Some((variant, font)) if variant.name == font_name => font,
+            _ => self.font_registry.get_or_default(font_name),
+        }
+    }
+
+    /// Get the color theme in effect, which a pomodoro phase may override,
+    /// adjusted to stay readable on the terminal background.
+    fn color_theme(&self) -> ColorTheme {
+        let theme = match &self.pomodoro {
+            Some(pomodoro) if self.config.pomodoro_auto_theme => pomodoro.phase().color_theme(),
+            _ => self.date_theme.unwrap_or(self.base_theme()),
+        };
+        theme.for_terminal(self.dark_terminal)
+    }
+
+    /// Get the scheduled theme if one applies, else the configured theme.
+    fn base_theme(&self) -> ColorTheme {
+        self.scheduled_theme.unwrap_or(self.config.color_theme)
+    }
+
+    /// Note that a theme was chosen by hand, which overrides date themes for the
+    /// session and the schedule until its next threshold.
+    fn mark_theme_chosen(&mut self) {
+        self.theme_chosen = true;
+        self.scheduled_theme = None;
+    }
+
+    /// Get the theme's base colors for a row, crossfaded from the previous theme
+    /// while switching.
+    fn theme_row_colors(&self, y: usize, width: usize, height: usize) -> Vec<Color> {
+        let angle = self.config.gradient_angle;
+        let colors = row_colors(self.color_theme(), angle, y, width, height);
+        match self.theme_transition_progress() {
+            Some((from, progress)) => blend_row_colors(
+                &row_colors(from, angle, y, width, height),
+                &colors,
+                progress,
+            ),
+            None => colors,
+        }
+    }
+
+    /// Get how far along a theme or font transition started at `start` is (0.0 to 1.0),
+    /// or `None` once it's over or when transitions are off.
+    fn transition_progress(&self, start: Instant) -> Option<f32> {
+        // Frames are too far apart to animate anything outside real time
+        if self.config.transition_ms == 0 || !self.config.refresh_mode.is_real_time() {
+            return None;
+        }
+        let elapsed = self.clock.elapsed(start).as_millis() as f32;
+        let progress = elapsed / self.config.transition_ms as f32;
+        (progress < 1.0).then_some(progress)
+    }
+
+    /// Start a crossfade when the theme differs from last frame's.
+    fn update_theme_transition(&mut self, theme: ColorTheme) {
+        if let Some(shown) = self.shown_theme
+            && shown != theme
+        {
+            self.theme_transition = Some((shown, self.clock.instant()));
+        }
+        self.shown_theme = Some(theme);
+    }
+
+    /// Get the theme being crossfaded from and how far along the crossfade is.
+    fn theme_transition_progress(&self) -> Option<(ColorTheme, f32)> {
+        let (from, start) = self.theme_transition?;
+        Some((from, self.transition_progress(start)?))
+    }
+
+    /// Start a fade when the clock font differs from last frame's, and get the font
+    /// to draw: the old one while it fades out, then the new one as it fades in.
+    fn update_font_transition(&mut self, font_name: String) -> String {
+        if let Some(shown) = self.shown_font.take()
+            && shown != font_name
+        {
+            self.font_transition = Some((shown, self.clock.instant()));
+        }
+        self.shown_font = Some(font_name.clone());
+        match &self.font_transition {
+            Some((old, start)) if self.transition_progress(*start).is_some_and(|t| t < 0.5) => {
+                old.clone()
             }
+            _ => font_name,
         }
+    }
+
+    /// Get the clock brightness during a font fade: dimming to nothing halfway, then back.
+    fn font_brightness(&self) -> f32 {
+        self.font_transition
+            .as_ref()
+            .and_then(|(_, start)| self.transition_progress(*start))
+            .map_or(1.0, |progress| (2.0 * progress - 1.0).abs())
+    }
+
+    /// Render a centered line of small text directly to the buffer,
+    /// skipping spaces to preserve background transparency.
+    fn render_text_line(&self, buf: &mut Buffer, area: Rect, text: &str, anim: &AnimationContext) {
+        self.render_colored_line(buf, area, text, &[], anim);
+    }
 
-        // Render date directly to buffer, skipping spaces to preserve background
-        let date_chunk = chunks[3];
-        let date_width = date_str.len() as u16;
-        let date_start_x = date_chunk.x + (date_chunk.width.saturating_sub(date_width)) / 2;
-        let date_y = date_chunk.y;
+    /// Render a line like [`App::render_text_line`], with characters that have a color
+    /// in `overrides` drawn in it instead of the theme (still animated).
+    fn render_colored_line(
+        &self,
+        buf: &mut Buffer,
+        area: Rect,
+        text: &str,
+        overrides: &[Option<Color>],
+        anim: &AnimationContext,
+    ) {
+        let text_len = text.chars().count();
+        let start_x = area.x + (area.width.saturating_sub(text_len as u16)) / 2;
+        let mut base = self.theme_row_colors(0, text_len, 1);
+        for (color, &override_color) in base.iter_mut().zip(overrides) {
+            if let Some(override_color) = override_color {
+                *color = override_color;
+            }
+        }
+        let colors = animate_line(&base, anim);
 
-        let buf = frame.buffer_mut();
-        for (char_idx, ch) in date_str.chars().enumerate() {
-            // Skip spaces to preserve background transparency
+        for (char_idx, ch) in text.chars().enumerate() {
             if ch == ' ' {
                 continue;
             }
 
-            let x_pos = date_start_x + char_idx as u16;
-            if x_pos >= date_chunk.x + date_chunk.width {
+            let x_pos = start_x + char_idx as u16;
+            if x_pos >= area.x + area.width {
                 continue;
             }
 
-            // Get base color
-            let base_color = if self.color_theme.is_dynamic() {
-                self.color_theme
-                    .color_at_position(char_idx, 0, date_str.len(), 1)
-            } else {
-                color
-            };
-
-            // Apply animation
-            let animated_color = apply_animation(
-                base_color,
-                self.animation_style,
-                self.animation_speed,
-                elapsed_ms,
-                char_idx,
-                date_str.len(),
-                self.flash_intensity,
-            );
-
             // Write directly to buffer
-            if let Some(cell) = buf.cell_mut(Position::new(x_pos, date_y)) {
+            if let Some(cell) = buf.cell_mut(Position::new(x_pos, area.y)) {
                 cell.set_char(ch);
-                cell.set_fg(animated_color);
-            }
-        }
-
-        // Render help text
-        let help = Line::from(vec![
-            "q".bold().fg(color),
-            " quit  ".dark_gray(),
-            "t".bold().fg(color),
-            " 12/24h  ".dark_gray(),
-            "c".bold().fg(color),
-            " color  ".dark_gray(),
-            "a".bold().fg(color),
-            " anim  ".dark_gray(),
-            "b".bold().fg(color),
-            " bg  ".dark_gray(),
-            "s".bold().fg(color),
-            " settings".dark_gray(),
-        ])
-        .centered();
-        frame.render_widget(help, chunks[5]);
+                cell.set_fg(colors[char_idx]);
+            }
+        }
+    }
 
-        // Render settings dialog if visible
-        self.settings_dialog.render(frame, area, color);
+    /// Render a one-row progress bar filled proportionally with block characters.
+    fn render_progress_bar(
+        &self,
+        buf: &mut Buffer,
+        area: Rect,
+        fraction: f32,
+        anim: &AnimationContext,
+    ) {
+        const PARTIAL_BLOCKS: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+        let width = area.width as usize;
+        let filled_eighths = (fraction.clamp(0.0, 1.0) * width as f32 * 8.0) as usize;
+        let colors = animate_line(&self.theme_row_colors(0, width, 1), anim);
+
+        for (char_idx, &color) in colors.iter().enumerate() {
+            let eighths = filled_eighths.saturating_sub(char_idx * 8).min(8);
+            let position = Position::new(area.x + char_idx as u16, area.y);
+            let Some(cell) = buf.cell_mut(position) else {
+                continue;
+            };
+
+            // Unfilled track stays dim so the bar's length is always visible
+            if eighths == 0 {
+                cell.set_char('─');
+                cell.set_fg(Color::DarkGray);
+                continue;
+            }
+
+            let ch = if eighths == 8 {
+                '█'
+            } else {
+                PARTIAL_BLOCKS[eighths]
+            };
+            cell.set_char(ch);
+            cell.set_fg(color);
+        }
     }
 
     /// Update flash intensity for reactive animation.
@@ -365,28 +1350,33 @@ impl App {
         let minute: u32 = now.format("%M").to_string().parse().unwrap_or(0);
         let hour: u32 = now.format("%H").to_string().parse().unwrap_or(0);
 
-        // Check for time changes and trigger flash
+        // Check for time changes and trigger flash, skipping changes finer than the granularity
+        let granularity = self.config.reactive_granularity;
         if hour != self.last_hour {
-            self.flash_intensity = 1.0; // Full flash for hour change
-            self.flash_start = Some(Instant::now());
+            self.flash_intensity = self.config.flash_hour; // Full flash for hour change
+            self.flash_start = Some(self.clock.instant());
             self.last_hour = hour;
             self.last_minute = minute;
             self.last_second = second;
         } else if minute != self.last_minute {
-            self.flash_intensity = 0.7; // Strong flash for minute change
-            self.flash_start = Some(Instant::now());
+            if granularity.flashes_on(ReactiveGranularity::Minute) {
+                self.flash_intensity = self.config.flash_minute; // Strong flash for minute change
+                self.flash_start = Some(self.clock.instant());
+            }
             self.last_minute = minute;
             self.last_second = second;
         } else if second != self.last_second {
-            self.flash_intensity = 0.3; // Subtle flash for second change
-            self.flash_start = Some(Instant::now());
+            if granularity.flashes_on(ReactiveGranularity::Second) {
+                self.flash_intensity = self.config.flash_second; // Subtle flash for second change
+                self.flash_start = Some(self.clock.instant());
+            }
             self.last_second = second;
         }
 
         // Decay flash over time
         if let Some(flash_start) = self.flash_start {
-            let decay_ms = self.animation_speed.flash_decay_ms();
-            let flash_elapsed = flash_start.elapsed().as_millis() as f32;
+            let decay_ms = self.config.animation_speed.flash_decay_ms();
+            let flash_elapsed = self.clock.elapsed(flash_start).as_millis() as f32;
             let decay_progress = (flash_elapsed / decay_ms as f32).min(1.0);
             self.flash_intensity *= 1.0 - decay_progress;
 
@@ -397,57 +1387,354 @@ impl App {
         }
     }
 
-    /// Reads the crossterm events and updates the state of [`App`].
-    /// Uses polling with timeout for real-time clock updates.
-    fn handle_crossterm_events(&mut self) -> color_eyre::Result<()> {
-        // Poll for events with 100ms timeout for smooth clock updates
-        if event::poll(Duration::from_millis(100))? {
-            match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
-                Event::Mouse(_) => {}
-                Event::Resize(_, _) => {}
-                _ => {}
-            }
+    /// Fire any alarm matching the current minute.
+    fn update_alarms(&mut self, now: &chrono::DateTime<chrono::Local>) {
+        let Some(minute_start) = now.naive_local().with_second(0) else {
+            return;
+        };
+        if self.alarm_last_fired == Some(minute_start) {
+            return;
         }
-        Ok(())
-    }
+
+        let weekday = now.weekday().num_days_from_monday();
+        if self
+            .alarms
+            .iter()
+            .any(|alarm| alarm.matches(now.hour(), now.minute(), weekday))
+        {
+            self.alarm_active = true;
+            self.alarm_last_fired = Some(minute_start);
+            self.ring_bell();
+            let message = format!("Alarm {:02}:{:02}", now.hour(), now.minute());
+            self.show_toast(message, ALARM_TOAST_DURATION);
+        }
+    }
+
+    /// Switch to the scheduled theme when a new time-of-day threshold is reached.
+    fn update_theme_schedule(&mut self, now: &chrono::DateTime<chrono::Local>) {
+        let minute_of_day = now.hour() * 60 + now.minute();
+        if let Some((idx, theme)) = self.theme_schedule.active(minute_of_day)
+            && self.theme_schedule_index != Some(idx)
+        {
+            self.theme_schedule_index = Some(idx);
+            self.scheduled_theme = Some(theme);
+        }
+    }
+
+    /// Pick today's date theme, unless a theme was chosen by hand this session.
+    fn update_date_theme(&mut self, now: &chrono::DateTime<chrono::Local>) {
+        let weekday = now.weekday().num_days_from_monday();
+        self.date_theme = self
+            .date_themes
+            .iter()
+            .find(|(rule, _)| !self.theme_chosen && rule.matches(now.month(), now.day(), weekday))
+            .map(|(_, theme)| *theme);
+    }
+
+    /// Step through themes, backgrounds and animations while no keys are pressed.
+    fn update_idle_cycle(&mut self) {
+        let Some(interval) = self.config.idle_cycle_interval() else {
+            return;
+        };
+        let last_step = self.last_idle_cycle.unwrap_or(self.last_input);
+        if self.clock.elapsed(last_step) >= interval {
+            self.last_idle_cycle = Some(self.clock.instant());
+            // Idle steps are a screensaver, not choices worth saving
+            let save_due = self.save_due;
+            self.pre_idle.get_or_insert(IdleSnapshot {
+                theme_chosen: self.theme_chosen,
+                scheduled_theme: self.scheduled_theme,
+                color_theme: self.config.color_theme,
+                background_style: self.config.background_style,
+                animation_style: self.config.animation_style,
+            });
+            self.cycle_color_theme();
+            self.cycle_background();
+            self.cycle_animation();
+            self.save_due = save_due;
+        }
+    }
+
+    /// Flash and ring the bell when the pomodoro phase changes.
+    fn update_pomodoro(&mut self) {
+        let now = self.clock.instant();
+        if self
+            .pomodoro
+            .as_mut()
+            .is_some_and(|pomodoro| pomodoro.update(now))
+        {
+            self.flash_intensity = 1.0;
+            self.flash_start = Some(self.clock.instant());
+            self.ring_bell();
+        }
+    }
+
+    /// Ring the terminal bell if enabled.
+    fn ring_bell(&self) {
+        if self.config.bell {
+            let mut stdout = std::io::stdout();
+            let _ = stdout.write_all(b"\x07");
+            let _ = stdout.flush();
+        }
+    }
+
+    /// Start the pomodoro timer, or pause/resume it if already running.
+    fn toggle_pomodoro(&mut self) {
+        let now = self.clock.instant();
+        match &mut self.pomodoro {
+            Some(pomodoro) => pomodoro.toggle_pause(now),
+            None => {
+                let minutes = |m: u32| Duration::from_secs(u64::from(m.max(1)) * 60);
+                self.pomodoro = Some(Pomodoro::new(
+                    minutes(self.config.pomodoro_work_minutes),
+                    minutes(self.config.pomodoro_break_minutes),
+                    now,
+                ));
+            }
+        }
+    }
+
+    /// Skip to the next pomodoro phase.
+    fn skip_pomodoro_phase(&mut self) {
+        if let Some(pomodoro) = &mut self.pomodoro {
+            pomodoro.skip(self.clock.instant());
+        }
+    }
+
+    /// Stop the pomodoro timer and return to the clock.
+    fn stop_pomodoro(&mut self) {
+        self.pomodoro = None;
+    }
+
+    /// Stop the ringing alarm.
+    fn dismiss_alarm(&mut self) {
+        self.alarm_active = false;
+    }
+
+    /// Track time string changes and get the typewriter reveal progress (0.0 to 1.0).
+    fn update_typewriter(&mut self, time_str: &str) -> f32 {
+        if self.config.animation_style != AnimationStyle::Typewriter {
+            self.last_time_str.clear();
+            return 1.0;
+        }
+
+        if time_str != self.last_time_str {
+            // Only the characters from the first change onward are retyped
+            self.typewriter_from = time_str
+                .chars()
+                .zip(self.last_time_str.chars())
+                .position(|(a, b)| a != b)
+                .unwrap_or(0);
+            self.typewriter_start = Some(self.clock.instant());
+            self.last_time_str = time_str.to_string();
+        }
+
+        match self.typewriter_start {
+            Some(start) => {
+                let duration_ms = self.config.animation_speed.typewriter_duration_ms();
+                (self.clock.elapsed(start).as_millis() as f32 / duration_ms as f32).min(1.0)
+            }
+            None => 1.0,
+        }
+    }
+
+    /// Reads the crossterm events and updates the state of [`App`].
+    /// Uses polling with timeout for real-time clock updates.
+    fn handle_crossterm_events(&mut self, timeout: Duration) -> color_eyre::Result<()> {
+        // Poll for events until the next frame is due
+        if event::poll(timeout)? {
+            match event::read()? {
+                Event::Key(key) if key.kind == KeyEventKind::Press => {
+                    self.on_key_event(key);
+                    // Show the effect of a key right away
+                    self.redraw = true;
+                }
+                Event::Mouse(_) => {}
+                Event::Resize(_, _) => {
+                    // Rebuild the background for the new size and redraw right away
+                    self.background_state.invalidate();
+                    self.redraw = true;
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
 
     /// Handles the key events and updates the state of [`App`].
     fn on_key_event(&mut self, key: KeyEvent) {
+        // Any key stops idle cycling and restarts the idle timer
+        self.last_input = self.clock.instant();
+        self.last_idle_cycle = None;
+        if let Some(snapshot) = self.pre_idle.take() {
+            self.theme_chosen = snapshot.theme_chosen;
+            self.scheduled_theme = snapshot.scheduled_theme;
+            snapshot.restore(&mut self.config);
+            self.update_system_monitor();
+        }
+
         // If settings dialog is visible, handle dialog keys
         if self.settings_dialog.visible {
             self.handle_settings_key(key);
             return;
         }
 
+        // After `g`, digits build a number that `c` applies to the theme and Enter to the font
+        if let Some(number) = &mut self.goto {
+            match key.code {
+                KeyCode::Char(ch @ '0'..='9') if number.len() < MAX_GOTO_DIGITS => {
+                    number.push(ch);
+                    return;
+                }
+                KeyCode::Backspace => {
+                    number.pop();
+                    return;
+                }
+                KeyCode::Char('c') => {
+                    if let Ok(n) = number.parse() {
+                        self.select_theme_number(n);
+                    }
+                }
+                KeyCode::Enter | KeyCode::Char('f') => {
+                    if let Ok(n) = number.parse() {
+                        self.select_font_number(n);
+                    }
+                }
+                _ => {}
+            }
+            self.goto = None;
+            return;
+        }
+
         // Main app keybindings
         match (key.modifiers, key.code) {
             (_, KeyCode::Esc | KeyCode::Char('q'))
             | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => self.quit(),
             (_, KeyCode::Char('t')) => self.toggle_time_format(),
+            (_, KeyCode::Char('m')) => self.cycle_display_mode(),
+            (_, KeyCode::Char('f')) => self.toggle_mirror(),
             (_, KeyCode::Char('c')) => self.cycle_color_theme(),
             (_, KeyCode::Char('a')) => self.cycle_animation(),
             (_, KeyCode::Char('b')) => self.cycle_background(),
+            (_, KeyCode::Char('+' | '=')) => self.cycle_animation_speed(true),
+            (_, KeyCode::Char('-')) => self.cycle_animation_speed(false),
+            (_, KeyCode::Char('r')) => self.reroll_background_seed(),
             (_, KeyCode::Char('s')) => self.open_settings(),
-            _ => {}
+            (_, KeyCode::Char('d')) => self.dismiss_alarm(),
+            (_, KeyCode::Char('x')) if self.banner.is_visible() => self.banner.dismiss(),
+            (_, KeyCode::Char('p')) => self.toggle_pomodoro(),
+            (_, KeyCode::Char('n')) => self.skip_pomodoro_phase(),
+            (_, KeyCode::Char('P')) => self.stop_pomodoro(),
+            (_, KeyCode::Char('H')) => self.toggle_footer(),
+            (_, KeyCode::Char(' ')) => self.toggle_animation_pause(),
+            (_, KeyCode::Char('0')) => self.restart_animation(),
+            (_, KeyCode::Char(ch @ '1'..='9')) => {
+                self.select_theme_number(ch.to_digit(10).unwrap_or(1) as usize);
+            }
+            (_, KeyCode::Char('g')) => self.goto = Some(String::new()),
+            _ => return,
+        }
+
+        // A handled key pulses the reactive flash, decaying like a time change
+        if self.config.flash_on_keypress {
+            self.flash_intensity = KEYPRESS_FLASH;
+            self.flash_start = Some(self.clock.instant());
+        }
+    }
+
+    /// Get the animation time, which stands still while paused.
+    fn animation_elapsed_ms(&self) -> u64 {
+        match self.animation_paused_at {
+            Some(paused_at) => paused_at,
+            None => {
+                let elapsed_ms = self.clock.elapsed(self.animation_start).as_millis() as u64;
+                // Bounded so backgrounds doing float math stay smooth after days of uptime
+                animation_phase_ms(elapsed_ms.saturating_sub(self.animation_paused_ms))
+            }
         }
     }
 
+    /// Hold the background still, e.g. behind the settings dialog.
+    fn hold_background(&mut self) {
+        self.background_state.hold();
+    }
+
+    /// Let the background move again from where it was held.
+    fn release_background(&mut self) {
+        self.held_background = None;
+        self.background_state.release();
+    }
+
+    /// Freeze or resume color and background motion. The clock keeps running.
+    fn toggle_animation_pause(&mut self) {
+        match self.animation_paused_at.take() {
+            Some(paused_at) => {
+                let elapsed_ms = self.clock.elapsed(self.animation_start).as_millis() as u64;
+                self.animation_paused_ms = elapsed_ms.saturating_sub(paused_at);
+            }
+            None => self.animation_paused_at = Some(self.animation_elapsed_ms()),
+        }
+        self.background_state
+            .set_paused(self.animation_paused_at.is_some());
+    }
+
+    /// Restart animations, backgrounds and the reactive flash from their first frame.
+    fn restart_animation(&mut self) {
+        self.animation_start = self.clock.instant();
+        self.animation_paused_ms = 0;
+        if self.animation_paused_at.is_some() {
+            self.animation_paused_at = Some(0);
+        }
+        self.flash_intensity = 0.0;
+        self.flash_start = None;
+        // A fresh state grows the background again, from a new seed
+        let held = self.background_state.is_held();
+        self.background_state = BackgroundState::new(Rc::clone(&self.clock));
+        self.background_state.set_dark_terminal(self.dark_terminal);
+        self.background_state
+            .set_paused(self.animation_paused_at.is_some());
+        if held {
+            self.background_state.hold();
+        }
+        self.reroll_background_seed();
+    }
+
     /// Handle key events when settings dialog is open.
     fn handle_settings_key(&mut self, key: KeyEvent) {
         match key.code {
+            // Esc and Enter end typing into a numeric field before closing the dialog
+            KeyCode::Esc if self.settings_dialog.is_entering() => {
+                self.settings_dialog.cancel_entry();
+                self.apply_preview();
+            }
+            KeyCode::Enter if self.settings_dialog.is_entering() => {
+                self.settings_dialog.finish_entry();
+            }
             KeyCode::Esc => {
                 self.cancel_settings();
             }
             KeyCode::Enter => {
                 self.save_settings();
             }
+            KeyCode::Char(ch) if self.settings_dialog.type_char(ch) => {
+                self.apply_preview();
+            }
+            KeyCode::Backspace => {
+                self.settings_dialog.backspace();
+                self.apply_preview();
+            }
             KeyCode::Up | KeyCode::Char('k') => {
                 self.settings_dialog.prev_field();
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 self.settings_dialog.next_field();
             }
+            KeyCode::Tab => {
+                self.settings_dialog.next_group();
+            }
+            KeyCode::BackTab => {
+                self.settings_dialog.prev_group();
+            }
             KeyCode::Left | KeyCode::Char('h') => {
                 self.settings_dialog.prev_value();
                 self.apply_preview();
@@ -462,91 +1749,203 @@ impl App {
 
     /// Apply current dialog values as live preview.
     fn apply_preview(&mut self) {
-        self.current_font = self.settings_dialog.selected_font().to_string();
-        self.color_theme = self.settings_dialog.color_theme;
-        self.time_format = self.settings_dialog.time_format;
-        self.animation_style = self.settings_dialog.animation_style;
-        self.animation_speed = self.settings_dialog.animation_speed;
-        self.colon_blink = self.settings_dialog.colon_blink;
-        self.background_style = self.settings_dialog.background_style;
+        let font_changed = self.settings_dialog.config.font_name != self.config.font_name;
+        // Picking a font or format in the dialog ends the command-line override
+        if font_changed {
+            self.font_override = None;
+        }
+        if self.settings_dialog.config.time_format != self.config.time_format {
+            self.format_override = None;
+        }
+        if self.settings_dialog.config.color_theme != self.config.color_theme {
+            self.mark_theme_chosen();
+        }
+        self.config = self.settings_dialog.config.clone();
+        // The held background may show the old colors or style
+        self.held_background = None;
         self.update_system_monitor();
+        if font_changed && let Some(warning) = self.text().font_coverage_warning() {
+            self.banner.push(warning);
+        }
     }
 
     /// Open settings dialog with current settings.
     fn open_settings(&mut self) {
-        self.settings_dialog.open(
-            &self.current_font,
-            self.color_theme,
-            self.time_format,
-            self.animation_style,
-            self.animation_speed,
-            self.colon_blink,
-            self.background_style,
-        );
+        self.settings_dialog.open(&self.config);
+        // The dialog covers most of the background, so it stops moving behind it
+        self.hold_background();
     }
 
     /// Save current settings to config file and close dialog.
     fn save_settings(&mut self) {
-        // Update and save config (values already applied via preview)
-        self.config.font_name = self.current_font.clone();
-        self.config.color_theme = self.color_theme;
-        self.config.time_format = self.time_format;
-        self.config.animation_style = self.animation_style;
-        self.config.animation_speed = self.animation_speed;
-        self.config.colon_blink = self.colon_blink;
-        self.config.background_style = self.background_style;
-
-        if let Err(e) = self.config.save() {
-            eprintln!("Warning: Failed to save config: {e}");
-        }
+        // Values are already applied via preview, including any pending quick changes
+        self.save_due = None;
+        self.save_config();
 
+        self.settings_dialog.remember_field();
         self.settings_dialog.close();
+        self.release_background();
     }
 
     /// Cancel settings and revert to original values.
     fn cancel_settings(&mut self) {
-        // Revert to original values
-        self.current_font = self.settings_dialog.original_font().to_string();
-        self.color_theme = self.settings_dialog.original_color_theme();
-        self.time_format = self.settings_dialog.original_time_format();
-        self.animation_style = self.settings_dialog.original_animation_style();
-        self.animation_speed = self.settings_dialog.original_animation_speed();
-        self.colon_blink = self.settings_dialog.original_colon_blink();
-        self.background_style = self.settings_dialog.original_background_style();
+        self.config = self.settings_dialog.original().clone();
         self.update_system_monitor();
 
         self.settings_dialog.close();
+        self.release_background();
+    }
+
+    /// Save the config, showing any failure in the banner.
+    fn save_config(&mut self) {
+        // Idle cycling is a screensaver, so save what it will give back
+        let config = match &self.pre_idle {
+            Some(snapshot) => {
+                let mut config = self.config.clone();
+                snapshot.restore(&mut config);
+                Cow::Owned(config)
+            }
+            None => Cow::Borrowed(&self.config),
+        };
+        if let Some(path) = &self.config_path
+            && let Err(e) = config.save_to(path)
+        {
+            self.banner.push(format!("Failed to save config: {e}"));
+        }
+    }
+
+    /// Save a quick change once the keys stop for a moment, if those are persisted.
+    fn schedule_save(&mut self) {
+        if self.config.persist_quick_changes {
+            self.save_due = Some(self.clock.instant() + QUICK_SAVE_DELAY);
+        }
+    }
+
+    /// Save pending quick changes when their delay has passed.
+    fn save_if_due(&mut self) {
+        if self.save_due.is_some_and(|due| self.clock.instant() >= due) {
+            self.save_due = None;
+            self.save_config();
+        }
     }
 
     /// Toggle between 12-hour and 24-hour time format.
     fn toggle_time_format(&mut self) {
-        self.time_format = self.time_format.toggle();
+        self.config.time_format = self.text().time_format.toggle();
+        self.format_override = None;
+        self.schedule_save();
+    }
+
+    /// Cycle between digital, analog and since display.
+    fn cycle_display_mode(&mut self) {
+        self.config.display_mode = self.config.display_mode.next();
+        self.schedule_save();
+    }
+
+    /// Toggle mirroring the clock horizontally.
+    fn toggle_mirror(&mut self) {
+        self.config.mirror = !self.config.mirror;
+        self.schedule_save();
+    }
+
+    /// Show or hide the key help line.
+    fn toggle_footer(&mut self) {
+        self.config.show_footer = !self.config.show_footer;
+        self.schedule_save();
     }
 
     /// Cycle through available color themes.
     fn cycle_color_theme(&mut self) {
-        self.color_theme = self.color_theme.next();
+        self.config.color_theme = self.base_theme().next();
+        self.mark_theme_chosen();
+        self.schedule_save();
+    }
+
+    /// Switch to the Nth color theme (counting from 1), wrapping past the last.
+    fn select_theme_number(&mut self, number: usize) {
+        if number > 0 {
+            self.config.color_theme = ColorTheme::nth(number - 1);
+            self.mark_theme_chosen();
+            self.schedule_save();
+        }
+    }
+
+    /// Switch to the Nth font in `--list-fonts` order (counting from 1), wrapping past the last.
+    fn select_font_number(&mut self, number: usize) {
+        let fonts = self.font_registry.list_fonts();
+        if number > 0 && !fonts.is_empty() {
+            self.config.font_name = fonts[(number - 1) % fonts.len()].to_string();
+            self.font_override = None;
+            self.schedule_save();
+        }
     }
 
     /// Cycle through animation styles.
     fn cycle_animation(&mut self) {
-        self.animation_style = self.animation_style.next();
+        self.config.animation_style = self.config.animation_style.next();
+        self.schedule_save();
+    }
+
+    /// Step the animation speed up or down, wrapping around, and show the new speed.
+    fn cycle_animation_speed(&mut self, faster: bool) {
+        let speed = self.config.animation_speed;
+        self.config.animation_speed = if faster { speed.next() } else { speed.prev() };
+        self.schedule_save();
+        let speed = self.config.animation_speed.display_name();
+        self.show_toast(format!("Speed: {speed}"), TOAST_DURATION);
+    }
+
+    /// Show a short message in the top right corner, replacing any shown now.
+    fn show_toast(&mut self, message: impl Into<String>, duration: Duration) {
+        self.toast = Some(Toast::new(message, self.clock.instant(), duration));
+        self.redraw = true;
+    }
+
+    /// Get the toast to show, if it hasn't gone away yet.
+    fn visible_toast(&self) -> Option<&Toast> {
+        self.toast
+            .as_ref()
+            .filter(|toast| !toast.is_expired(self.clock.instant()))
+    }
+
+    /// Drop an expired toast and redraw without it, even when not refreshing in real time.
+    fn update_toast(&mut self) {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|toast| toast.is_expired(self.clock.instant()))
+        {
+            self.toast = None;
+            self.redraw = true;
+        }
     }
 
     /// Cycle through background styles.
     fn cycle_background(&mut self) {
-        self.background_style = self.background_style.next();
+        self.config.background_style = self.config.background_style.next();
         self.update_system_monitor();
+        self.schedule_save();
+    }
+
+    /// Pick a new background seed for a fresh pattern.
+    fn reroll_background_seed(&mut self) {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        self.config.background_seed = nanos ^ self.config.background_seed.rotate_left(17);
+        self.schedule_save();
     }
 
     /// Start or stop system monitor based on current background style.
     fn update_system_monitor(&mut self) {
-        if self.background_style.is_reactive() && self.system_monitor.is_none() {
+        let reactive = self.config.background_style.is_reactive();
+        if reactive && self.system_monitor.is_none() {
             // Start monitor for reactive backgrounds
             let monitor = SystemMonitor::new();
             monitor.start();
             self.system_monitor = Some(monitor);
-        } else if !self.background_style.is_reactive() && self.system_monitor.is_some() {
+        } else if !reactive && self.system_monitor.is_some() {
             // Stop monitor when not using reactive backgrounds
             self.system_monitor = None;
         }
@@ -558,8 +1957,1428 @@ impl App {
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self::new()
+/// What the clock text is drawn from, borrowed from an [`App`] or a [`ClockSetup`].
+#[derive(Clone, Copy)]
+struct ClockText<'a> {
+    config: &'a Config,
+    font_registry: &'a FontRegistry,
+    /// Label shown above the clock, if any.
+    label: Option<&'a str>,
+    /// Custom strftime format replacing the 12/24h formats, if valid.
+    time_format_str: Option<&'a str>,
+    /// Font the clock is drawn in, which `--font` may override.
+    font_name: &'a str,
+    /// 12/24h format the clock is drawn in, which `--format` may override.
+    time_format: TimeFormat,
+}
+
+impl<'a> ClockText<'a> {
+    /// Describe the clock characters the clock font can't draw, if any. Unknown
+    /// fonts are skipped, since the clock falls back to Standard for them.
+    fn font_coverage_warning(&self) -> Option<String> {
+        let font = self.font_registry.get(self.font_name)?;
+        let mut needed = CLOCK_CHARS.replace(':', &self.config.separator.to_string());
+        needed.extend(self.time_format_str.and_then(format_literals));
+        let missing = font.missing_chars(&needed);
+        if missing.is_empty() {
+            return None;
+        }
+        let missing: Vec<String> = missing.iter().map(|ch| format!("'{ch}'")).collect();
+        Some(format!(
+            "Font '{}' has no glyphs for {}",
+            self.font_name,
+            missing.join(", ")
+        ))
+    }
+
+    /// Build the clock time string for the configured format.
+    fn clock_time_str(&self, now: &chrono::DateTime<chrono::Local>) -> String {
+        if let Some(format) = self.time_format_str {
+            return now.format(format).to_string();
+        }
+
+        // Get time components
+        let (hours, is_pm) = match self.time_format {
+            TimeFormat::TwentyFourHour => {
+                (now.format("%H").to_string().parse().unwrap_or(0), false)
+            }
+            TimeFormat::TwelveHour => {
+                let h: u32 = now.format("%I").to_string().parse().unwrap_or(12);
+                let pm = now.format("%p").to_string() == "PM";
+                (h, pm)
+            }
+        };
+        let minutes: u32 = now.format("%M").to_string().parse().unwrap_or(0);
+        let sep = self.config.separator;
+        // Seconds would go stale between redraws in the slower refresh modes
+        let seconds = if self.config.refresh_mode.is_real_time() {
+            format!(
+                "{sep}{:02}{}",
+                now.second(),
+                self.config.precision.fraction_str(now.nanosecond())
+            )
+        } else {
+            String::new()
+        };
+
+        let mut time_str = match self.time_format {
+            TimeFormat::TwentyFourHour => {
+                format!("{hours:02}{sep}{minutes:02}{seconds}")
+            }
+            TimeFormat::TwelveHour => {
+                let hours = if self.config.pad_hours {
+                    format!("{hours:02}")
+                } else {
+                    format!("{hours:2}")
+                };
+                if self.config.show_ampm {
+                    let ampm = if is_pm { "PM" } else { "AM" };
+                    format!("{hours}{sep}{minutes:02}{seconds} {ampm}")
+                } else {
+                    format!("{hours}{sep}{minutes:02}{seconds}")
+                }
+            }
+        };
+        if self.config.blank_leading_zero && time_str.starts_with('0') {
+            time_str.replace_range(0..1, " ");
+        }
+        time_str
+    }
+
+    /// Get the separator that blinks. A custom time format may not use the configured
+    /// one, so a `:` in it blinks instead.
+    fn clock_separator(&self, time_str: &str) -> char {
+        let separator = self.config.separator;
+        if self.time_format_str.is_some() && !time_str.contains(separator) {
+            ':'
+        } else {
+            separator
+        }
+    }
+
+    /// Split the time into the part shown big and the seconds shown below it, when the
+    /// compact layout is on and the time has seconds.
+    fn compact_split<'t>(&self, time_str: &'t str) -> Option<(&'t str, &'t str)> {
+        if !self.config.compact_layout {
+            return None;
+        }
+        let separator = self.clock_separator(time_str);
+        // Hours and minutes come before the last of at least two separators
+        if time_str.matches(separator).count() < 2 {
+            return None;
+        }
+        let (time, seconds) = time_str.rsplit_once(separator)?;
+        Some((time, seconds))
+    }
+
+    /// Get the font for the seconds in the compact layout: the first fallback font
+    /// smaller than the clock font, or the clock font itself.
+    fn seconds_font(&self, font: &'a Font) -> &'a Font {
+        FALLBACK_FONTS
+            .iter()
+            .filter_map(|name| self.font_registry.get(name))
+            .find(|fallback| fallback.height < font.height)
+            .unwrap_or(font)
+    }
+
+    /// Snapshot what the clock shows for `--json`, in the configured theme.
+    fn state(&self, now: &chrono::DateTime<Local>) -> AppState {
+        let time = self.clock_time_str(now);
+        let font = self.font_registry.get_or_default(self.font_name);
+        AppState {
+            lines: font.render_text_with(&time, self.text_layout(font)),
+            time,
+            date: now.format("%A, %B %d, %Y").to_string(),
+            unix_time: now.timestamp(),
+            theme: self.config.color_theme,
+            font: font.name.clone(),
+            time_format: self.time_format,
+        }
+    }
+
+    /// Get the columns and rows the clock needs for `--measure`: the time in the
+    /// configured font and the label if there is one, plus the date line and help
+    /// line if asked for.
+    fn measure(
+        &self,
+        now: &chrono::DateTime<Local>,
+        with_date: bool,
+        with_footer: bool,
+    ) -> (usize, usize) {
+        let font = self.font_registry.get_or_default(self.font_name);
+        let layout = self.text_layout(font);
+        let time = self.clock_time_str(now);
+        let (time, seconds) = match self.compact_split(&time) {
+            Some((time, seconds)) => (time, Some(seconds)),
+            None => (time.as_str(), None),
+        };
+        let mut lines = font.render_text_with(time, layout);
+        if let Some(seconds) = seconds {
+            lines.extend(self.seconds_font(font).render_text_with(seconds, layout));
+        }
+        let mut width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut height = lines.len();
+        if let Some(label) = self.label {
+            let (text, _) = markup::flatten(&markup::parse(label));
+            width = width.max(text.chars().count());
+            height += 1;
+        }
+        if with_date {
+            // The date sits below two blank rows
+            width = width.max(now.format("%A, %B %d, %Y").to_string().chars().count());
+            height += 3;
+        }
+        if with_footer {
+            height += 1;
+        }
+        (width, height)
+    }
+
+    /// List the registered fonts for `--list-fonts`, one per line with height and source.
+    fn font_list(&self) -> String {
+        let names = self.font_registry.list_fonts();
+        let name_width = names.iter().map(|name| name.chars().count()).max();
+        let mut out = String::new();
+        for name in names {
+            // Fonts that fail to parse can't be used, so they aren't listed
+            let (Some(font), Some(source)) = (
+                self.font_registry.get(name),
+                self.font_registry.font_source(name),
+            ) else {
+                continue;
+            };
+            out.push_str(&format!(
+                "{name:<width$}  {:>2} rows  {}\n",
+                font.height,
+                source.display_name(),
+                width = name_width.unwrap_or(0),
+            ));
+        }
+        out
+    }
+
+    /// Render text in the configured font for `--text`, one font-height block per line
+    /// of input, colored with the theme as ANSI escapes if `color` is set.
+    fn banner_text(&self, text: &str, color: bool) -> String {
+        let font = self.font_registry.get_or_default(self.font_name);
+        let layout = self.text_layout(font);
+        let (text, markup_colors) = markup::flatten(&markup::parse(&text.replace("\\n", "\n")));
+        let mut lines: Vec<String> = Vec::new();
+        // Markup color of each column, for the rows of each text line
+        let mut column_colors: Vec<Vec<Option<Color>>> = Vec::new();
+        let mut offset = 0;
+        for piece in text.split_inclusive('\n') {
+            // Same lines as `str::lines`, keeping track of where each starts
+            let line = piece.strip_suffix('\n').unwrap_or(piece);
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let line_colors = &markup_colors[offset..offset + line.chars().count()];
+            offset += piece.chars().count();
+            let columns = glyph_column_colors(font, line, line_colors, layout);
+            for row in font.render_text_with(line, layout) {
+                lines.push(row.trim_end().to_string());
+                column_colors.push(columns.clone());
+            }
+        }
+        if !color {
+            return lines.iter().map(|line| format!("{line}\n")).collect();
+        }
+
+        // One gradient across the whole banner
+        let theme = self.config.color_theme;
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = lines.len();
+        let mut out = String::new();
+        for (y, line) in lines.iter().enumerate() {
+            let colors = row_colors(theme, self.config.gradient_angle, y, width, height);
+            for (x, ch) in line.chars().enumerate() {
+                if ch == ' ' {
+                    out.push(ch);
+                    continue;
+                }
+                let color = column_colors[y].get(x).copied().flatten();
+                let (r, g, b) = color_to_rgb(color.unwrap_or(colors[x]));
+                out.push_str(&format!("\x1b[38;2;{r};{g};{b}m{ch}"));
+            }
+            if !line.is_empty() {
+                out.push_str("\x1b[0m");
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Get the layout the big clock uses for `font`, following `text_direction`.
+    fn text_layout(&self, font: &Font) -> TextLayout {
+        TextLayout {
+            rtl: self.config.text_direction.is_rtl(font.rtl),
+            letter_spacing: self.config.letter_spacing,
+        }
+    }
+
+    /// Render sample text in every font for `--gallery`, each under its name.
+    fn gallery(&self, text: &str) -> String {
+        let mut out = String::new();
+        for name in self.font_registry.list_fonts() {
+            let Some(font) = self
+                .font_registry
+                .get(name)
+                .filter(|font| font.height <= GALLERY_MAX_HEIGHT)
+            else {
+                continue;
+            };
+            out.push_str(name);
+            out.push('\n');
+            for line in font.render_text_with(text, self.text_layout(font)) {
+                out.push_str(line.trim_end());
+                out.push('\n');
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// The config and fonts resolved from the arguments, which is all one-shot commands
+/// such as `--json` and `--text` need. The terminal UI builds the rest of [`App`]
+/// (remote socket, system monitor, timers) on top of it.
+struct ClockSetup {
+    config: Config,
+    font_registry: FontRegistry,
+    label: Option<String>,
+    time_format_str: Option<String>,
+    /// Font from `--font`, kept out of `config` so it is never saved.
+    font_override: Option<String>,
+    /// Time format from `--format`, kept out of `config` so it is never saved.
+    format_override: Option<TimeFormat>,
+}
+
+impl ClockSetup {
+    /// Load the user's config and fonts for a one-shot command. The font cache is
+    /// read but not rewritten, and problems are dropped as the clock isn't shown.
+    fn load(args: &Args) -> Self {
+        let (config, mut warnings) = Config::load_result();
+        let fonts = FontSources::configured(args, &config, &mut warnings).read_only();
+        Self::new(args, config, &mut warnings, &fonts)
+    }
+
+    /// Validate `config`, load fonts and apply the arguments that override it,
+    /// adding a warning for each problem.
+    fn new(args: &Args, mut config: Config, warnings: &mut Warnings, fonts: &FontSources) -> Self {
+        // Keep numbers in range
+        for warning in config.validate() {
+            warnings.push(format!("Config: {warning}"));
+        }
+
+        // Warn when the clock would be hard to read against the fill color
+        if let Some([r, g, b]) = config.bg_color
+            && config.color_theme.min_contrast_against(Color::Rgb(r, g, b)) < MIN_CONTRAST
+        {
+            warnings.push("Color theme has low contrast against bg_color".to_string());
+        }
+
+        // Initialize font registry with bundled fonts, then custom ones
+        let mut font_registry = FontRegistry::new();
+        font_registry.set_allow_override(config.allow_override);
+        fonts.load_into(&mut font_registry, args.rescan_fonts, warnings);
+
+        // A custom time format replaces the 12/24h formats, unless --format picks one
+        let time_format_str = match args.format {
+            Some(_) => None,
+            None => config.time_format_str.clone().filter(|format| {
+                let valid = format_literals(format).is_some();
+                if !valid {
+                    warnings.push(format!(
+                        "Invalid time_format_str '{format}', using time_format instead"
+                    ));
+                }
+                valid
+            }),
+        };
+
+        // Settle a loosely written font name such as "ansi shadow" on the registered one
+        if let Some(name) = font_registry.resolve_name(&config.font_name) {
+            config.font_name = name.to_string();
+        }
+        let font_override = args
+            .font
+            .as_deref()
+            .map(|font| font_registry.resolve_name(font).unwrap_or(font).to_string());
+
+        let label = args.label.clone().or_else(|| config.label.clone());
+
+        let setup = Self {
+            config,
+            font_registry,
+            label,
+            time_format_str,
+            font_override,
+            format_override: args.format,
+        };
+        warnings.extend(setup.text().font_coverage_warning());
+        setup
+    }
+
+    /// Get the clock text view of this setup.
+    fn text(&self) -> ClockText<'_> {
+        ClockText {
+            config: &self.config,
+            font_registry: &self.font_registry,
+            label: self.label.as_deref(),
+            time_format_str: self.time_format_str.as_deref(),
+            font_name: self
+                .font_override
+                .as_deref()
+                .unwrap_or(&self.config.font_name),
+            time_format: self.format_override.unwrap_or(self.config.time_format),
+        }
+    }
+}
+
+/// Where custom fonts and their parse cache live.
+#[derive(Debug, Default)]
+struct FontSources {
+    /// Directories to load `.flf` files from; earlier ones win when two define the
+    /// same font name.
+    dirs: Vec<PathBuf>,
+    /// Cache of parsed fonts to reuse, if any.
+    cache: Option<PathBuf>,
+    /// Whether to write new parses back to the cache.
+    update_cache: bool,
+}
+
+impl FontSources {
+    /// Pick custom font directories: --fonts-dir, then the config, then the default.
+    /// Missing directories the user named get a warning.
+    fn configured(args: &Args, config: &Config, warnings: &mut Warnings) -> Self {
+        let dirs: Vec<PathBuf> = if !args.fonts_dirs.is_empty() {
+            args.fonts_dirs.iter().map(|dir| expand_path(dir)).collect()
+        } else {
+            config.custom_fonts_dirs()
+        };
+        let dirs = if dirs.is_empty() {
+            vec![Config::fonts_dir()]
+        } else {
+            for dir in dirs.iter().filter(|dir| !dir.is_dir()) {
+                warnings.push(format!("Font directory not found: {}", dir.display()));
+            }
+            dirs
+        };
+        Self {
+            dirs,
+            cache: Some(Config::font_cache_path()),
+            update_cache: true,
+        }
+    }
+
+    /// Use the cache without writing to it, for commands that only print something.
+    fn read_only(self) -> Self {
+        Self {
+            update_cache: false,
+            ..self
+        }
+    }
+
+    /// Load the custom fonts, reusing cached parses of unchanged files unless `rescan`.
+    fn load_into(&self, registry: &mut FontRegistry, rescan: bool, warnings: &mut Warnings) {
+        let Some(cache_path) = &self.cache else {
+            for dir in &self.dirs {
+                warnings.extend(registry.load_custom_fonts(dir));
+            }
+            return;
+        };
+        let mut cache = if rescan {
+            FontCache::empty(cache_path)
+        } else {
+            FontCache::load(cache_path)
+        };
+        for dir in &self.dirs {
+            warnings.extend(registry.load_custom_fonts_cached(dir, &mut cache));
+        }
+        if self.update_cache
+            && let Err(e) = cache.save()
+        {
+            warnings.push(format!("Failed to save font cache: {e}"));
+        }
+    }
+}
+
+/// Settings idle cycling changes, kept to put back when a key is pressed.
+#[derive(Debug, Clone, Copy)]
+struct IdleSnapshot {
+    theme_chosen: bool,
+    scheduled_theme: Option<ColorTheme>,
+    color_theme: ColorTheme,
+    background_style: sigye_core::BackgroundStyle,
+    animation_style: AnimationStyle,
+}
+
+impl IdleSnapshot {
+    /// Put the idle-cycled settings in `config` back.
+    fn restore(&self, config: &mut Config) {
+        config.color_theme = self.color_theme;
+        config.background_style = self.background_style;
+        config.animation_style = self.animation_style;
+    }
+}
+
+/// Rows of the clock display, top to bottom.
+struct ClockLayout {
+    label: Rect,
+    clock: Rect,
+    progress: Rect,
+    date: Rect,
+    extra: Rect,
+    help: Rect,
+}
+
+/// Split the screen into the label, clock, progress bar, spacing, date and extra info rows
+/// (each collapsed when its height is 0), with the help line (if any) at the bottom. The content is
+/// centered vertically, and when it doesn't fit the rows below the clock are cut off first
+/// so the clock is never clipped at the top.
+fn clock_layout(
+    area: Rect,
+    label_height: u16,
+    clock_height: u16,
+    progress_height: u16,
+    extra_height: u16,
+    footer_height: u16,
+) -> ClockLayout {
+    let help_height = area.height.min(footer_height);
+    let body_bottom = area.bottom() - help_height;
+    let heights = [
+        label_height,
+        clock_height,
+        progress_height,
+        2,
+        1,
+        extra_height,
+    ];
+    let content: u16 = heights.iter().sum();
+    let top = (body_bottom - area.y).saturating_sub(content) / 2;
+
+    let mut y = area.y + top;
+    let [label, clock, progress, _spacing, date, extra] = heights.map(|height| {
+        let height = height.min(body_bottom - y);
+        let row = Rect::new(area.x, y, area.width, height);
+        y += height;
+        row
+    });
+    ClockLayout {
+        label,
+        clock,
+        progress,
+        date,
+        extra,
+        help: Rect::new(area.x, body_bottom, area.width, help_height),
+    }
+}
+
+/// Spread the markup color of each character over the columns its glyph covers.
+fn glyph_column_colors(
+    font: &Font,
+    text: &str,
+    colors: &[Option<Color>],
+    layout: TextLayout,
+) -> Vec<Option<Color>> {
+    let mut glyphs: Vec<(char, Option<Color>)> = text.chars().zip(colors.iter().copied()).collect();
+    if layout.rtl {
+        glyphs.reverse();
+    }
+    let mut columns = Vec::new();
+    for (idx, (ch, color)) in glyphs.into_iter().enumerate() {
+        if idx > 0 {
+            columns.extend(std::iter::repeat_n(None, layout.letter_spacing));
+        }
+        // Unknown characters are drawn as spaces
+        let width = if font.chars.contains_key(&ch) {
+            font.char_width(ch)
+        } else {
+            font.char_width(' ')
+        };
+        columns.extend(std::iter::repeat_n(color, width));
+    }
+    columns
+}
+
+/// Get the text a strftime format prints as is, or `None` if chrono can't read it.
+fn format_literals(format: &str) -> Option<String> {
+    let mut literals = String::new();
+    for item in StrftimeItems::new(format) {
+        match item {
+            Item::Literal(text) | Item::Space(text) => literals.push_str(text),
+            Item::OwnedLiteral(text) | Item::OwnedSpace(text) => literals.push_str(&text),
+            Item::Error => return None,
+            _ => {}
+        }
+    }
+    Some(literals)
+}
+
+/// Get the number of frames a recording of `seconds` needs at `delay_cs` hundredths of
+/// a second each, capped at [`MAX_GIF_FRAMES`], and whether the cap cut it short.
+fn gif_frame_count(seconds: u32, delay_cs: u16) -> (u32, bool) {
+    let wanted = u64::from(seconds) * 100 / u64::from(delay_cs.max(1));
+    let frames = wanted.clamp(1, u64::from(MAX_GIF_FRAMES)) as u32;
+    (frames, wanted > u64::from(MAX_GIF_FRAMES))
+}
+
+/// Get how long to wait for input: until the next frame, or the `input_poll_ms`
+/// override if set, but never past the next second so the clock can't skip one.
+fn poll_timeout(until_frame: Duration, input_poll: Option<Duration>, nanos: u32) -> Duration {
+    let until_second = Duration::from_nanos(1_000_000_000 - u64::from(nanos.min(999_999_999)));
+    input_poll.unwrap_or(until_frame).min(until_second)
+}
+
+/// Get how long until the next minute starts, for `refresh_mode = "Minute"`.
+fn until_next_minute(second: u32, nanos: u32) -> Duration {
+    let second = u64::from(second.min(59));
+    let nanos = u64::from(nanos.min(999_999_999));
+    Duration::from_secs(59 - second) + Duration::from_nanos(1_000_000_000 - nanos)
+}
+
+/// Guess the terminal's color depth. Truecolor terminals set `COLORTERM`, and 256-color
+/// ones approximate RGB well enough on their own; anything else gets the 16 ANSI colors.
+fn detect_color_depth() -> ColorDepth {
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    let term = std::env::var("TERM").unwrap_or_default();
+    if matches!(colorterm.as_str(), "truecolor" | "24bit") || term.contains("256color") {
+        ColorDepth::TrueColor
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/// Settings a transformed clock font was built with.
+#[derive(Debug, Clone, PartialEq)]
+struct ClockFontVariant {
+    name: String,
+    mirror: bool,
+    /// Characters whose blank rows were trimmed, if trimming.
+    trim_to: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use sigye_core::AnimationSpeed;
+
+    use super::*;
+    use crate::clock::ManualClock;
+
+    /// Build an app on a manual clock starting at `now`, with default settings and
+    /// only the bundled fonts. Nothing is read from or saved to the user's config.
+    fn test_app(args: &Args, now: chrono::DateTime<Local>) -> (App, ManualClock) {
+        let clock = ManualClock::new(now);
+        let app = App::with_config(
+            args,
+            Config::default(),
+            Warnings::new(),
+            &FontSources::default(),
+            Rc::new(clock.clone()),
+        );
+        (app, clock)
+    }
+
+    #[test]
+    fn test_banner_text_stacks_lines() {
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.font_name = "Standard".to_string();
+        let height = app.font_registry.get_or_default("Standard").height;
+
+        let plain = app.text().banner_text("HI\\nYO", false);
+        assert_eq!(plain.lines().count(), height * 2);
+        assert!(!plain.contains('\x1b'));
+
+        let colored = app.text().banner_text("HI", true);
+        assert_eq!(colored.lines().count(), height);
+        assert!(colored.contains("\x1b[38;2;"));
+    }
+
+    #[test]
+    fn test_one_shot_setup_leaves_font_cache_alone() {
+        let dir = std::env::temp_dir().join(format!("sigye-one-shot-{}", std::process::id()));
+        let fonts_dir = dir.join("fonts");
+        std::fs::create_dir_all(&fonts_dir).unwrap();
+        let standard = sigye_fonts::BUNDLED_FONTS
+            .iter()
+            .find(|(name, _)| *name == "Standard")
+            .unwrap()
+            .1;
+        std::fs::write(fonts_dir.join("Custom.flf"), standard).unwrap();
+        let fonts = FontSources {
+            dirs: vec![fonts_dir],
+            cache: Some(dir.join("font-cache")),
+            update_cache: true,
+        };
+
+        let mut warnings = Warnings::new();
+        let setup = ClockSetup::new(
+            &Args::default(),
+            Config::default(),
+            &mut warnings,
+            &fonts.read_only(),
+        );
+        assert!(setup.text().font_list().contains("Custom"));
+        assert!(!dir.join("font-cache").exists());
+        assert!(warnings.is_empty(), "{warnings:?}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_measure_clock_size() {
+        let args = Args {
+            font: Some("standard".to_string()),
+            format: Some(TimeFormat::TwentyFourHour),
+            ..Args::default()
+        };
+        let now = Local.with_ymd_and_hms(2026, 1, 1, 12, 34, 56).unwrap();
+        let mut app = test_app(&args, now).0;
+        app.label = None;
+        assert_eq!(app.text().font_name, "Standard");
+
+        let time = app.text().clock_time_str(&now);
+        let lines = app
+            .font_registry
+            .get_or_default("Standard")
+            .render_text(&time);
+        let width = lines[0].chars().count();
+        assert_eq!(app.text().measure(&now, false, false), (width, lines.len()));
+        assert_eq!(
+            app.text().measure(&now, true, true),
+            (width, lines.len() + 4)
+        );
+
+        app.label = Some("[red]Kitchen[/]".to_string());
+        assert_eq!(
+            app.text().measure(&now, false, false),
+            (width, lines.len() + 1)
+        );
+    }
+
+    #[test]
+    fn test_compact_layout_fits_narrow_width() {
+        let now = Local.with_ymd_and_hms(2026, 1, 1, 12, 34, 56).unwrap();
+        let mut app = test_app(&Args::default(), now).0;
+        app.label = None;
+        app.config.font_name = "Standard".to_string();
+        app.config.time_format = TimeFormat::TwentyFourHour;
+        app.config.separator = ':';
+        app.config.precision = TimePrecision::Seconds;
+        app.config.refresh_mode = RefreshMode::RealTime;
+        app.config.letter_spacing = 0;
+        let (full_width, full_height) = app.text().measure(&now, false, false);
+
+        app.config.compact_layout = true;
+        assert_eq!(app.text().compact_split("12:34:56"), Some(("12:34", "56")));
+        assert_eq!(app.text().compact_split("25:00"), None);
+
+        let standard = app.font_registry.get_or_default("Standard");
+        let small = app.font_registry.get_or_default("Small");
+        let (width, height) = app.text().measure(&now, false, false);
+        assert_eq!(width, standard.render_text("12:34")[0].chars().count());
+        assert_eq!(height, full_height + small.height);
+        assert!(width < full_width && width <= 40, "{width} of {full_width}");
+
+        // Both lines draw within a 40 column terminal
+        app.config.background_style = sigye_core::BackgroundStyle::None;
+        app.config.display_mode = DisplayMode::Digital;
+        let mut terminal = Terminal::new(TestBackend::new(40, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let clock = app.clock_rect.unwrap();
+        assert_eq!(usize::from(clock.width), width);
+        assert_eq!(usize::from(clock.height), height);
+    }
+
+    #[test]
+    fn test_time_format_str() {
+        let now = Local.with_ymd_and_hms(2026, 1, 1, 9, 5, 7).unwrap();
+        let mut app = test_app(&Args::default(), now).0;
+        app.config.separator = '.';
+        app.time_format_str = Some("%H시 %M분".to_string());
+        let time = app.text().clock_time_str(&now);
+        assert_eq!(time, "09시 05분");
+        // Without the configured separator in the text, only a colon would blink
+        assert_eq!(app.text().clock_separator(&time), ':');
+        app.time_format_str = Some("%H.%M".to_string());
+        assert_eq!(
+            app.text().clock_separator(&app.text().clock_time_str(&now)),
+            '.'
+        );
+
+        assert_eq!(format_literals("%H시 %M분").as_deref(), Some("시 분"));
+        assert_eq!(format_literals("%H:%Q"), None);
+    }
+
+    #[test]
+    fn test_banner_text_markup() {
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.font_name = "Standard".to_string();
+        app.config.color_theme = ColorTheme::Cyan;
+
+        // Tags are dropped from the plain banner
+        assert_eq!(
+            app.text().banner_text("[red]H[/]I", false),
+            app.text().banner_text("HI", false)
+        );
+        let (r, g, b) = color_to_rgb(Color::Red);
+        let colored = app.text().banner_text("[red]H[/]I", true);
+        assert!(colored.contains(&format!("\x1b[38;2;{r};{g};{b}m")));
+        assert!(
+            !app.text()
+                .banner_text("HI", true)
+                .contains(&format!("\x1b[38;2;{r};{g};{b}m"))
+        );
+    }
+
+    #[test]
+    fn test_flash_decays_to_zero() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.animation_speed = AnimationSpeed::Medium;
+        let decay = Duration::from_millis(AnimationSpeed::Medium.flash_decay_ms());
+
+        // The next second starts a subtle flash
+        clock.advance(Duration::from_secs(1));
+        app.update_flash(&clock.now());
+        assert_eq!(app.flash_intensity, 0.3);
+
+        clock.advance(decay / 2);
+        app.update_flash(&clock.now());
+        assert!(app.flash_intensity > 0.0 && app.flash_intensity < 0.3);
+
+        clock.advance(decay / 2);
+        app.update_flash(&clock.now());
+        assert_eq!(app.flash_intensity, 0.0);
+        assert!(app.flash_start.is_none());
+    }
+
+    #[test]
+    fn test_keypress_flash() {
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        let key = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+
+        app.config.flash_on_keypress = false;
+        app.on_key_event(key('t'));
+        assert_eq!(app.flash_intensity, 0.0);
+
+        app.config.flash_on_keypress = true;
+        app.on_key_event(key('t'));
+        assert_eq!(app.flash_intensity, KEYPRESS_FLASH);
+        assert!(app.flash_start.is_some());
+
+        // Unbound keys don't flash
+        app.flash_intensity = 0.0;
+        app.on_key_event(key('z'));
+        assert_eq!(app.flash_intensity, 0.0);
+    }
+
+    #[test]
+    fn test_reactive_granularity_skips_finer_changes() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 58).unwrap(),
+        );
+        app.config.reactive_granularity = ReactiveGranularity::Minute;
+
+        clock.advance(Duration::from_secs(1));
+        app.update_flash(&clock.now());
+        assert_eq!(app.flash_intensity, 0.0);
+
+        clock.advance(Duration::from_secs(1));
+        app.update_flash(&clock.now());
+        assert_eq!(app.flash_intensity, app.config.flash_minute);
+    }
+
+    #[test]
+    fn test_restart_keeps_light_terminal_stars() {
+        let star_colors = |app: &mut App| {
+            let mut terminal = Terminal::new(TestBackend::new(200, 60)).unwrap();
+            terminal.draw(|frame| app.render(frame)).unwrap();
+            // The top rows hold nothing but the background
+            let buffer = terminal.backend().buffer();
+            buffer.content[..200 * 5]
+                .iter()
+                .filter(|cell| cell.symbol() != " ")
+                .map(|cell| cell.fg)
+                .collect::<Vec<_>>()
+        };
+        let (mut dark, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        dark.config.background_style = sigye_core::BackgroundStyle::Starfield;
+        dark.color_depth = ColorDepth::TrueColor;
+        let dark_colors = star_colors(&mut dark);
+
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.background_style = sigye_core::BackgroundStyle::Starfield;
+        app.color_depth = ColorDepth::TrueColor;
+        app.dark_terminal = false;
+        app.background_state.set_dark_terminal(false);
+        let seed = app.config.background_seed;
+        app.on_key_event(KeyEvent::new(KeyCode::Char('0'), KeyModifiers::NONE));
+        assert_ne!(app.config.background_seed, seed);
+
+        let light_colors = star_colors(&mut app);
+        assert!(!light_colors.is_empty());
+        assert!(
+            light_colors
+                .iter()
+                .all(|color| !dark_colors.contains(color))
+        );
+    }
+
+    #[test]
+    fn test_life_cells_follow_theme_on_screen() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 12, 26, 12, 0, 0).unwrap(),
+        );
+        app.config.background_style = sigye_core::BackgroundStyle::GameOfLife;
+        app.config.color_theme = ColorTheme::Cyan;
+        app.color_depth = ColorDepth::TrueColor;
+        app.date_themes = vec![(DateRule::parse("12-01..12-31").unwrap(), ColorTheme::Red)];
+        app.update_date_theme(&clock.now());
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        // The top row holds nothing but the background
+        let cells: Vec<_> = terminal.backend().buffer().content[..80]
+            .iter()
+            .filter(|cell| cell.symbol() == "▓")
+            .collect();
+        assert!(!cells.is_empty());
+        for cell in cells {
+            assert!(
+                matches!(cell.fg, Color::Rgb(r, 0, 0) if r > 0),
+                "{:?}",
+                cell.fg
+            );
+        }
+    }
+
+    #[test]
+    fn test_transparent_leaves_blank_cells_untouched() {
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.background_style = sigye_core::BackgroundStyle::Starfield;
+        app.config.bg_color = Some([16, 16, 24]);
+        app.config.frame_flash = true;
+        app.config.show_border = true;
+        app.config.transparent = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(buffer.content.iter().any(|cell| cell.symbol() != " "));
+        for cell in buffer.content.iter().filter(|cell| cell.symbol() == " ") {
+            assert_eq!(*cell, ratatui::buffer::Cell::default());
+        }
+
+        // Without a background or fill, blank cells keep the terminal's background anyway
+        // (spaces inside text lines only pick up a foreground color)
+        app.config.transparent = false;
+        app.config.background_style = sigye_core::BackgroundStyle::None;
+        app.config.bg_color = None;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let buffer = terminal.backend().buffer();
+        for cell in buffer.content.iter().filter(|cell| cell.symbol() == " ") {
+            assert_eq!(cell.bg, Color::Reset);
+        }
+    }
+
+    #[test]
+    fn test_theme_and_font_transitions() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.transition_ms = 300;
+
+        // The first frame has nothing to fade from
+        app.update_theme_transition(ColorTheme::Cyan);
+        assert_eq!(
+            app.update_font_transition("Standard".to_string()),
+            "Standard"
+        );
+        assert!(app.theme_transition_progress().is_none());
+        assert_eq!(app.font_brightness(), 1.0);
+
+        app.update_theme_transition(ColorTheme::Red);
+        assert_eq!(
+            app.theme_transition_progress(),
+            Some((ColorTheme::Cyan, 0.0))
+        );
+        // The old font fades out before the new one fades in
+        assert_eq!(app.update_font_transition("Small".to_string()), "Standard");
+        assert_eq!(app.font_brightness(), 1.0);
+        clock.advance(Duration::from_millis(75));
+        assert_eq!(app.update_font_transition("Small".to_string()), "Standard");
+        assert_eq!(app.font_brightness(), 0.5);
+        clock.advance(Duration::from_millis(150));
+        assert_eq!(app.update_font_transition("Small".to_string()), "Small");
+        assert_eq!(app.font_brightness(), 0.5);
+        assert_eq!(
+            app.theme_transition_progress(),
+            Some((ColorTheme::Cyan, 0.75))
+        );
+
+        clock.advance(Duration::from_millis(75));
+        assert!(app.theme_transition_progress().is_none());
+        assert_eq!(app.font_brightness(), 1.0);
+
+        // Turned off, switches are instant
+        app.config.transition_ms = 0;
+        app.update_theme_transition(ColorTheme::Green);
+        assert!(app.theme_transition_progress().is_none());
+    }
+
+    #[test]
+    fn test_date_theme_until_theme_chosen() {
+        // 2026-12-26 is a Saturday, both in December and on a weekend
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 12, 26, 12, 0, 0).unwrap(),
+        );
+        app.config.color_theme = ColorTheme::Cyan;
+        app.date_themes = vec![
+            (DateRule::parse("12-01..12-31").unwrap(), ColorTheme::Red),
+            (DateRule::parse("Sat,Sun").unwrap(), ColorTheme::Green),
+        ];
+        app.update_date_theme(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::Red);
+
+        // Into January only the weekend rule is left
+        clock.advance(Duration::from_secs(7 * 24 * 3600));
+        app.update_date_theme(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::Green);
+
+        // Picking a theme by hand wins for the rest of the session
+        app.cycle_color_theme();
+        app.update_date_theme(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::Cyan.next());
+    }
+
+    #[test]
+    fn test_idle_cycle_gives_back_date_theme() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 12, 26, 12, 0, 0).unwrap(),
+        );
+        let dir = std::env::temp_dir().join(format!("sigye-idle-cycle-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+        app.config.persist_quick_changes = true;
+        app.config.idle_cycle_secs = Some(60);
+        app.date_themes = vec![(DateRule::parse("12-01..12-31").unwrap(), ColorTheme::Red)];
+        app.update_date_theme(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::Red);
+        let before = app.config.clone();
+
+        // Idle cycling takes over the screen...
+        clock.advance(Duration::from_secs(61));
+        app.update_idle_cycle();
+        app.update_date_theme(&clock.now());
+        assert_ne!(app.color_theme(), ColorTheme::Red);
+        assert_ne!(app.config.background_style, before.background_style);
+        assert_ne!(app.config.animation_style, before.animation_style);
+
+        // ...a save that comes due meanwhile keeps the settings from before it...
+        app.schedule_save();
+        clock.advance(QUICK_SAVE_DELAY);
+        app.save_if_due();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        for (key, value) in [
+            ("color_theme", format!("{:?}", before.color_theme)),
+            ("background_style", format!("{:?}", before.background_style)),
+            ("animation_style", format!("{:?}", before.animation_style)),
+        ] {
+            assert!(saved.contains(&format!("{key} = \"{value}\"")), "{saved}");
+        }
+
+        // ...and everything comes back once a key is pressed
+        app.on_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        app.update_date_theme(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::Red);
+        assert_eq!(app.config.color_theme, before.color_theme);
+        assert_eq!(app.config.background_style, before.background_style);
+        assert_eq!(app.config.animation_style, before.animation_style);
+        assert_eq!(app.save_due, None);
+    }
+
+    #[test]
+    fn test_theme_schedule_leaves_configured_theme() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.color_theme = ColorTheme::Cyan;
+        app.theme_schedule = ThemeSchedule::new(vec![
+            (6 * 60, ColorTheme::GradientWarm),
+            (18 * 60, ColorTheme::GradientCool),
+        ]);
+        app.update_theme_schedule(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::GradientWarm);
+        assert_eq!(app.config.color_theme, ColorTheme::Cyan);
+
+        // A theme picked by hand holds until the next threshold
+        app.cycle_color_theme();
+        app.update_theme_schedule(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::GradientWarm.next());
+        clock.advance(Duration::from_secs(7 * 3600));
+        app.update_theme_schedule(&clock.now());
+        assert_eq!(app.color_theme(), ColorTheme::GradientCool);
+        assert_eq!(app.config.color_theme, ColorTheme::GradientWarm.next());
+    }
+
+    #[test]
+    fn test_jump_to_theme_and_font_by_number() {
+        let (mut app, _) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        let key = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+
+        app.on_key_event(key('3'));
+        assert_eq!(app.config.color_theme, ColorTheme::all()[2]);
+
+        // Go-to numbers past the end wrap around
+        for ch in format!("g{}c", ColorTheme::all().len() + 1).chars() {
+            app.on_key_event(key(ch));
+        }
+        assert_eq!(app.config.color_theme, ColorTheme::all()[0]);
+        assert!(app.goto.is_none());
+
+        for ch in "g12".chars() {
+            app.on_key_event(key(ch));
+        }
+        app.on_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(app.config.font_name, app.font_registry.list_fonts()[11]);
+
+        // Other keys cancel, and digits in the settings dialog stay with its fields
+        app.on_key_event(key('g'));
+        app.on_key_event(key('x'));
+        assert!(app.goto.is_none());
+        let theme = app.config.color_theme;
+        app.open_settings();
+        app.on_key_event(key('5'));
+        assert_eq!(app.config.color_theme, theme);
+    }
+
+    #[test]
+    fn test_animation_pause_resumes_where_it_left_off() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+
+        clock.advance(Duration::from_millis(500));
+        app.toggle_animation_pause();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(app.animation_elapsed_ms(), 500);
+
+        app.toggle_animation_pause();
+        clock.advance(Duration::from_millis(200));
+        assert_eq!(app.animation_elapsed_ms(), 700);
+    }
+
+    #[test]
+    fn test_pomodoro_moves_to_break() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.bell = false;
+        app.config.pomodoro_work_minutes = 25;
+        app.config.pomodoro_break_minutes = 5;
+        app.on_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        let pomodoro = app.pomodoro.as_ref().unwrap();
+        assert_eq!(pomodoro.remaining_str(clock.instant()), "25:00");
+
+        clock.advance(Duration::from_secs(25 * 60 - 1));
+        app.update_pomodoro();
+        let pomodoro = app.pomodoro.as_ref().unwrap();
+        assert_eq!(pomodoro.phase(), pomodoro::PomodoroPhase::Work);
+        assert_eq!(pomodoro.remaining_str(clock.instant()), "00:01");
+
+        clock.advance(Duration::from_secs(2));
+        app.update_pomodoro();
+        let pomodoro = app.pomodoro.as_ref().unwrap();
+        assert_eq!(pomodoro.phase(), pomodoro::PomodoroPhase::Break);
+        assert_eq!(pomodoro.remaining_str(clock.instant()), "04:59");
+        assert_eq!(app.flash_intensity, 1.0);
+    }
+
+    #[test]
+    fn test_background_holds_while_settings_open() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+
+        clock.advance(Duration::from_millis(500));
+        app.open_settings();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(app.background_state.elapsed_ms(), 500);
+        // The clock's own animations keep running for the live preview
+        assert_eq!(app.animation_elapsed_ms(), 3500);
+
+        app.cancel_settings();
+        clock.advance(Duration::from_millis(200));
+        assert_eq!(app.background_state.elapsed_ms(), 700);
+    }
+
+    #[test]
+    fn test_held_background_is_drawn_once() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.background_style = sigye_core::BackgroundStyle::MatrixRain;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let top_row = |terminal: &Terminal<TestBackend>| {
+            let buffer = terminal.backend().buffer();
+            (0..80).map(|x| buffer[(x, 0)].clone()).collect::<Vec<_>>()
+        };
+
+        app.open_settings();
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        let held = top_row(&terminal);
+        assert!(app.held_background.is_some());
+        assert!(held.iter().any(|cell| cell.symbol() != " "));
+
+        clock.advance(Duration::from_secs(3));
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert_eq!(top_row(&terminal), held);
+
+        // Previewing a different background draws it afresh
+        app.settings_dialog.config.background_style = sigye_core::BackgroundStyle::Starfield;
+        app.apply_preview();
+        assert!(app.held_background.is_none());
+
+        app.cancel_settings();
+        assert!(app.held_background.is_none());
+    }
+
+    #[test]
+    fn test_cycle_animation_speed_shows_toast() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.animation_speed = AnimationSpeed::Medium;
+
+        app.on_key_event(KeyEvent::new(KeyCode::Char('+'), KeyModifiers::NONE));
+        assert_eq!(app.config.animation_speed, AnimationSpeed::Fast);
+        assert_eq!(app.visible_toast().map(Toast::message), Some("Speed: Fast"));
+
+        app.on_key_event(KeyEvent::new(KeyCode::Char('-'), KeyModifiers::NONE));
+        app.on_key_event(KeyEvent::new(KeyCode::Char('-'), KeyModifiers::NONE));
+        assert_eq!(app.config.animation_speed, AnimationSpeed::Slow);
+
+        clock.advance(TOAST_DURATION);
+        assert!(app.visible_toast().is_none());
+    }
+
+    #[test]
+    fn test_expired_toast_stops_rendering() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        app.config.background_style = sigye_core::BackgroundStyle::None;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let top_right = |terminal: &Terminal<TestBackend>| {
+            let buffer = terminal.backend().buffer();
+            (66..80)
+                .map(|x| buffer[(x, 0)].symbol())
+                .collect::<String>()
+        };
+
+        app.show_toast("Hello toast", Duration::from_secs(2));
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(top_right(&terminal).contains("Hello toast"));
+
+        clock.advance(Duration::from_secs(2));
+        app.update_toast();
+        assert!(app.toast.is_none() && app.redraw);
+        terminal.draw(|frame| app.render(frame)).unwrap();
+        assert!(!top_right(&terminal).contains("Hello"));
+    }
+
+    #[test]
+    fn test_quick_changes_saved_after_delay() {
+        let (mut app, clock) = test_app(
+            &Args::default(),
+            Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap(),
+        );
+        let dir = std::env::temp_dir().join(format!("sigye-quick-save-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+        app.config.persist_quick_changes = true;
+        app.config.time_format = TimeFormat::TwentyFourHour;
+
+        app.on_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        assert_eq!(app.config.time_format, TimeFormat::TwelveHour);
+        clock.advance(Duration::from_millis(500));
+        app.on_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        // Each key pushes the save back
+        clock.advance(Duration::from_millis(900));
+        app.save_if_due();
+        assert!(!path.exists());
+
+        clock.advance(QUICK_SAVE_DELAY);
+        app.save_if_due();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("time_format = \"TwelveHour\""));
+        assert!(saved.contains(&format!("color_theme = \"{:?}\"", app.config.color_theme)));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        // Turned off, keys only change the session
+        app.config.persist_quick_changes = false;
+        app.on_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        assert_eq!(app.save_due, None);
+    }
+
+    #[test]
+    fn test_command_line_overrides_are_not_saved() {
+        let args = Args {
+            font: Some("small".to_string()),
+            format: Some(TimeFormat::TwelveHour),
+            ..Args::default()
+        };
+        let (mut app, clock) =
+            test_app(&args, Local.with_ymd_and_hms(2026, 1, 1, 12, 0, 0).unwrap());
+        let dir = std::env::temp_dir().join(format!("sigye-overrides-{}", std::process::id()));
+        let path = dir.join("config.toml");
+        app.config_path = Some(path.clone());
+        app.config.persist_quick_changes = true;
+        assert_eq!(app.text().font_name, "Small");
+        assert_eq!(app.text().time_format, TimeFormat::TwelveHour);
+
+        app.on_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
+        clock.advance(QUICK_SAVE_DELAY);
+        app.save_if_due();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(saved.contains("font_name = \"Standard\""), "{saved}");
+        assert!(
+            saved.contains("time_format = \"TwentyFourHour\""),
+            "{saved}"
+        );
+        // The session still shows them
+        assert_eq!(app.text().font_name, "Small");
+
+        // Toggling the format starts from the one on screen and ends the override
+        app.on_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        assert_eq!(app.text().time_format, TimeFormat::TwentyFourHour);
+        assert_eq!(app.config.time_format, TimeFormat::TwentyFourHour);
+    }
+
+    #[test]
+    fn test_gif_frame_count() {
+        assert_eq!(gif_frame_count(5, 5), (100, false));
+        assert_eq!(gif_frame_count(1, 1000), (1, false));
+        // Exactly the cap isn't cut short
+        assert_eq!(gif_frame_count(30, 5), (MAX_GIF_FRAMES, false));
+        assert_eq!(gif_frame_count(31, 5), (MAX_GIF_FRAMES, true));
+        assert_eq!(gif_frame_count(u32::MAX, 2), (MAX_GIF_FRAMES, true));
+    }
+
+    #[test]
+    fn test_until_next_minute() {
+        assert_eq!(until_next_minute(0, 0), Duration::from_secs(60));
+        assert_eq!(until_next_minute(59, 0), Duration::from_secs(1));
+        assert_eq!(
+            until_next_minute(30, 250_000_000),
+            Duration::from_millis(29_750)
+        );
+        assert_eq!(until_next_minute(59, 999_999_999), Duration::from_nanos(1));
+        // Leap seconds report 60 seconds or extra nanoseconds
+        assert_eq!(
+            until_next_minute(60, 1_500_000_000),
+            Duration::from_nanos(1)
+        );
+    }
+
+    #[test]
+    fn test_poll_timeout_stops_at_next_second() {
+        let frame = Duration::from_millis(50);
+        assert_eq!(poll_timeout(frame, None, 0), frame);
+        assert_eq!(
+            poll_timeout(frame, Some(Duration::from_millis(10)), 0),
+            Duration::from_millis(10)
+        );
+        // A slow override still wakes up for the next second
+        assert_eq!(
+            poll_timeout(frame, Some(Duration::from_secs(5)), 700_000_000),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            poll_timeout(frame, None, 980_000_000),
+            Duration::from_millis(20)
+        );
+    }
+
+    #[test]
+    fn test_clock_layout_centers() {
+        let rows = clock_layout(Rect::new(0, 0, 80, 20), 1, 6, 0, 0, 1);
+        // 19 rows above the help line hold 10 rows of content
+        assert_eq!(rows.label, Rect::new(0, 4, 80, 1));
+        assert_eq!(rows.clock, Rect::new(0, 5, 80, 6));
+        assert_eq!(rows.date.y, 13);
+        assert_eq!(rows.help, Rect::new(0, 19, 80, 1));
+
+        let rows = clock_layout(Rect::new(0, 0, 80, 20), 0, 6, 0, 0, 1);
+        assert_eq!(rows.label.height, 0);
+        assert_eq!(rows.clock, Rect::new(0, 5, 80, 6));
+
+        // Without the help line, content centers on all 20 rows
+        let rows = clock_layout(Rect::new(0, 0, 80, 20), 0, 6, 0, 0, 0);
+        assert_eq!(rows.clock, Rect::new(0, 5, 80, 6));
+        assert_eq!(rows.date.y, 13);
+        assert_eq!(rows.help.height, 0);
+        let rows = clock_layout(Rect::new(0, 0, 80, 10), 0, 10, 0, 0, 0);
+        assert_eq!(rows.clock, Rect::new(0, 0, 80, 10));
+    }
+
+    #[test]
+    fn test_clock_layout_tall_font_not_clipped_at_top() {
+        let area = Rect::new(0, 2, 80, 10);
+        let rows = clock_layout(area, 0, 12, 1, 1, 1);
+        assert_eq!(rows.clock, Rect::new(0, 2, 80, 9));
+        assert!(
+            [rows.progress, rows.date, rows.extra]
+                .iter()
+                .all(|row| row.height == 0)
+        );
+        assert_eq!(rows.help, Rect::new(0, 11, 80, 1));
     }
 }
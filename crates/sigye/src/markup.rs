@@ -0,0 +1,140 @@
+//! Color markup for labels and banner text.
+//!
+//! `[red]Server[/] Room` draws "Server" in red and "Room" in the theme's colors.
+//! Tags name a color theme (its base color) or a terminal color such as `lightblue`
+//! or `#ff8800`, and `[/]` ends the innermost open tag. Anything else in brackets,
+//! including a `[/]` with nothing open, is kept as literal text.
+
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use sigye_core::ColorTheme;
+
+/// A stretch of text drawn in one color.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub text: String,
+    /// Color from the markup, or `None` for the theme's colors.
+    pub color: Option<Color>,
+}
+
+/// Split marked-up text into colored runs.
+pub fn parse(markup: &str) -> Vec<Run> {
+    let mut runs = Vec::new();
+    let mut open: Vec<Color> = Vec::new();
+    let mut text = String::new();
+    let mut rest = markup;
+
+    while let Some(start) = rest.find('[') {
+        text.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let tag = rest[1..].find(']').map(|end| &rest[1..=end]);
+        let change = match tag {
+            Some("/") if !open.is_empty() => Some(None),
+            Some(name) => tag_color(name).map(Some),
+            None => None,
+        };
+        let Some(change) = change else {
+            // Not a tag, so the bracket is part of the text
+            text.push('[');
+            rest = &rest[1..];
+            continue;
+        };
+
+        if !text.is_empty() {
+            runs.push(Run {
+                text: std::mem::take(&mut text),
+                color: open.last().copied(),
+            });
+        }
+        match change {
+            Some(color) => open.push(color),
+            None => {
+                open.pop();
+            }
+        }
+        rest = &rest[tag.map_or(0, str::len) + 2..];
+    }
+    text.push_str(rest);
+    if !text.is_empty() {
+        runs.push(Run {
+            text,
+            color: open.last().copied(),
+        });
+    }
+    runs
+}
+
+/// Get the text of runs without markup, with the markup color of each character.
+pub fn flatten(runs: &[Run]) -> (String, Vec<Option<Color>>) {
+    let mut text = String::new();
+    let mut colors = Vec::new();
+    for run in runs {
+        text.push_str(&run.text);
+        colors.extend(run.text.chars().map(|_| run.color));
+    }
+    (text, colors)
+}
+
+/// Get the color a tag names. Bare numbers aren't colors, so `[1]` stays literal.
+fn tag_color(name: &str) -> Option<Color> {
+    if let Ok(theme) = ColorTheme::from_str(name) {
+        return Some(theme.color());
+    }
+    if name.is_empty() || name.chars().all(|ch| ch.is_ascii_digit()) {
+        return None;
+    }
+    Color::from_str(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(text: &str, color: Option<Color>) -> Run {
+        Run {
+            text: text.to_string(),
+            color,
+        }
+    }
+
+    #[test]
+    fn test_parse_tagged_text() {
+        assert_eq!(
+            parse("[red]Server[/] Room"),
+            vec![run("Server", Some(Color::Red)), run(" Room", None)]
+        );
+        assert_eq!(
+            parse("[#ff8800]A[lightblue]B[/]C"),
+            vec![
+                run("A", Some(Color::Rgb(255, 136, 0))),
+                run("B", Some(Color::LightBlue)),
+                run("C", Some(Color::Rgb(255, 136, 0))),
+            ]
+        );
+        // Theme names use the theme's base color
+        assert_eq!(
+            parse("[gray]x"),
+            vec![run("x", Some(ColorTheme::Gray.color()))]
+        );
+    }
+
+    #[test]
+    fn test_unknown_tags_stay_literal() {
+        assert_eq!(
+            parse("[nope] [1] [/] a[b"),
+            vec![run("[nope] [1] [/] a[b", None)]
+        );
+        assert_eq!(
+            parse("[[red]x]"),
+            vec![run("[", None), run("x]", Some(Color::Red))]
+        );
+        assert_eq!(
+            flatten(&parse("[green]ok[/]!")),
+            (
+                "ok!".to_string(),
+                vec![Some(Color::Green), Some(Color::Green), None]
+            )
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Pomodoro timer alternating work and break intervals.
+
+use std::time::{Duration, Instant};
+
+use sigye_core::ColorTheme;
+
+/// Current pomodoro phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PomodoroPhase {
+    Work,
+    Break,
+}
+
+impl PomodoroPhase {
+    /// Get the label shown under the timer.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PomodoroPhase::Work => "WORK",
+            PomodoroPhase::Break => "BREAK",
+        }
+    }
+
+    /// Get the theme used when auto-switching colors per phase.
+    pub fn color_theme(&self) -> ColorTheme {
+        match self {
+            PomodoroPhase::Work => ColorTheme::Red,
+            PomodoroPhase::Break => ColorTheme::Green,
+        }
+    }
+
+    /// Get the phase that follows this one.
+    fn next(&self) -> Self {
+        match self {
+            PomodoroPhase::Work => PomodoroPhase::Break,
+            PomodoroPhase::Break => PomodoroPhase::Work,
+        }
+    }
+}
+
+/// Pomodoro timer state.
+#[derive(Debug)]
+pub struct Pomodoro {
+    phase: PomodoroPhase,
+    /// When the current phase ends (meaningless while paused).
+    phase_end: Instant,
+    /// Time left in the phase when paused.
+    paused_remaining: Option<Duration>,
+    work: Duration,
+    rest: Duration,
+}
+
+impl Pomodoro {
+    /// Start a new pomodoro with a work phase at `now`.
+    pub fn new(work: Duration, rest: Duration, now: Instant) -> Self {
+        Self {
+            phase: PomodoroPhase::Work,
+            phase_end: now + work,
+            paused_remaining: None,
+            work,
+            rest,
+        }
+    }
+
+    /// Get the current phase.
+    pub fn phase(&self) -> PomodoroPhase {
+        self.phase
+    }
+
+    /// Check if the timer is paused.
+    pub fn is_paused(&self) -> bool {
+        self.paused_remaining.is_some()
+    }
+
+    /// Get the time left in the current phase as of `now`.
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.paused_remaining
+            .unwrap_or_else(|| self.phase_end.saturating_duration_since(now))
+    }
+
+    /// Format the remaining time as `MM:SS`, rounding up so `00:00` only shows at the end.
+    pub fn remaining_str(&self, now: Instant) -> String {
+        let remaining = self.remaining(now);
+        let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+
+    /// Pause or resume the timer.
+    pub fn toggle_pause(&mut self, now: Instant) {
+        match self.paused_remaining.take() {
+            Some(remaining) => self.phase_end = now + remaining,
+            None => self.paused_remaining = Some(self.remaining(now)),
+        }
+    }
+
+    /// Skip to the next phase, keeping the paused state.
+    pub fn skip(&mut self, now: Instant) {
+        self.phase = self.phase.next();
+        let duration = self.phase_duration();
+        if self.is_paused() {
+            self.paused_remaining = Some(duration);
+        } else {
+            self.phase_end = now + duration;
+        }
+    }
+
+    /// Advance to the next phase once the current one ends.
+    /// Returns true if a phase transition happened.
+    pub fn update(&mut self, now: Instant) -> bool {
+        if self.is_paused() || now < self.phase_end {
+            return false;
+        }
+        self.phase = self.phase.next();
+        self.phase_end += self.phase_duration();
+        true
+    }
+
+    /// Get the configured duration of the current phase.
+    fn phase_duration(&self) -> Duration {
+        match self.phase {
+            PomodoroPhase::Work => self.work,
+            PomodoroPhase::Break => self.rest,
+        }
+    }
+}
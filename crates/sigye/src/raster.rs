@@ -0,0 +1,105 @@
+//! Rasterizing terminal cells into RGB pixels, for image output.
+//!
+//! There are no glyph bitmaps; common ASCII art characters are drawn as simple
+//! strokes and everything else as a block in its foreground color.
+
+use ratatui::{buffer::Buffer, style::Color};
+use sigye_core::color_to_rgb;
+
+/// Size of one terminal cell in pixels.
+pub const CELL_WIDTH: usize = 6;
+pub const CELL_HEIGHT: usize = 12;
+
+/// Colors used for the terminal's default foreground and background.
+const DEFAULT_FG: (u8, u8, u8) = (229, 229, 229);
+const DEFAULT_BG: (u8, u8, u8) = (0, 0, 0);
+
+/// An RGB image, row-major.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<(u8, u8, u8)>,
+}
+
+/// Draw every cell of a buffer into an image.
+pub fn rasterize(buf: &Buffer) -> Image {
+    let area = buf.area;
+    let width = usize::from(area.width) * CELL_WIDTH;
+    let height = usize::from(area.height) * CELL_HEIGHT;
+    let mut pixels = vec![DEFAULT_BG; width * height];
+
+    for (i, cell) in buf.content.iter().enumerate() {
+        let cell_x = i % usize::from(area.width.max(1)) * CELL_WIDTH;
+        let cell_y = i / usize::from(area.width.max(1)) * CELL_HEIGHT;
+        let fg = resolve(cell.fg, DEFAULT_FG);
+        let bg = resolve(cell.bg, DEFAULT_BG);
+        let ch = cell.symbol().chars().next().unwrap_or(' ');
+        for py in 0..CELL_HEIGHT {
+            for px in 0..CELL_WIDTH {
+                let color = if is_ink(ch, px, py) { fg } else { bg };
+                pixels[(cell_y + py) * width + cell_x + px] = color;
+            }
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Get the RGB value of a cell color, using `default` for the terminal's own color.
+fn resolve(color: Color, default: (u8, u8, u8)) -> (u8, u8, u8) {
+    match color {
+        Color::Reset => default,
+        color => color_to_rgb(color),
+    }
+}
+
+/// Check if a pixel of a cell is part of the character's shape.
+fn is_ink(ch: char, x: usize, y: usize) -> bool {
+    let (w, h) = (CELL_WIDTH, CELL_HEIGHT);
+    let center_x = x == w / 2 - 1 || x == w / 2;
+    let center_y = y == h / 2 - 1 || y == h / 2;
+    // Position along the cell's diagonal, bottom-left to top-right
+    let rising = (h - 1 - y) * w / h;
+    match ch {
+        ' ' => false,
+        '_' => y >= h - 2,
+        '-' | '─' | '~' => center_y,
+        '=' => y == h / 2 - 2 || y == h / 2 + 1,
+        '|' | '│' | '!' => center_x,
+        '/' | '╱' => x == rising || x == rising + 1,
+        '\\' | '╲' => x + rising == w - 1 || x + rising == w - 2,
+        '.' | ',' => center_x && y >= h - 3,
+        ':' => center_x && (y == 3 || y == 4 || y == h - 4 || y == h - 3),
+        '\'' | '`' => center_x && y < 3,
+        '▀' => y < h / 2,
+        '▄' => y >= h / 2,
+        // Anything else is a block with a thin gap, so neighbors stay apart
+        _ => x > 0 && y > 0 && y < h - 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::layout::Rect;
+
+    use super::*;
+
+    #[test]
+    fn test_rasterize_cells() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 2, 1));
+        buf[(0, 0)].set_char('█').set_fg(Color::Rgb(10, 20, 30));
+        buf[(1, 0)].set_char('_').set_fg(Color::Red);
+
+        let image = rasterize(&buf);
+        assert_eq!((image.width, image.height), (2 * CELL_WIDTH, CELL_HEIGHT));
+        let pixel = |x: usize, y: usize| image.pixels[y * image.width + x];
+        assert_eq!(pixel(2, 5), (10, 20, 30));
+        assert_eq!(pixel(CELL_WIDTH + 2, CELL_HEIGHT - 1), (255, 0, 0));
+        assert_eq!(pixel(CELL_WIDTH + 2, 0), DEFAULT_BG);
+    }
+}
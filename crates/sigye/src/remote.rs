@@ -0,0 +1,111 @@
+//! Remote control over a Unix domain socket.
+//!
+//! Clients send one command per line (e.g. `theme rainbow`) and get `ok`, the
+//! command's output, or `err: <reason>` back for each. A listener thread forwards commands to the main
+//! loop, which applies them between event polls.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// A command from a client, waiting for the app to apply it.
+pub struct Request {
+    /// The command line, without the trailing newline.
+    pub command: String,
+    reply: Sender<String>,
+}
+
+impl Request {
+    /// Send the outcome of the command back to the client: its output, or `ok` if it has none.
+    pub fn respond(self, result: Result<Option<String>, String>) {
+        let reply = match result {
+            Ok(output) => output.unwrap_or_else(|| "ok".to_string()),
+            Err(e) => format!("err: {e}"),
+        };
+        // The client may have hung up already
+        let _ = self.reply.send(reply);
+    }
+}
+
+/// Socket listener handing commands to the main loop.
+pub struct RemoteControl {
+    requests: Receiver<Request>,
+    path: PathBuf,
+}
+
+impl RemoteControl {
+    /// Start listening for commands on a socket at `path`.
+    #[cfg(unix)]
+    pub fn listen(path: &Path) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+        use std::os::unix::net::UnixListener;
+
+        // Replace a socket left behind by an earlier run, but never another kind of file
+        if std::fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+            std::fs::remove_file(path)?;
+        }
+        let listener = UnixListener::bind(path)?;
+
+        let (sender, requests) = mpsc::channel();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                std::thread::spawn(move || serve_client(stream, sender));
+            }
+        });
+
+        Ok(Self {
+            requests,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Remote control needs Unix sockets, so elsewhere this listens for nothing.
+    #[cfg(not(unix))]
+    pub fn listen(path: &Path) -> io::Result<Self> {
+        let (_, requests) = mpsc::channel();
+        Ok(Self {
+            requests,
+            path: path.to_path_buf(),
+        })
+    }
+
+    /// Get the next pending command without blocking.
+    pub fn try_recv(&self) -> Option<Request> {
+        self.requests.try_recv().ok()
+    }
+}
+
+impl Drop for RemoteControl {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Read commands from one client, replying to each once the app has applied it.
+#[cfg(unix)]
+fn serve_client(stream: std::os::unix::net::UnixStream, sender: Sender<Request>) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(command) = line else {
+            break;
+        };
+        let (reply, response) = mpsc::channel();
+        let request = Request { command, reply };
+        // Stop once the app has shut down
+        if sender.send(request).is_err() {
+            break;
+        }
+        let Ok(response) = response.recv() else {
+            break;
+        };
+        if writeln!(writer, "{response}").is_err() {
+            break;
+        }
+    }
+}
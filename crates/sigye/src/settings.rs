@@ -1,5 +1,7 @@
 //! Settings dialog widget for configuring the clock.
 
+use std::cell::Cell;
+
 use ratatui::{
     Frame,
     layout::{Alignment, Constraint, Layout, Rect},
@@ -7,7 +9,44 @@ use ratatui::{
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph},
 };
-use sigye_core::{AnimationSpeed, AnimationStyle, BackgroundStyle, ColorTheme, TimeFormat};
+use sigye_config::Config;
+use sigye_core::{AnimationStyle, BackgroundStyle, DisplayMode, ProgressScope, TimeFormat};
+
+/// Longest value that can be typed into a numeric field.
+const MAX_ENTRY_LEN: usize = 8;
+
+/// Limits and step size of a numeric settings field.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct NumericRange {
+    min: f64,
+    max: f64,
+    /// Change per Left/Right press.
+    step: f64,
+    /// Decimal places shown and kept.
+    decimals: usize,
+}
+
+impl NumericRange {
+    /// Clamp a value to the range and round it to the shown precision.
+    fn fit(self, value: f64) -> f64 {
+        let scale = 10f64.powi(self.decimals as i32);
+        ((value * scale).round() / scale).clamp(self.min, self.max)
+    }
+
+    /// Get the value one step up or down, snapped to the step grid.
+    fn step(self, value: f64, up: bool) -> f64 {
+        let steps = (value / self.step).round() + if up { 1.0 } else { -1.0 };
+        self.fit(steps * self.step)
+    }
+}
+
+/// A value being typed into a numeric field.
+#[derive(Debug, Clone, PartialEq)]
+struct NumericEntry {
+    text: String,
+    /// Value before typing started, restored if the entry is cancelled.
+    original: f64,
+}
 
 /// The settings field currently being edited.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -15,37 +54,166 @@ pub enum SettingsField {
     #[default]
     Font,
     Color,
+    Display,
     TimeFormat,
+    ShowAmPm,
     Animation,
     Speed,
+    Reactive,
     Background,
-    ColonBlink,
+    BackgroundBrightness,
+    StarDensity,
+    Blink,
+    Progress,
+    Footer,
+    Fps,
+}
+
+/// All settings fields in display order, grouped by tab.
+const ALL_SETTINGS_FIELDS: &[SettingsField] = &[
+    SettingsField::Font,
+    SettingsField::Display,
+    SettingsField::TimeFormat,
+    SettingsField::ShowAmPm,
+    SettingsField::Blink,
+    SettingsField::Progress,
+    SettingsField::Footer,
+    SettingsField::Fps,
+    SettingsField::Color,
+    SettingsField::Animation,
+    SettingsField::Speed,
+    SettingsField::Reactive,
+    SettingsField::Background,
+    SettingsField::BackgroundBrightness,
+    SettingsField::StarDensity,
+];
+
+/// A tab of the settings dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SettingsGroup {
+    Display,
+    Colors,
+    Animation,
+    Background,
+}
+
+/// All settings tabs in display order.
+const ALL_SETTINGS_GROUPS: &[SettingsGroup] = &[
+    SettingsGroup::Display,
+    SettingsGroup::Colors,
+    SettingsGroup::Animation,
+    SettingsGroup::Background,
+];
+
+impl SettingsGroup {
+    /// Get the tab title.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Display => "Display",
+            Self::Colors => "Colors",
+            Self::Animation => "Animation",
+            Self::Background => "Background",
+        }
+    }
+
+    /// Get the fields on this tab, in display order.
+    fn fields(self) -> impl Iterator<Item = SettingsField> {
+        ALL_SETTINGS_FIELDS
+            .iter()
+            .copied()
+            .filter(move |field| field.group() == self)
+    }
 }
 
 impl SettingsField {
     /// Move to the next field.
     pub fn next(self) -> Self {
-        match self {
-            Self::Font => Self::Color,
-            Self::Color => Self::TimeFormat,
-            Self::TimeFormat => Self::Animation,
-            Self::Animation => Self::Speed,
-            Self::Speed => Self::Background,
-            Self::Background => Self::ColonBlink,
-            Self::ColonBlink => Self::Font,
-        }
+        let current_idx = ALL_SETTINGS_FIELDS
+            .iter()
+            .position(|f| *f == self)
+            .unwrap_or(0);
+        let next_idx = (current_idx + 1) % ALL_SETTINGS_FIELDS.len();
+        ALL_SETTINGS_FIELDS[next_idx]
     }
 
     /// Move to the previous field.
     pub fn prev(self) -> Self {
+        let current_idx = ALL_SETTINGS_FIELDS
+            .iter()
+            .position(|f| *f == self)
+            .unwrap_or(0);
+        let prev_idx = if current_idx == 0 {
+            ALL_SETTINGS_FIELDS.len() - 1
+        } else {
+            current_idx - 1
+        };
+        ALL_SETTINGS_FIELDS[prev_idx]
+    }
+
+    /// Get the limits of a numeric field, or `None` for fields that cycle through choices.
+    /// The limits match what [`Config::validate`] accepts.
+    fn numeric_range(self) -> Option<NumericRange> {
+        let range = match self {
+            Self::Fps => NumericRange {
+                min: 1.0,
+                max: 120.0,
+                step: 1.0,
+                decimals: 0,
+            },
+            Self::BackgroundBrightness => NumericRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.05,
+                decimals: 2,
+            },
+            Self::StarDensity => NumericRange {
+                min: 0.0,
+                max: 1.0,
+                step: 0.01,
+                decimals: 2,
+            },
+            _ => return None,
+        };
+        Some(range)
+    }
+
+    /// Get the tab the field is on.
+    pub fn group(self) -> SettingsGroup {
+        match self {
+            Self::Font
+            | Self::Display
+            | Self::TimeFormat
+            | Self::ShowAmPm
+            | Self::Blink
+            | Self::Progress
+            | Self::Footer
+            | Self::Fps => SettingsGroup::Display,
+            Self::Color => SettingsGroup::Colors,
+            Self::Animation | Self::Speed | Self::Reactive => SettingsGroup::Animation,
+            Self::Background | Self::BackgroundBrightness | Self::StarDensity => {
+                SettingsGroup::Background
+            }
+        }
+    }
+
+    /// Get the label shown next to the field value.
+    pub fn label(self) -> &'static str {
         match self {
-            Self::Font => Self::ColonBlink,
-            Self::Color => Self::Font,
-            Self::TimeFormat => Self::Color,
-            Self::Animation => Self::TimeFormat,
-            Self::Speed => Self::Animation,
-            Self::Background => Self::Speed,
-            Self::ColonBlink => Self::Background,
+            Self::Font => "Font",
+            Self::Color => "Color",
+            Self::Display => "Display",
+            Self::TimeFormat => "Format",
+            Self::ShowAmPm => "AM/PM",
+            Self::Animation => "Animation",
+            Self::Speed => "Speed",
+            Self::Reactive => "Flash On",
+            Self::Background => "Background",
+            Self::BackgroundBrightness => "BG Brightness",
+            Self::StarDensity => "Stars",
+            Self::Blink => "Blink",
+            Self::Progress => "Progress",
+            Self::Footer => "Help Line",
+            Self::Fps => "FPS",
         }
     }
 }
@@ -61,32 +229,18 @@ pub struct SettingsDialog {
     pub font_index: usize,
     /// List of available font names.
     pub available_fonts: Vec<String>,
-    /// Current color theme selection.
-    pub color_theme: ColorTheme,
-    /// Current time format selection.
-    pub time_format: TimeFormat,
-    /// Current animation style selection.
-    pub animation_style: AnimationStyle,
-    /// Current animation speed selection.
-    pub animation_speed: AnimationSpeed,
-    /// Current background style selection.
-    pub background_style: BackgroundStyle,
-    /// Current colon blink setting.
-    pub colon_blink: bool,
-    /// Original font index (for cancel/revert).
-    original_font_index: usize,
-    /// Original color theme (for cancel/revert).
-    original_color_theme: ColorTheme,
-    /// Original time format (for cancel/revert).
-    original_time_format: TimeFormat,
-    /// Original animation style (for cancel/revert).
-    original_animation_style: AnimationStyle,
-    /// Original animation speed (for cancel/revert).
-    original_animation_speed: AnimationSpeed,
-    /// Original background style (for cancel/revert).
-    original_background_style: BackgroundStyle,
-    /// Original colon blink (for cancel/revert).
-    original_colon_blink: bool,
+    /// Settings being edited (applied as live preview).
+    pub config: Config,
+    /// Settings when the dialog was opened (for cancel/revert).
+    original: Config,
+    /// Index of the first field shown when they don't all fit.
+    scroll_offset: usize,
+    /// Number of fields the last render had room for.
+    visible_fields: Cell<usize>,
+    /// Value being typed into the selected numeric field.
+    entry: Option<NumericEntry>,
+    /// Field selected when settings were last saved, reopened on for this session.
+    last_field: SettingsField,
 }
 
 impl SettingsDialog {
@@ -97,57 +251,31 @@ impl SettingsDialog {
             selected_field: SettingsField::default(),
             font_index: 0,
             available_fonts,
-            color_theme: ColorTheme::default(),
-            time_format: TimeFormat::default(),
-            animation_style: AnimationStyle::default(),
-            animation_speed: AnimationSpeed::default(),
-            background_style: BackgroundStyle::default(),
-            colon_blink: false,
-            original_font_index: 0,
-            original_color_theme: ColorTheme::default(),
-            original_time_format: TimeFormat::default(),
-            original_animation_style: AnimationStyle::default(),
-            original_animation_speed: AnimationSpeed::default(),
-            original_background_style: BackgroundStyle::default(),
-            original_colon_blink: false,
+            config: Config::default(),
+            original: Config::default(),
+            scroll_offset: 0,
+            visible_fields: Cell::new(ALL_SETTINGS_FIELDS.len()),
+            entry: None,
+            last_field: SettingsField::default(),
         }
     }
 
     /// Open dialog with current settings.
-    pub fn open(
-        &mut self,
-        font_name: &str,
-        color_theme: ColorTheme,
-        time_format: TimeFormat,
-        animation_style: AnimationStyle,
-        animation_speed: AnimationSpeed,
-        colon_blink: bool,
-        background_style: BackgroundStyle,
-    ) {
+    pub fn open(&mut self, config: &Config) {
         self.visible = true;
-        self.selected_field = SettingsField::default();
-        self.color_theme = color_theme;
-        self.time_format = time_format;
-        self.animation_style = animation_style;
-        self.animation_speed = animation_speed;
-        self.background_style = background_style;
-        self.colon_blink = colon_blink;
+        self.selected_field = self.last_field;
+        self.scroll_offset = 0;
+        self.scroll_offset = self.first_visible_field(self.visible_fields.get());
+        self.entry = None;
+        self.config = config.clone();
+        self.original = config.clone();
 
         // Find font index
         self.font_index = self
             .available_fonts
             .iter()
-            .position(|f| f == font_name)
+            .position(|f| *f == config.font_name)
             .unwrap_or(0);
-
-        // Store original values for cancel/revert
-        self.original_font_index = self.font_index;
-        self.original_color_theme = color_theme;
-        self.original_time_format = time_format;
-        self.original_animation_style = animation_style;
-        self.original_animation_speed = animation_speed;
-        self.original_background_style = background_style;
-        self.original_colon_blink = colon_blink;
     }
 
     /// Close without saving.
@@ -155,85 +283,234 @@ impl SettingsDialog {
         self.visible = false;
     }
 
-    /// Get original font name (for reverting on cancel).
-    pub fn original_font(&self) -> &str {
-        self.available_fonts
-            .get(self.original_font_index)
-            .map(String::as_str)
-            .unwrap_or("Standard")
+    /// Reopen on the selected field next time, after saving.
+    /// Cancelling doesn't call this, so it keeps the field from the last save.
+    pub fn remember_field(&mut self) {
+        self.last_field = self.selected_field;
     }
 
-    /// Get original color theme (for reverting on cancel).
-    pub fn original_color_theme(&self) -> ColorTheme {
-        self.original_color_theme
+    /// Get original settings (for reverting on cancel).
+    pub fn original(&self) -> &Config {
+        &self.original
     }
 
-    /// Get original time format (for reverting on cancel).
-    pub fn original_time_format(&self) -> TimeFormat {
-        self.original_time_format
+    /// Move to next field, continuing onto the next tab past the last one.
+    pub fn next_field(&mut self) {
+        self.select(self.selected_field.next());
     }
 
-    /// Get original animation style (for reverting on cancel).
-    pub fn original_animation_style(&self) -> AnimationStyle {
-        self.original_animation_style
+    /// Move to previous field, continuing onto the previous tab past the first one.
+    pub fn prev_field(&mut self) {
+        self.select(self.selected_field.prev());
     }
 
-    /// Get original animation speed (for reverting on cancel).
-    pub fn original_animation_speed(&self) -> AnimationSpeed {
-        self.original_animation_speed
+    /// Switch to the first field of the next tab.
+    pub fn next_group(&mut self) {
+        self.select_group(1);
     }
 
-    /// Get original colon blink (for reverting on cancel).
-    pub fn original_colon_blink(&self) -> bool {
-        self.original_colon_blink
+    /// Switch to the first field of the previous tab.
+    pub fn prev_group(&mut self) {
+        self.select_group(ALL_SETTINGS_GROUPS.len() - 1);
     }
 
-    /// Get original background style (for reverting on cancel).
-    pub fn original_background_style(&self) -> BackgroundStyle {
-        self.original_background_style
+    /// Switch to the first field of the tab `step` tabs further on, wrapping around.
+    fn select_group(&mut self, step: usize) {
+        let current = ALL_SETTINGS_GROUPS
+            .iter()
+            .position(|g| *g == self.selected_field.group())
+            .unwrap_or(0);
+        let group = ALL_SETTINGS_GROUPS[(current + step) % ALL_SETTINGS_GROUPS.len()];
+        if let Some(field) = group.fields().next() {
+            self.select(field);
+        }
     }
 
-    /// Move to next field.
-    pub fn next_field(&mut self) {
-        self.selected_field = self.selected_field.next();
+    /// Select a field, scrolling it into view.
+    fn select(&mut self, field: SettingsField) {
+        self.finish_entry();
+        if field.group() != self.selected_field.group() {
+            self.scroll_offset = 0;
+        }
+        self.selected_field = field;
+        self.scroll_offset = self.first_visible_field(self.visible_fields.get());
     }
 
-    /// Move to previous field.
-    pub fn prev_field(&mut self) {
-        self.selected_field = self.selected_field.prev();
+    /// Get the first field of the current tab to show so the selected one is in a
+    /// viewport of `visible` fields, scrolling as little as possible from the current offset.
+    fn first_visible_field(&self, visible: usize) -> usize {
+        let fields: Vec<SettingsField> = self.selected_field.group().fields().collect();
+        let visible = visible.clamp(1, fields.len());
+        let selected = fields
+            .iter()
+            .position(|f| *f == self.selected_field)
+            .unwrap_or(0);
+        let offset = if selected < self.scroll_offset {
+            selected
+        } else if selected >= self.scroll_offset + visible {
+            selected + 1 - visible
+        } else {
+            self.scroll_offset
+        };
+        offset.min(fields.len() - visible)
+    }
+
+    /// Check whether a value is being typed into a numeric field.
+    pub fn is_entering(&self) -> bool {
+        self.entry.is_some()
+    }
+
+    /// Type a digit or decimal point into the selected numeric field, applying the
+    /// value as soon as it parses. Returns `false` if the field isn't numeric.
+    pub fn type_char(&mut self, ch: char) -> bool {
+        let field = self.selected_field;
+        let Some(range) = field.numeric_range() else {
+            return false;
+        };
+        if !(ch.is_ascii_digit() || ch == '.' && range.decimals > 0) {
+            return false;
+        }
+        let original = self.numeric_value(field);
+        let entry = self.entry.get_or_insert_with(|| NumericEntry {
+            text: String::new(),
+            original,
+        });
+        if entry.text.len() < MAX_ENTRY_LEN {
+            entry.text.push(ch);
+        }
+        self.apply_entry();
+        true
+    }
+
+    /// Delete the last typed character.
+    pub fn backspace(&mut self) {
+        if let Some(entry) = &mut self.entry {
+            entry.text.pop();
+            self.apply_entry();
+        }
+    }
+
+    /// Stop typing, keeping the typed value.
+    pub fn finish_entry(&mut self) {
+        self.entry = None;
+    }
+
+    /// Stop typing and restore the value from before.
+    pub fn cancel_entry(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.set_numeric_value(self.selected_field, entry.original);
+        }
+    }
+
+    /// Apply the typed text to the selected field, if it's a number.
+    fn apply_entry(&mut self) {
+        if let Some(value) = self.entry.as_ref().and_then(|e| e.text.parse::<f64>().ok()) {
+            self.set_numeric_value(self.selected_field, value);
+        }
+    }
+
+    /// Get the value of a numeric field.
+    fn numeric_value(&self, field: SettingsField) -> f64 {
+        match field {
+            SettingsField::Fps => f64::from(self.config.fps),
+            SettingsField::BackgroundBrightness => f64::from(self.config.background_brightness),
+            SettingsField::StarDensity => f64::from(self.config.star_density),
+            _ => 0.0,
+        }
+    }
+
+    /// Set a numeric field, clamped to its range.
+    fn set_numeric_value(&mut self, field: SettingsField, value: f64) {
+        let Some(range) = field.numeric_range() else {
+            return;
+        };
+        let value = range.fit(value);
+        match field {
+            SettingsField::Fps => self.config.fps = value as u8,
+            SettingsField::BackgroundBrightness => self.config.background_brightness = value as f32,
+            SettingsField::StarDensity => self.config.star_density = value as f32,
+            _ => {}
+        }
+    }
+
+    /// Step a numeric field up or down.
+    fn step_numeric_value(&mut self, field: SettingsField, up: bool) {
+        self.finish_entry();
+        if let Some(range) = field.numeric_range() {
+            let value = range.step(self.numeric_value(field), up);
+            self.set_numeric_value(field, value);
+        }
     }
 
     /// Select next value for current field.
     pub fn next_value(&mut self) {
+        if self.selected_field.numeric_range().is_some() {
+            self.step_numeric_value(self.selected_field, true);
+            return;
+        }
+        let config = &mut self.config;
         match self.selected_field {
             SettingsField::Font => {
                 if !self.available_fonts.is_empty() {
                     self.font_index = (self.font_index + 1) % self.available_fonts.len();
+                    config.font_name = self.available_fonts[self.font_index].clone();
                 }
             }
             SettingsField::Color => {
-                self.color_theme = self.color_theme.next();
+                config.color_theme = config.color_theme.next();
+            }
+            SettingsField::Display => {
+                config.display_mode = config.display_mode.next();
             }
             SettingsField::TimeFormat => {
-                self.time_format = self.time_format.toggle();
+                config.time_format = config.time_format.toggle();
+            }
+            SettingsField::ShowAmPm => {
+                config.show_ampm = !config.show_ampm;
             }
             SettingsField::Animation => {
-                self.animation_style = self.animation_style.next();
+                config.animation_style = config.animation_style.next();
             }
             SettingsField::Speed => {
-                self.animation_speed = self.animation_speed.next();
+                config.animation_speed = config.animation_speed.next();
+            }
+            SettingsField::Reactive => {
+                config.reactive_granularity = config.reactive_granularity.next();
             }
             SettingsField::Background => {
-                self.background_style = self.background_style.next();
+                config.background_style = config.background_style.next();
             }
-            SettingsField::ColonBlink => {
-                self.colon_blink = !self.colon_blink;
+            SettingsField::Blink => {
+                config.blink_mode = config.blink_mode.next();
             }
+            SettingsField::Progress => {
+                // Cycles Off → Minute → Hour → Day → Off
+                if !config.show_progress {
+                    config.show_progress = true;
+                    config.progress_scope = ProgressScope::Minute;
+                } else if config.progress_scope == ProgressScope::Day {
+                    config.show_progress = false;
+                } else {
+                    config.progress_scope = config.progress_scope.next();
+                }
+            }
+            SettingsField::Footer => {
+                config.show_footer = !config.show_footer;
+            }
+            // Stepped above
+            SettingsField::Fps
+            | SettingsField::BackgroundBrightness
+            | SettingsField::StarDensity => {}
         }
     }
 
     /// Select previous value for current field.
     pub fn prev_value(&mut self) {
+        if self.selected_field.numeric_range().is_some() {
+            self.step_numeric_value(self.selected_field, false);
+            return;
+        }
+        let config = &mut self.config;
         match self.selected_field {
             SettingsField::Font => {
                 if !self.available_fonts.is_empty() {
@@ -242,26 +519,53 @@ impl SettingsDialog {
                     } else {
                         self.font_index - 1
                     };
+                    config.font_name = self.available_fonts[self.font_index].clone();
                 }
             }
             SettingsField::Color => {
-                self.color_theme = self.color_theme.prev();
+                config.color_theme = config.color_theme.prev();
+            }
+            SettingsField::Display => {
+                config.display_mode = config.display_mode.prev();
             }
             SettingsField::TimeFormat => {
-                self.time_format = self.time_format.toggle();
+                config.time_format = config.time_format.toggle();
+            }
+            SettingsField::ShowAmPm => {
+                config.show_ampm = !config.show_ampm;
             }
             SettingsField::Animation => {
-                self.animation_style = self.animation_style.prev();
+                config.animation_style = config.animation_style.prev();
             }
             SettingsField::Speed => {
-                self.animation_speed = self.animation_speed.prev();
+                config.animation_speed = config.animation_speed.prev();
+            }
+            SettingsField::Reactive => {
+                config.reactive_granularity = config.reactive_granularity.prev();
             }
             SettingsField::Background => {
-                self.background_style = self.background_style.prev();
+                config.background_style = config.background_style.prev();
+            }
+            SettingsField::Blink => {
+                config.blink_mode = config.blink_mode.prev();
+            }
+            SettingsField::Progress => {
+                if !config.show_progress {
+                    config.show_progress = true;
+                    config.progress_scope = ProgressScope::Day;
+                } else if config.progress_scope == ProgressScope::Minute {
+                    config.show_progress = false;
+                } else {
+                    config.progress_scope = config.progress_scope.prev();
+                }
             }
-            SettingsField::ColonBlink => {
-                self.colon_blink = !self.colon_blink;
+            SettingsField::Footer => {
+                config.show_footer = !config.show_footer;
             }
+            // Stepped above
+            SettingsField::Fps
+            | SettingsField::BackgroundBrightness
+            | SettingsField::StarDensity => {}
         }
     }
 
@@ -273,15 +577,83 @@ impl SettingsDialog {
             .unwrap_or("Standard")
     }
 
+    /// Get the display value for a field.
+    fn field_value(&self, field: SettingsField) -> String {
+        let config = &self.config;
+        match field {
+            SettingsField::Font => self.selected_font().to_string(),
+            SettingsField::Color => config.color_theme.display_name().to_string(),
+            SettingsField::Display => config.display_mode.display_name().to_string(),
+            SettingsField::TimeFormat => match config.time_format {
+                TimeFormat::TwentyFourHour => "24-hour".to_string(),
+                TimeFormat::TwelveHour => "12-hour".to_string(),
+            },
+            SettingsField::ShowAmPm => on_off(config.show_ampm),
+            SettingsField::Animation => config.animation_style.display_name().to_string(),
+            SettingsField::Speed => config.animation_speed.display_name().to_string(),
+            SettingsField::Reactive => config.reactive_granularity.display_name().to_string(),
+            SettingsField::Background => config.background_style.display_name().to_string(),
+            SettingsField::Blink => config.blink_mode.display_name().to_string(),
+            SettingsField::Progress if config.show_progress => {
+                config.progress_scope.display_name().to_string()
+            }
+            SettingsField::Progress => on_off(false),
+            SettingsField::Footer => on_off(config.show_footer),
+            SettingsField::Fps
+            | SettingsField::BackgroundBrightness
+            | SettingsField::StarDensity => self.numeric_field_value(field),
+        }
+    }
+
+    /// Get the display value for a numeric field, as typed while entering a value.
+    fn numeric_field_value(&self, field: SettingsField) -> String {
+        match (&self.entry, field.numeric_range()) {
+            (Some(entry), _) if field == self.selected_field => format!("{}_", entry.text),
+            (_, Some(range)) => format!("{:.*}", range.decimals, self.numeric_value(field)),
+            (_, None) => String::new(),
+        }
+    }
+
+    /// Check whether a field currently has any effect.
+    fn field_enabled(&self, field: SettingsField) -> bool {
+        match field {
+            // The analog face doesn't use a font
+            SettingsField::Font => self.config.display_mode != DisplayMode::Analog,
+            // AM/PM only applies to 12-hour format
+            SettingsField::ShowAmPm => self.config.time_format == TimeFormat::TwelveHour,
+            // Speed is grayed out when Animation is None
+            SettingsField::Speed => self.config.animation_style != AnimationStyle::None,
+            // Flash granularity only matters to the reactive animation
+            SettingsField::Reactive => self.config.animation_style == AnimationStyle::Reactive,
+            SettingsField::BackgroundBrightness => {
+                self.config.background_style != BackgroundStyle::None
+            }
+            // Star density only applies to the starfield background
+            SettingsField::StarDensity => {
+                self.config.background_style == BackgroundStyle::Starfield
+            }
+            _ => true,
+        }
+    }
+
     /// Render the settings dialog.
     pub fn render(&self, frame: &mut Frame, area: Rect, accent_color: Color) {
         if !self.visible {
             return;
         }
 
+        // Each field takes a line plus a spacing line, with the tab bar, padding, help and
+        // borders around. The dialog fits the largest tab so it keeps its size when switching.
+        // When a tab's fields don't all fit, only a window around the selection is shown.
+        let field_count = ALL_SETTINGS_GROUPS
+            .iter()
+            .map(|group| group.fields().count())
+            .max()
+            .unwrap_or(0) as u16;
+
         // Calculate centered dialog area
-        let dialog_width = 40.min(area.width.saturating_sub(4));
-        let dialog_height = 19.min(area.height.saturating_sub(2));
+        let dialog_width = 48.min(area.width.saturating_sub(4));
+        let dialog_height = (field_count * 2 + 6).min(area.height.saturating_sub(2));
 
         let dialog_x = area.x + (area.width.saturating_sub(dialog_width)) / 2;
         let dialog_y = area.y + (area.height.saturating_sub(dialog_height)) / 2;
@@ -301,121 +673,80 @@ impl SettingsDialog {
         let inner_area = block.inner(dialog_area);
         frame.render_widget(block, dialog_area);
 
-        // Layout for settings fields
-        let chunks = Layout::vertical([
-            Constraint::Length(1), // 0: Top padding
-            Constraint::Length(1), // 1: Font
-            Constraint::Length(1), // 2: Spacing
-            Constraint::Length(1), // 3: Color
-            Constraint::Length(1), // 4: Spacing
-            Constraint::Length(1), // 5: Time Format
-            Constraint::Length(1), // 6: Spacing
-            Constraint::Length(1), // 7: Animation
-            Constraint::Length(1), // 8: Spacing
-            Constraint::Length(1), // 9: Speed
-            Constraint::Length(1), // 10: Spacing
-            Constraint::Length(1), // 11: Background
-            Constraint::Length(1), // 12: Spacing
-            Constraint::Length(1), // 13: Colon Blink
-            Constraint::Fill(1),   // 14: Bottom space
-            Constraint::Length(1), // 15: Help text
-        ])
-        .split(inner_area);
-
-        // Render font field
-        let font_line = self.render_field(
-            "Font",
-            self.selected_font(),
-            self.selected_field == SettingsField::Font,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(font_line).alignment(Alignment::Center),
-            chunks[1],
-        );
-
-        // Render color field
-        let color_line = self.render_field(
-            "Color",
-            self.color_theme.display_name(),
-            self.selected_field == SettingsField::Color,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(color_line).alignment(Alignment::Center),
-            chunks[3],
-        );
-
-        // Render time format field
-        let time_format_name = match self.time_format {
-            TimeFormat::TwentyFourHour => "24-hour",
-            TimeFormat::TwelveHour => "12-hour",
-        };
-        let time_line = self.render_field(
-            "Format",
-            time_format_name,
-            self.selected_field == SettingsField::TimeFormat,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(time_line).alignment(Alignment::Center),
-            chunks[5],
-        );
-
-        // Render animation field
-        let animation_line = self.render_field(
-            "Animation",
-            self.animation_style.display_name(),
-            self.selected_field == SettingsField::Animation,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(animation_line).alignment(Alignment::Center),
-            chunks[7],
-        );
-
-        // Render speed field (grayed out when Animation is None)
-        let speed_line = self.render_field_with_style(
-            "Speed",
-            self.animation_speed.display_name(),
-            self.selected_field == SettingsField::Speed,
-            accent_color,
-            self.animation_style != AnimationStyle::None,
-        );
+        // Fields fit between the tab bar and top padding rows and the bottom space and help rows
+        let visible = (usize::from(inner_area.height.saturating_sub(4)) / 2).max(1);
+        self.visible_fields.set(visible);
+        let group = self.selected_field.group();
+        let fields: Vec<SettingsField> = group.fields().collect();
+        let first = self.first_visible_field(visible);
+        let shown = &fields[first..(first + visible).min(fields.len())];
+
+        // Layout: tab bar, top padding, then a field line and a spacing line per shown field,
+        // then bottom space and help text
+        let [tabs_area, inner_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner_area);
+        let mut tabs = Vec::new();
+        for (idx, &tab) in ALL_SETTINGS_GROUPS.iter().enumerate() {
+            if idx > 0 {
+                tabs.push(Span::styled(" │ ", Style::default().dark_gray()));
+            }
+            let style = if tab == group {
+                Style::default().fg(accent_color).bold()
+            } else {
+                Style::default().dark_gray()
+            };
+            tabs.push(Span::styled(tab.label(), style));
+        }
         frame.render_widget(
-            Paragraph::new(speed_line).alignment(Alignment::Center),
-            chunks[9],
+            Paragraph::new(Line::from(tabs)).alignment(Alignment::Center),
+            tabs_area,
         );
 
-        // Render background field
-        let background_line = self.render_field(
-            "Background",
-            self.background_style.display_name(),
-            self.selected_field == SettingsField::Background,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(background_line).alignment(Alignment::Center),
-            chunks[11],
-        );
+        let mut constraints = vec![Constraint::Length(1)];
+        for _ in shown {
+            constraints.push(Constraint::Length(1));
+            constraints.push(Constraint::Length(1));
+        }
+        constraints.push(Constraint::Fill(1));
+        constraints.push(Constraint::Length(1));
+        let chunks = Layout::vertical(constraints).split(inner_area);
+
+        // Arrows in the padding rows show there are more fields to scroll to
+        let indicator_style = Style::default().fg(accent_color);
+        if first > 0 {
+            frame.render_widget(
+                Paragraph::new(Span::styled("▲", indicator_style)).alignment(Alignment::Center),
+                chunks[0],
+            );
+        }
+        if first + shown.len() < fields.len() {
+            frame.render_widget(
+                Paragraph::new(Span::styled("▼", indicator_style)).alignment(Alignment::Center),
+                chunks[shown.len() * 2],
+            );
+        }
 
-        // Render colon blink field
-        let blink_value = if self.colon_blink { "On" } else { "Off" };
-        let blink_line = self.render_field(
-            "Colon Blink",
-            blink_value,
-            self.selected_field == SettingsField::ColonBlink,
-            accent_color,
-        );
-        frame.render_widget(
-            Paragraph::new(blink_line).alignment(Alignment::Center),
-            chunks[13],
-        );
+        // Render each shown field
+        for (idx, &field) in shown.iter().enumerate() {
+            let line = self.render_field_with_style(
+                field.label(),
+                &self.field_value(field),
+                self.selected_field == field,
+                accent_color,
+                self.field_enabled(field),
+            );
+            frame.render_widget(
+                Paragraph::new(line).alignment(Alignment::Center),
+                chunks[1 + idx * 2],
+            );
+        }
 
         // Render help text
         let help = Line::from(vec![
             Span::styled("↑↓", Style::default().fg(accent_color).bold()),
             Span::styled(" nav  ", Style::default().dark_gray()),
+            Span::styled("Tab", Style::default().fg(accent_color).bold()),
+            Span::styled(" tab  ", Style::default().dark_gray()),
             Span::styled("←→", Style::default().fg(accent_color).bold()),
             Span::styled(" change  ", Style::default().dark_gray()),
             Span::styled("Enter", Style::default().fg(accent_color).bold()),
@@ -425,7 +756,7 @@ impl SettingsDialog {
         ]);
         frame.render_widget(
             Paragraph::new(help).alignment(Alignment::Center),
-            chunks[15],
+            chunks[chunks.len() - 1],
         );
     }
 
@@ -482,3 +813,136 @@ impl SettingsDialog {
         self.render_field(label, value, selected, accent_color)
     }
 }
+
+/// Get display value for a toggle.
+fn on_off(value: bool) -> String {
+    if value { "On" } else { "Off" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+
+    /// Render the dialog into a terminal of the given height and get its text.
+    fn render_text(dialog: &SettingsDialog, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(44, height)).unwrap();
+        terminal
+            .draw(|frame| dialog.render(frame, frame.area(), Color::Cyan))
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content.iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn test_selecting_last_field_scrolls_into_view() {
+        let mut dialog = SettingsDialog::new(vec!["Standard".to_string()]);
+        dialog.open(&Config::default());
+
+        let text = render_text(&dialog, 16);
+        assert!(text.contains("Font:"));
+        assert!(!text.contains("FPS:"));
+        assert!(text.contains('▼'));
+
+        // FPS is the last field on the Display tab
+        while dialog.selected_field != SettingsField::Fps {
+            dialog.next_field();
+        }
+        let text = render_text(&dialog, 16);
+        assert!(text.contains("FPS:"));
+        assert!(!text.contains("Font:"));
+        assert!(text.contains('▲'));
+        assert!(!text.contains('▼'));
+
+        // Everything fits on a tall terminal
+        let text = render_text(&dialog, 60);
+        assert!(text.contains("Font:") && text.contains("FPS:"));
+        assert!(!text.contains('▲') && !text.contains('▼'));
+    }
+
+    #[test]
+    fn test_numeric_fields_step_and_take_typed_values() {
+        let mut dialog = SettingsDialog::new(vec!["Standard".to_string()]);
+        dialog.open(&Config::default());
+        dialog.select(SettingsField::BackgroundBrightness);
+        dialog.config.background_brightness = 0.5;
+
+        dialog.next_value();
+        assert_eq!(dialog.config.background_brightness, 0.55);
+        assert_eq!(
+            dialog.field_value(SettingsField::BackgroundBrightness),
+            "0.55"
+        );
+
+        // Typed values apply as they parse and are clamped
+        assert!(dialog.type_char('.'));
+        assert!(dialog.type_char('2'));
+        assert_eq!(dialog.config.background_brightness, 0.2);
+        assert_eq!(
+            dialog.field_value(SettingsField::BackgroundBrightness),
+            ".2_"
+        );
+        dialog.backspace();
+        dialog.type_char('7');
+        dialog.type_char('5');
+        assert_eq!(dialog.config.background_brightness, 0.75);
+        dialog.finish_entry();
+        dialog.type_char('9');
+        assert_eq!(dialog.config.background_brightness, 1.0);
+
+        // Cancelling restores the value from before typing
+        dialog.cancel_entry();
+        assert_eq!(dialog.config.background_brightness, 0.75);
+
+        // Fps steps by one within its limits, and other fields don't take digits
+        dialog.select(SettingsField::Fps);
+        dialog.config.fps = 120;
+        dialog.next_value();
+        assert_eq!(dialog.config.fps, 120);
+        assert!(!dialog.type_char('.'));
+        dialog.select(SettingsField::Color);
+        assert!(!dialog.type_char('1'));
+    }
+
+    #[test]
+    fn test_reopens_on_last_saved_field() {
+        let mut dialog = SettingsDialog::new(vec!["Standard".to_string()]);
+        dialog.open(&Config::default());
+        assert_eq!(dialog.selected_field, SettingsField::Font);
+
+        dialog.select(SettingsField::Speed);
+        dialog.remember_field();
+        dialog.close();
+        dialog.open(&Config::default());
+        assert_eq!(dialog.selected_field, SettingsField::Speed);
+
+        // Cancelling keeps the field from the last save
+        dialog.select(SettingsField::Fps);
+        dialog.close();
+        dialog.open(&Config::default());
+        assert_eq!(dialog.selected_field, SettingsField::Speed);
+    }
+
+    #[test]
+    fn test_tabs_show_only_their_fields() {
+        let mut dialog = SettingsDialog::new(vec!["Standard".to_string()]);
+        dialog.open(&Config::default());
+
+        dialog.next_group();
+        assert_eq!(dialog.selected_field, SettingsField::Color);
+        let text = render_text(&dialog, 40);
+        assert!(text.contains("Color:"));
+        assert!(!text.contains("Font:"));
+
+        // Moving past a tab's last field continues on the next tab
+        dialog.next_field();
+        assert_eq!(dialog.selected_field.group(), SettingsGroup::Animation);
+
+        // Switching back wraps around to the last tab
+        dialog.prev_group();
+        dialog.prev_group();
+        dialog.prev_group();
+        assert_eq!(dialog.selected_field, SettingsField::Background);
+    }
+}
@@ -0,0 +1,31 @@
+//! Machine-readable snapshot of the clock for scripts and status bars.
+
+use serde::Serialize;
+use sigye_core::{ColorTheme, TimeFormat};
+
+/// What the clock currently shows. Field names and enum names are kept stable
+/// so scripts can rely on them.
+#[derive(Debug, Serialize)]
+pub struct AppState {
+    /// Time as displayed, e.g. `"14:05:09"`.
+    pub time: String,
+    /// Date line as displayed.
+    pub date: String,
+    /// Seconds since the Unix epoch.
+    pub unix_time: i64,
+    /// Configured color theme.
+    pub theme: ColorTheme,
+    /// Font used for the big clock.
+    pub font: String,
+    /// Configured time format.
+    pub time_format: TimeFormat,
+    /// The time rendered in the big font, one string per row.
+    pub lines: Vec<String>,
+}
+
+impl AppState {
+    /// Serialize the state as a single line of JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("state serializes to JSON")
+    }
+}
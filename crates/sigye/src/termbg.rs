@@ -0,0 +1,98 @@
+//! Terminal background color detection.
+//!
+//! Terminals report their background color in reply to an OSC 11 query
+//! (`ESC ] 11 ; ? ST`) as `ESC ] 11 ; rgb:RRRR/GGGG/BBBB ST`. Terminals that don't
+//! support it stay silent, so the reply is only waited for briefly.
+
+/// How long to wait for the terminal to answer.
+#[cfg(unix)]
+const QUERY_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// Ask the terminal for its background color. Needs raw mode so the reply isn't echoed.
+/// Returns `None` if there is no terminal or it doesn't answer in time.
+#[cfg(unix)]
+pub fn query_background() -> Option<(u8, u8, u8)> {
+    use std::fs::OpenOptions;
+    use std::io::{Read, Write};
+    use std::os::fd::AsRawFd;
+    use std::time::Instant;
+
+    let mut tty = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    tty.write_all(b"\x1b]11;?\x1b\\").ok()?;
+    tty.flush().ok()?;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut reply = Vec::new();
+    let mut chunk = [0u8; 64];
+    // Read until the reply's terminator (BEL or ST), without blocking past the deadline
+    while !reply.ends_with(b"\x07") && !reply.ends_with(b"\x1b\\") {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return None;
+        }
+        let mut poll_fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `poll_fd` is a valid pollfd for an open descriptor, and the count is 1
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, remaining.as_millis() as libc::c_int) };
+        if ready <= 0 {
+            return None;
+        }
+        let read = tty.read(&mut chunk).ok()?;
+        if read == 0 {
+            return None;
+        }
+        reply.extend_from_slice(&chunk[..read]);
+    }
+
+    parse_reply(&String::from_utf8_lossy(&reply))
+}
+
+/// Background detection isn't supported here.
+#[cfg(not(unix))]
+pub fn query_background() -> Option<(u8, u8, u8)> {
+    None
+}
+
+/// Parse an OSC 11 reply into an RGB color.
+/// Each channel has 1 to 4 hex digits, scaled to 8 bits.
+fn parse_reply(reply: &str) -> Option<(u8, u8, u8)> {
+    let spec = reply.split("rgb:").nth(1)?;
+    let spec = spec.trim_end_matches(['\x07', '\\', '\x1b']);
+    let mut channels = spec.split('/').map(|hex| {
+        if hex.is_empty() || hex.len() > 4 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let max = (1u32 << (4 * hex.len())) - 1;
+        Some((value * 255 / max) as u8)
+    });
+    let rgb = (channels.next()??, channels.next()??, channels.next()??);
+    channels.next().is_none().then_some(rgb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reply() {
+        assert_eq!(
+            parse_reply("\x1b]11;rgb:ffff/ffff/ffff\x1b\\"),
+            Some((255, 255, 255))
+        );
+        assert_eq!(
+            parse_reply("\x1b]11;rgb:28/2a/36\x07"),
+            Some((0x28, 0x2a, 0x36))
+        );
+        assert_eq!(parse_reply("\x1b]11;rgb:8/8/8\x07"), Some((136, 136, 136)));
+        assert_eq!(parse_reply("\x1b]11;?\x07"), None);
+        assert_eq!(parse_reply("\x1b]11;rgb:ff/ff\x07"), None);
+    }
+}
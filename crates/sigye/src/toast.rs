@@ -0,0 +1,97 @@
+//! Short messages shown in a corner of the screen that go away by themselves.
+
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::{Color, Stylize},
+    text::Line,
+    widgets::Clear,
+};
+use sigye_core::dim;
+
+/// Part of a toast's time spent fading out at the end.
+const FADE_FRACTION: f32 = 0.3;
+
+/// A message shown until its time runs out.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    message: String,
+    shown_at: Instant,
+    duration: Duration,
+}
+
+impl Toast {
+    /// Create a toast shown from `shown_at` for `duration`.
+    pub fn new(message: impl Into<String>, shown_at: Instant, duration: Duration) -> Self {
+        Self {
+            message: message.into(),
+            shown_at,
+            duration,
+        }
+    }
+
+    /// Get the message text.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get when the toast goes away.
+    pub fn expires_at(&self) -> Instant {
+        self.shown_at + self.duration
+    }
+
+    /// Check if the toast's time has run out.
+    pub fn is_expired(&self, now: Instant) -> bool {
+        now >= self.expires_at()
+    }
+
+    /// Get how visible the toast is (0.0 to 1.0), fading out over the end of its time.
+    pub fn opacity(&self, now: Instant) -> f32 {
+        let fade = self.duration.mul_f32(FADE_FRACTION);
+        let left = self.expires_at().saturating_duration_since(now);
+        if fade.is_zero() || left >= fade {
+            if left.is_zero() { 0.0 } else { 1.0 }
+        } else {
+            left.as_secs_f32() / fade.as_secs_f32()
+        }
+    }
+
+    /// Render the toast in the top right corner of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect, color: Color, now: Instant) {
+        let opacity = self.opacity(now);
+        if opacity <= 0.0 || area.is_empty() {
+            return;
+        }
+
+        let width = (self.message().chars().count() as u16 + 2).min(area.width);
+        let toast_area = Rect::new(area.right() - width, area.y, width, 1);
+        frame.render_widget(Clear, toast_area);
+        frame.render_widget(
+            Line::from(self.message())
+                .bold()
+                .fg(dim(color, opacity))
+                .centered(),
+            toast_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toast_fades_then_expires() {
+        let start = Instant::now();
+        let toast = Toast::new("Hi", start, Duration::from_secs(1));
+        assert_eq!(toast.opacity(start), 1.0);
+        assert_eq!(toast.opacity(start + Duration::from_millis(700)), 1.0);
+        let fading = toast.opacity(start + Duration::from_millis(850));
+        assert!((fading - 0.5).abs() < 0.01);
+        assert!(!toast.is_expired(start + Duration::from_millis(999)));
+        assert!(toast.is_expired(start + Duration::from_secs(1)));
+        assert_eq!(toast.opacity(start + Duration::from_secs(2)), 0.0);
+    }
+}
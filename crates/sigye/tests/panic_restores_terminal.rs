@@ -0,0 +1,44 @@
+//! A panic inside the terminal UI must leave raw mode and the alternate screen.
+
+#![cfg(target_os = "linux")]
+
+use std::process::{Command, Stdio};
+
+/// Alternate screen escape sequences written by crossterm.
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+#[test]
+fn test_panic_restores_terminal() {
+    // `script` gives the clock a real terminal, and `stty` then reports its mode
+    let sigye = env!("CARGO_BIN_EXE_sigye");
+    let Ok(output) = Command::new("script")
+        .args([
+            "-qec",
+            &format!("'{sigye}' --panic-test; stty -a"),
+            "/dev/null",
+        ])
+        .env("RUST_BACKTRACE", "0")
+        .stdin(Stdio::null())
+        .output()
+    else {
+        eprintln!("skipping: `script` isn't available");
+        return;
+    };
+    let out = String::from_utf8_lossy(&output.stdout);
+
+    let entered = out
+        .find(ENTER_ALTERNATE_SCREEN)
+        .expect("never entered the UI");
+    let left = out
+        .rfind(LEAVE_ALTERNATE_SCREEN)
+        .expect("alternate screen left on");
+    let report = out.find("panic test").expect("panic report missing");
+    assert!(entered < left && left < report, "{out:?}");
+
+    let modes: Vec<&str> = out[report..].split_whitespace().collect();
+    assert!(
+        modes.contains(&"icanon") && modes.contains(&"echo"),
+        "{out:?}"
+    );
+}